@@ -0,0 +1,46 @@
+use rand::{Rand, SeedableRng, XorShiftRng};
+
+use pairing::bls12_381::*;
+use pairing::fft::EvaluationDomain;
+
+#[bench]
+fn bench_fr_fft_2_16(b: &mut ::test::Bencher) {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let domain = EvaluationDomain::<Fr>::new(1 << 16).unwrap();
+    let mut v: Vec<Fr> = (0..domain.size()).map(|_| Fr::rand(&mut rng)).collect();
+
+    b.iter(|| domain.fft(&mut v));
+}
+
+#[bench]
+fn bench_fr_fft_2_16_parallel(b: &mut ::test::Bencher) {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let domain = EvaluationDomain::<Fr>::new(1 << 16)
+        .unwrap()
+        .with_threads(4);
+    let mut v: Vec<Fr> = (0..domain.size()).map(|_| Fr::rand(&mut rng)).collect();
+
+    b.iter(|| domain.fft(&mut v));
+}
+
+#[bench]
+fn bench_fr_coset_ifft_2_16(b: &mut ::test::Bencher) {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let domain = EvaluationDomain::<Fr>::new(1 << 16).unwrap();
+    let mut v: Vec<Fr> = (0..domain.size()).map(|_| Fr::rand(&mut rng)).collect();
+
+    b.iter(|| domain.coset_ifft(&mut v));
+}
+
+#[bench]
+fn bench_g1_lagrange_basis_2_6(b: &mut ::test::Bencher) {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let domain = EvaluationDomain::<Fr>::new(1 << 6).unwrap();
+    let v: Vec<G1> = (0..domain.size()).map(|_| G1::rand(&mut rng)).collect();
+
+    b.iter(|| domain.lagrange_basis(&v));
+}
@@ -1,7 +1,7 @@
 use rand::{Rand, SeedableRng, XorShiftRng};
 
 use pairing::bls12_381::*;
-use pairing::Field;
+use pairing::{Engine, Field};
 
 #[bench]
 fn bench_fq12_add_assign(b: &mut ::test::Bencher) {
@@ -60,6 +60,32 @@ fn bench_fq12_mul_assign(b: &mut ::test::Bencher) {
     });
 }
 
+#[bench]
+fn bench_fq12_mul_by_014(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 1000;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<(Fq12, Fq2, Fq2, Fq2)> = (0..SAMPLES)
+        .map(|_| {
+            (
+                Fq12::rand(&mut rng),
+                Fq2::rand(&mut rng),
+                Fq2::rand(&mut rng),
+                Fq2::rand(&mut rng),
+            )
+        })
+        .collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        let mut tmp = v[count].0;
+        tmp.mul_by_014(&v[count].1, &v[count].2, &v[count].3);
+        count = (count + 1) % SAMPLES;
+        tmp
+    });
+}
+
 #[bench]
 fn bench_fq12_squaring(b: &mut ::test::Bencher) {
     const SAMPLES: usize = 1000;
@@ -92,3 +118,37 @@ fn bench_fq12_inverse(b: &mut ::test::Bencher) {
         tmp
     });
 }
+
+#[bench]
+fn bench_fq12_batch_invert(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 1000;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<Fq12> = (0..SAMPLES).map(|_| Fq12::rand(&mut rng)).collect();
+
+    b.iter(|| {
+        let mut tmp = v.clone();
+        Fq12::batch_invert(&mut tmp);
+        tmp
+    });
+}
+
+#[bench]
+fn bench_fq12_cyclotomic_squaring(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 1000;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<Fq12> = (0..SAMPLES)
+        .map(|_| Bls12::final_exponentiation(&Fq12::rand(&mut rng)).unwrap())
+        .collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        let mut tmp = v[count];
+        tmp.cyclotomic_square();
+        count = (count + 1) % SAMPLES;
+        tmp
+    });
+}
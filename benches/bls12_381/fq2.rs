@@ -93,6 +93,21 @@ fn bench_fq2_inverse(b: &mut ::test::Bencher) {
     });
 }
 
+#[bench]
+fn bench_fq2_batch_invert(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 1000;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<Fq2> = (0..SAMPLES).map(|_| Fq2::rand(&mut rng)).collect();
+
+    b.iter(|| {
+        let mut tmp = v.clone();
+        Fq2::batch_invert(&mut tmp);
+        tmp
+    });
+}
+
 #[bench]
 fn bench_fq2_sqrt(b: &mut ::test::Bencher) {
     const SAMPLES: usize = 1000;
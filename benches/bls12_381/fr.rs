@@ -197,6 +197,21 @@ fn bench_fr_inverse(b: &mut ::test::Bencher) {
     });
 }
 
+#[bench]
+fn bench_fr_batch_invert(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 1000;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<Fr> = (0..SAMPLES).map(|_| Fr::rand(&mut rng)).collect();
+
+    b.iter(|| {
+        let mut tmp = v.clone();
+        Fr::batch_invert(&mut tmp);
+        tmp
+    });
+}
+
 #[bench]
 fn bench_fr_negate(b: &mut ::test::Bencher) {
     const SAMPLES: usize = 1000;
@@ -214,6 +229,21 @@ fn bench_fr_negate(b: &mut ::test::Bencher) {
     });
 }
 
+#[bench]
+fn bench_fr_legendre(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 1000;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<Fr> = (0..SAMPLES).map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        count = (count + 1) % SAMPLES;
+        v[count].legendre()
+    });
+}
+
 #[bench]
 fn bench_fr_sqrt(b: &mut ::test::Bencher) {
     const SAMPLES: usize = 1000;
@@ -0,0 +1,83 @@
+use rand::{Rand, SeedableRng, XorShiftRng};
+
+use pairing::bls12_381::*;
+use pairing::{multiexp, FixedBaseTable, PrimeField, TargetGroup};
+
+#[bench]
+fn bench_gt_pow(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 100;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<(Gt, Fr)> = (0..SAMPLES)
+        .map(|_| (Gt::rand(&mut rng), Fr::rand(&mut rng)))
+        .collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        let tmp = v[count].0.pow(v[count].1);
+        count = (count + 1) % SAMPLES;
+        tmp
+    });
+}
+
+#[bench]
+fn bench_gt_pow_cyclotomic_exp(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 100;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<(Fq12, FrRepr)> = (0..SAMPLES)
+        .map(|_| (Gt::rand(&mut rng).into(), Fr::rand(&mut rng).into_repr()))
+        .collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        let tmp = v[count].0.cyclotomic_exp(v[count].1);
+        count = (count + 1) % SAMPLES;
+        tmp
+    });
+}
+
+#[bench]
+fn bench_gt_fixed_base_exp(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 1000;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let table = FixedBaseTable::new(Gt::generator(), 8);
+    let v: Vec<FrRepr> = (0..SAMPLES)
+        .map(|_| Fr::rand(&mut rng).into_repr())
+        .collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        let tmp = table.exp(v[count]);
+        count = (count + 1) % SAMPLES;
+        tmp
+    });
+}
+
+#[bench]
+fn bench_gt_multiexp(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 10;
+    const TERMS: usize = 10;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<(Vec<Gt>, Vec<FrRepr>)> = (0..SAMPLES)
+        .map(|_| {
+            (
+                (0..TERMS).map(|_| Gt::rand(&mut rng)).collect(),
+                (0..TERMS).map(|_| Fr::rand(&mut rng).into_repr()).collect(),
+            )
+        })
+        .collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        let tmp = multiexp(&v[count].0, &v[count].1);
+        count = (count + 1) % SAMPLES;
+        tmp
+    });
+}
@@ -1,8 +1,11 @@
 mod ec;
+mod fft;
 mod fq;
 mod fq12;
 mod fq2;
 mod fr;
+mod gt;
+mod poly;
 
 use rand::{Rand, SeedableRng, XorShiftRng};
 
@@ -41,6 +44,67 @@ fn bench_pairing_g2_preparation(b: &mut ::test::Bencher) {
     });
 }
 
+#[bench]
+fn bench_pairing_g2_batch_preparation(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 100;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<G2Affine> = (0..SAMPLES)
+        .map(|_| G2Affine::from(G2::rand(&mut rng)))
+        .collect();
+
+    b.iter(|| G2Prepared::batch_from_affine(&v));
+}
+
+#[bench]
+fn bench_pairing_g2_prepared_read(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 100;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<(G2Affine, Vec<u8>)> = (0..SAMPLES)
+        .map(|_| {
+            let q = G2Affine::from(G2::rand(&mut rng));
+            let mut encoded = vec![];
+            G2Prepared::from_affine_compact(q)
+                .write(&mut encoded)
+                .unwrap();
+            (q, encoded)
+        })
+        .collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        let tmp = G2Prepared::read(&v[count].1[..], &v[count].0).unwrap();
+        count = (count + 1) % SAMPLES;
+        tmp
+    });
+}
+
+#[bench]
+fn bench_pairing_miller_loop_compact(b: &mut ::test::Bencher) {
+    const SAMPLES: usize = 1000;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let v: Vec<(G1Prepared, G2Prepared)> = (0..SAMPLES)
+        .map(|_| {
+            (
+                G1Affine::from(G1::rand(&mut rng)).prepare(),
+                G2Prepared::from_affine_compact(G2Affine::from(G2::rand(&mut rng))),
+            )
+        })
+        .collect();
+
+    let mut count = 0;
+    b.iter(|| {
+        let tmp = Bls12::miller_loop(&[(&v[count].0, &v[count].1)]);
+        count = (count + 1) % SAMPLES;
+        tmp
+    });
+}
+
 #[bench]
 fn bench_pairing_miller_loop(b: &mut ::test::Bencher) {
     const SAMPLES: usize = 1000;
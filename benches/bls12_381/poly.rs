@@ -0,0 +1,43 @@
+use rand::{Rand, SeedableRng, XorShiftRng};
+
+use pairing::bls12_381::*;
+use pairing::poly::DensePolynomial;
+
+#[bench]
+fn bench_fr_poly_mul_2_12(b: &mut ::test::Bencher) {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let p =
+        DensePolynomial::from_coefficients((0..(1 << 12)).map(|_| Fr::rand(&mut rng)).collect());
+    let q =
+        DensePolynomial::from_coefficients((0..(1 << 12)).map(|_| Fr::rand(&mut rng)).collect());
+
+    b.iter(|| {
+        let mut tmp = p.clone();
+        tmp.mul_assign(&q);
+        tmp
+    });
+}
+
+#[bench]
+fn bench_fr_poly_div_rem_2_12(b: &mut ::test::Bencher) {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let p =
+        DensePolynomial::from_coefficients((0..(1 << 12)).map(|_| Fr::rand(&mut rng)).collect());
+    let q =
+        DensePolynomial::from_coefficients((0..(1 << 11)).map(|_| Fr::rand(&mut rng)).collect());
+
+    b.iter(|| p.div_rem(&q));
+}
+
+#[bench]
+fn bench_fr_poly_evaluate_many_2_10(b: &mut ::test::Bencher) {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let p =
+        DensePolynomial::from_coefficients((0..(1 << 10)).map(|_| Fr::rand(&mut rng)).collect());
+    let points: Vec<Fr> = (0..(1 << 10)).map(|_| Fr::rand(&mut rng)).collect();
+
+    b.iter(|| p.evaluate_many(&points));
+}
@@ -0,0 +1,46 @@
+//! Reports the field operations performed by a pairing, by the preparation
+//! of a G2 point and by a scalar multiplication in G1 and G2.
+//!
+//! Run with `cargo run --release --features op-counting --example op_counts`.
+
+extern crate pairing;
+extern crate rand;
+
+use pairing::bls12_381::counters;
+use pairing::bls12_381::*;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use rand::{Rand, SeedableRng, XorShiftRng};
+
+fn main() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let p = G1Affine::from(G1::rand(&mut rng));
+    let q = G2Affine::from(G2::rand(&mut rng));
+    let s = Fr::rand(&mut rng);
+
+    let (prepared, counts) = counters::count(|| G2Prepared::from_affine(q));
+    println!("G2Prepared::from_affine\n{}", counts);
+
+    let (f, counts) = counters::count(|| Bls12::miller_loop(&[(&p.prepare(), &prepared)]));
+    println!("Bls12::miller_loop (G1 preparation included)\n{}", counts);
+
+    let (_, counts) = counters::count(|| Bls12::final_exponentiation(&f));
+    println!("Bls12::final_exponentiation\n{}", counts);
+
+    let (_, counts) = counters::count(|| Bls12::pairing(p, q));
+    println!("Bls12::pairing\n{}", counts);
+
+    let (_, counts) = counters::count(|| {
+        let mut g = p.into_projective();
+        g.mul_assign(s);
+        g
+    });
+    println!("G1::mul_assign\n{}", counts);
+
+    let (_, counts) = counters::count(|| {
+        let mut g = q.into_projective();
+        g.mul_assign(s);
+        g
+    });
+    println!("G2::mul_assign\n{}", counts);
+}
@@ -0,0 +1,230 @@
+use super::{Bls12, Fq12, G1Affine, G1Prepared, G2Affine, G2Prepared};
+use std::borrow::Cow;
+use std::thread;
+use {CurveAffine, Engine, Field};
+
+/// Accumulates (G1, G2) pairs for a multi-miller loop, so that the pairs
+/// of a large verification can be gathered incrementally, evaluated in
+/// parts (possibly on different threads) and merged before a single
+/// final exponentiation.
+///
+/// The miller loop of a set of pairs is the product of the miller loops
+/// of any partition of it, so the result always equals that of
+/// `Bls12::miller_loop` over every pair added.
+#[derive(Clone, Debug)]
+pub struct MillerLoopAccumulator<'a> {
+    pairs: Vec<(Cow<'a, G1Prepared>, Cow<'a, G2Prepared>)>,
+    f: Fq12,
+}
+
+impl<'a> Default for MillerLoopAccumulator<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> MillerLoopAccumulator<'a> {
+    /// Creates an accumulator without any pairs.
+    pub fn new() -> Self {
+        MillerLoopAccumulator {
+            pairs: vec![],
+            f: Fq12::one(),
+        }
+    }
+
+    /// Adds a pair of borrowed prepared points.
+    pub fn add(&mut self, p: &'a G1Prepared, q: &'a G2Prepared) {
+        self.pairs.push((Cow::Borrowed(p), Cow::Borrowed(q)));
+    }
+
+    /// Adds a pair of owned prepared points.
+    pub fn add_owned(&mut self, p: G1Prepared, q: G2Prepared) {
+        self.pairs.push((Cow::Owned(p), Cow::Owned(q)));
+    }
+
+    /// Prepares and adds a pair of affine points.
+    pub fn add_affine(&mut self, p: &G1Affine, q: &G2Affine) {
+        self.add_owned(p.prepare(), q.prepare());
+    }
+
+    /// Returns the number of pairs which have not been evaluated yet.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Determines if every pair has been evaluated.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Moves the pairs from index `at` onwards, which have not been
+    /// evaluated yet, into a new accumulator.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        MillerLoopAccumulator {
+            pairs: self.pairs.split_off(at),
+            f: Fq12::one(),
+        }
+    }
+
+    /// Splits this accumulator into at most `n` accumulators with roughly
+    /// the same number of pending pairs, whose merged result equals the
+    /// result of this accumulator.
+    pub fn split(mut self, n: usize) -> Vec<Self> {
+        assert!(n > 0, "cannot split into zero accumulators");
+
+        let chunk = (self.pairs.len() + n - 1) / n;
+        let mut res = vec![];
+
+        while self.pairs.len() > chunk {
+            let at = self.pairs.len() - chunk;
+            res.push(self.split_off(at));
+        }
+        res.push(self);
+        res.reverse();
+
+        res
+    }
+
+    /// Merges the pairs and partial result of another accumulator into
+    /// this one.
+    pub fn merge(&mut self, other: Self) {
+        self.f.mul_assign(&other.f);
+        self.pairs.extend(other.pairs);
+    }
+
+    /// Performs the miller loop over the pairs which have not been
+    /// evaluated yet, folding the result into the partial result of this
+    /// accumulator.
+    pub fn evaluate(&mut self) {
+        if !self.pairs.is_empty() {
+            let f = {
+                let pairs: Vec<_> = self.pairs.iter().map(|(p, q)| (&**p, &**q)).collect();
+                Bls12::miller_loop(&pairs)
+            };
+
+            self.f.mul_assign(&f);
+            self.pairs.clear();
+        }
+    }
+
+    /// Performs the miller loop over the pending pairs, spread over `threads`
+    /// threads, or a single one if it is zero, folding the result into the
+    /// partial result of this accumulator.
+    pub fn evaluate_parallel(&mut self, threads: usize) {
+        let parts = ::std::mem::take(self).split(threads.max(1));
+
+        let parts: Vec<_> = thread::scope(|s| {
+            let handles: Vec<_> = parts
+                .into_iter()
+                .map(|mut part| {
+                    s.spawn(move || {
+                        part.evaluate();
+                        part
+                    })
+                })
+                .collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        for part in parts {
+            self.merge(part);
+        }
+    }
+
+    /// Returns the result of the miller loop over every pair added to
+    /// this accumulator.
+    pub fn miller_loop(mut self) -> Fq12 {
+        self.evaluate();
+        self.f
+    }
+
+    /// Performs final exponentiation of the result of the miller loop over
+    /// every pair added to this accumulator.
+    pub fn final_exponentiation(self) -> Option<Fq12> {
+        Bls12::final_exponentiation(&self.miller_loop())
+    }
+}
+
+#[test]
+fn test_miller_loop_accumulator() {
+    use super::{G1, G2};
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let pairs: Vec<(G1Prepared, G2Prepared)> = (0..10)
+        .map(|i| {
+            let p = if i == 3 {
+                G1Affine::zero()
+            } else {
+                G1Affine::from(G1::rand(&mut rng))
+            };
+            let q = if i == 7 {
+                G2Affine::zero()
+            } else {
+                G2Affine::from(G2::rand(&mut rng))
+            };
+
+            (p.prepare(), q.prepare())
+        })
+        .collect();
+    let refs: Vec<_> = pairs.iter().map(|(p, q)| (p, q)).collect();
+    let expected = Bls12::miller_loop(&refs);
+
+    assert_eq!(MillerLoopAccumulator::new().miller_loop(), Fq12::one());
+
+    let mut acc = MillerLoopAccumulator::new();
+    for (i, (p, q)) in pairs.iter().enumerate() {
+        if i % 2 == 0 {
+            acc.add(p, q);
+        } else {
+            acc.add_owned(p.clone(), q.clone());
+        }
+    }
+    assert_eq!(acc.len(), 10);
+    assert_eq!(acc.clone().miller_loop(), expected);
+    assert_eq!(
+        acc.clone().final_exponentiation(),
+        Bls12::final_exponentiation(&expected)
+    );
+
+    // Evaluate in parts and merge the partial results.
+    for n in 1..12 {
+        let mut parts = acc.clone().split(n);
+        assert!(parts.len() <= n);
+        assert_eq!(parts.iter().map(|p| p.len()).sum::<usize>(), 10);
+
+        let mut merged = parts.remove(0);
+        merged.evaluate();
+        for mut part in parts {
+            if n % 2 == 0 {
+                part.evaluate();
+            }
+            merged.merge(part);
+        }
+
+        assert_eq!(merged.miller_loop(), expected);
+    }
+
+    let mut head = acc.clone();
+    let mut tail = head.split_off(4);
+    tail.evaluate();
+    assert!(tail.is_empty());
+    head.merge(tail);
+    assert_eq!(head.miller_loop(), expected);
+
+    for threads in 0..4 {
+        let mut parallel = acc.clone();
+        parallel.evaluate_parallel(threads);
+        assert!(parallel.is_empty());
+        assert_eq!(parallel.miller_loop(), expected);
+    }
+
+    let mut from_affine = MillerLoopAccumulator::new();
+    from_affine.add_affine(&G1Affine::one(), &G2Affine::one());
+    assert_eq!(
+        from_affine.miller_loop(),
+        Bls12::miller_loop(&[(&G1Affine::one().prepare(), &G2Affine::one().prepare())])
+    );
+}
@@ -0,0 +1,374 @@
+//! Multiplication and Montgomery reduction for `Fq` and `Fr` in x86_64
+//! assembly, using the BMI2 `MULX` and ADX `ADCX`/`ADOX` instructions. The
+//! fields select these at runtime by `enabled()` when the `adx-support`
+//! feature is enabled, and fall back to the portable arithmetic otherwise.
+//!
+//! `MULX` leaves the flags alone, and `ADCX` and `ADOX` only use the carry
+//! and overflow flags respectively, so each row of products is accumulated
+//! in two interleaved carry chains: the low halves through one and the high
+//! halves through the other. A row works on a window of `N + 1` registers
+//! holding the limbs it can reach, and the window slides up one register
+//! per row, the register leaving the bottom being stored or known to be
+//! zero and then reused as the new top.
+//!
+//! The functions compute the same values as the portable arithmetic,
+//! including the unreduced result of the Montgomery reduction.
+
+use std::arch::asm;
+
+/// Returns whether the running processor supports the instructions used
+/// here. The detection is cached by the standard library.
+#[inline(always)]
+pub fn enabled() -> bool {
+    is_x86_feature_detected!("bmi2") && is_x86_feature_detected!("adx")
+}
+
+/// Adds `rdx * x[j]` to the window limbs `lo` and `hi`, carrying the low
+/// half through `OF` and the high half through `CF`.
+macro_rules! mul_term {
+    ($x:literal, $j:literal, $lo:literal, $hi:literal) => {
+        concat!(
+            "mulx r15, rax, qword ptr [{", $x, "} + 8*", $j, "]\n",
+            "adox ", $lo, ", rax\n",
+            "adcx ", $hi, ", r15\n",
+        )
+    };
+}
+
+/// Clears both carry flags, and `rax`, before a row.
+macro_rules! row_start {
+    () => {
+        "xor eax, eax\n"
+    };
+}
+
+/// Finishes a row whose window ends at `top`, adding the pending carry of
+/// the low chain. The high chain cannot carry out of the window.
+macro_rules! row_end {
+    ($top:literal) => {
+        concat!("mov eax, 0\n", "adox ", $top, ", rax\n")
+    };
+}
+
+/// Adds `k * modulus` to the window `w0..=w6`, where `k` makes `w0` zero,
+/// so that it becomes the next row's top. `inv` is the operand holding
+/// `-modulus^(-1) mod 2^64`.
+macro_rules! reduce_row_6 {
+    ($inv:literal, $w0:literal, $w1:literal, $w2:literal, $w3:literal, $w4:literal, $w5:literal, $w6:literal) => {
+        concat!(
+            "mov rdx, ", $inv, "\n",
+            "imul rdx, ", $w0, "\n",
+            row_start!(),
+            mul_term!("m", 0, $w0, $w1),
+            mul_term!("m", 1, $w1, $w2),
+            mul_term!("m", 2, $w2, $w3),
+            mul_term!("m", 3, $w3, $w4),
+            mul_term!("m", 4, $w4, $w5),
+            mul_term!("m", 5, $w5, $w6),
+            row_end!($w6),
+        )
+    };
+}
+
+/// As `reduce_row_6`, for four limbs in the window `w0..=w4`.
+macro_rules! reduce_row_4 {
+    ($inv:literal, $w0:literal, $w1:literal, $w2:literal, $w3:literal, $w4:literal) => {
+        concat!(
+            "mov rdx, ", $inv, "\n",
+            "imul rdx, ", $w0, "\n",
+            row_start!(),
+            mul_term!("m", 0, $w0, $w1),
+            mul_term!("m", 1, $w1, $w2),
+            mul_term!("m", 2, $w2, $w3),
+            mul_term!("m", 3, $w3, $w4),
+            row_end!($w4),
+        )
+    };
+}
+
+/// Adds `a * b[i]` to the window `w0..=w6` and then reduces it as in
+/// `reduce_row_6`, interleaving the product with the reduction.
+macro_rules! mont_row_6 {
+    ($i:literal, $inv:literal, $w0:literal, $w1:literal, $w2:literal, $w3:literal, $w4:literal, $w5:literal, $w6:literal) => {
+        concat!(
+            "mov rdx, qword ptr [{b} + 8*", $i, "]\n",
+            row_start!(),
+            mul_term!("a", 0, $w0, $w1),
+            mul_term!("a", 1, $w1, $w2),
+            mul_term!("a", 2, $w2, $w3),
+            mul_term!("a", 3, $w3, $w4),
+            mul_term!("a", 4, $w4, $w5),
+            mul_term!("a", 5, $w5, $w6),
+            row_end!($w6),
+            reduce_row_6!($inv, $w0, $w1, $w2, $w3, $w4, $w5, $w6),
+        )
+    };
+}
+
+/// As `mont_row_6`, for four limbs in the window `w0..=w4`.
+macro_rules! mont_row_4 {
+    ($i:literal, $inv:literal, $w0:literal, $w1:literal, $w2:literal, $w3:literal, $w4:literal) => {
+        concat!(
+            "mov rdx, qword ptr [{b} + 8*", $i, "]\n",
+            row_start!(),
+            mul_term!("a", 0, $w0, $w1),
+            mul_term!("a", 1, $w1, $w2),
+            mul_term!("a", 2, $w2, $w3),
+            mul_term!("a", 3, $w3, $w4),
+            row_end!($w4),
+            reduce_row_4!($inv, $w0, $w1, $w2, $w3, $w4),
+        )
+    };
+}
+
+/// Performs Montgomery reduction of `t`, which is below `modulus * 2^384`,
+/// writing `t / 2^384` modulo `modulus` to `res`. As in the portable
+/// reduction, the result may exceed `modulus` by one multiple of it. `inv`
+/// is `-modulus^(-1) mod 2^64`, and `modulus` must be below `2^383`.
+///
+/// The low half of `t` is reduced first, leaving a value of at most
+/// `modulus`, to which the high half is then added.
+///
+/// # Safety
+///
+/// The processor must support BMI2 and ADX, as reported by `enabled()`.
+#[inline(always)]
+pub unsafe fn mont_reduce_6(t: &[u64; 12], modulus: &[u64; 6], inv: u64, res: &mut [u64; 6]) {
+    let (r0, r1, r2, r3, r4, r5);
+    asm!(
+        "mov r8, qword ptr [{t}]",
+        "mov r9, qword ptr [{t} + 8]",
+        "mov r10, qword ptr [{t} + 16]",
+        "mov r11, qword ptr [{t} + 24]",
+        "mov r12, qword ptr [{t} + 32]",
+        "mov r13, qword ptr [{t} + 40]",
+        "xor r14, r14",
+        reduce_row_6!("{inv}", "r8", "r9", "r10", "r11", "r12", "r13", "r14"),
+        reduce_row_6!("{inv}", "r9", "r10", "r11", "r12", "r13", "r14", "r8"),
+        reduce_row_6!("{inv}", "r10", "r11", "r12", "r13", "r14", "r8", "r9"),
+        reduce_row_6!("{inv}", "r11", "r12", "r13", "r14", "r8", "r9", "r10"),
+        reduce_row_6!("{inv}", "r12", "r13", "r14", "r8", "r9", "r10", "r11"),
+        reduce_row_6!("{inv}", "r13", "r14", "r8", "r9", "r10", "r11", "r12"),
+        "add r14, qword ptr [{t} + 48]",
+        "adc r8, qword ptr [{t} + 56]",
+        "adc r9, qword ptr [{t} + 64]",
+        "adc r10, qword ptr [{t} + 72]",
+        "adc r11, qword ptr [{t} + 80]",
+        "adc r12, qword ptr [{t} + 88]",
+        t = in(reg) t.as_ptr(),
+        m = in(reg) modulus.as_ptr(),
+        inv = in(reg) inv,
+        out("rax") _, out("rdx") _,
+        out("r14") r0, out("r8") r1, out("r9") r2, out("r10") r3,
+        out("r11") r4, out("r12") r5, out("r13") _, out("r15") _,
+        options(nostack, readonly),
+    );
+    *res = [r0, r1, r2, r3, r4, r5];
+}
+
+/// Writes the Montgomery product `a * b / 2^384` to `res`, which may exceed
+/// `modulus` by one multiple of it. See `mont_reduce_6`.
+///
+/// # Safety
+///
+/// The processor must support BMI2 and ADX, as reported by `enabled()`.
+#[inline(always)]
+pub unsafe fn mont_mul_6(
+    a: &[u64; 6],
+    b: &[u64; 6],
+    modulus: &[u64; 6],
+    inv: u64,
+    res: &mut [u64; 6],
+) {
+    // Every general purpose register the operands may take is in use, so
+    // `inv` is read from memory, after the limbs of `b`.
+    let b = [b[0], b[1], b[2], b[3], b[4], b[5], inv];
+    let (r0, r1, r2, r3, r4, r5);
+    asm!(
+        "xor r8, r8",
+        "xor r9, r9",
+        "xor r10, r10",
+        "xor r11, r11",
+        "xor r12, r12",
+        "xor r13, r13",
+        "xor r14, r14",
+        mont_row_6!(0, "qword ptr [{b} + 48]", "r8", "r9", "r10", "r11", "r12", "r13", "r14"),
+        mont_row_6!(1, "qword ptr [{b} + 48]", "r9", "r10", "r11", "r12", "r13", "r14", "r8"),
+        mont_row_6!(2, "qword ptr [{b} + 48]", "r10", "r11", "r12", "r13", "r14", "r8", "r9"),
+        mont_row_6!(3, "qword ptr [{b} + 48]", "r11", "r12", "r13", "r14", "r8", "r9", "r10"),
+        mont_row_6!(4, "qword ptr [{b} + 48]", "r12", "r13", "r14", "r8", "r9", "r10", "r11"),
+        mont_row_6!(5, "qword ptr [{b} + 48]", "r13", "r14", "r8", "r9", "r10", "r11", "r12"),
+        a = in(reg) a.as_ptr(),
+        b = in(reg) b.as_ptr(),
+        m = in(reg) modulus.as_ptr(),
+        out("rax") _, out("rdx") _,
+        out("r14") r0, out("r8") r1, out("r9") r2, out("r10") r3,
+        out("r11") r4, out("r12") r5, out("r13") _, out("r15") _,
+        options(nostack, readonly),
+    );
+    *res = [r0, r1, r2, r3, r4, r5];
+}
+
+/// Performs Montgomery reduction of `t`, which is below `modulus * 2^256`,
+/// writing `t / 2^256` modulo `modulus` to `res`. See `mont_reduce_6`;
+/// here `modulus` must be below `2^255`.
+///
+/// # Safety
+///
+/// The processor must support BMI2 and ADX, as reported by `enabled()`.
+#[inline(always)]
+pub unsafe fn mont_reduce_4(t: &[u64; 8], modulus: &[u64; 4], inv: u64, res: &mut [u64; 4]) {
+    let (r0, r1, r2, r3);
+    asm!(
+        "mov r8, qword ptr [{t}]",
+        "mov r9, qword ptr [{t} + 8]",
+        "mov r10, qword ptr [{t} + 16]",
+        "mov r11, qword ptr [{t} + 24]",
+        "xor r12, r12",
+        reduce_row_4!("{inv}", "r8", "r9", "r10", "r11", "r12"),
+        reduce_row_4!("{inv}", "r9", "r10", "r11", "r12", "r8"),
+        reduce_row_4!("{inv}", "r10", "r11", "r12", "r8", "r9"),
+        reduce_row_4!("{inv}", "r11", "r12", "r8", "r9", "r10"),
+        "add r12, qword ptr [{t} + 32]",
+        "adc r8, qword ptr [{t} + 40]",
+        "adc r9, qword ptr [{t} + 48]",
+        "adc r10, qword ptr [{t} + 56]",
+        t = in(reg) t.as_ptr(),
+        m = in(reg) modulus.as_ptr(),
+        inv = in(reg) inv,
+        out("rax") _, out("rdx") _,
+        out("r12") r0, out("r8") r1, out("r9") r2, out("r10") r3,
+        out("r11") _, out("r15") _,
+        options(nostack, readonly),
+    );
+    *res = [r0, r1, r2, r3];
+}
+
+/// Writes the Montgomery product `a * b / 2^256` to `res`, which may exceed
+/// `modulus` by one multiple of it. See `mont_reduce_4`.
+///
+/// # Safety
+///
+/// The processor must support BMI2 and ADX, as reported by `enabled()`.
+#[inline(always)]
+pub unsafe fn mont_mul_4(
+    a: &[u64; 4],
+    b: &[u64; 4],
+    modulus: &[u64; 4],
+    inv: u64,
+    res: &mut [u64; 4],
+) {
+    let (r0, r1, r2, r3);
+    asm!(
+        "xor r8, r8",
+        "xor r9, r9",
+        "xor r10, r10",
+        "xor r11, r11",
+        "xor r12, r12",
+        mont_row_4!(0, "{inv}", "r8", "r9", "r10", "r11", "r12"),
+        mont_row_4!(1, "{inv}", "r9", "r10", "r11", "r12", "r8"),
+        mont_row_4!(2, "{inv}", "r10", "r11", "r12", "r8", "r9"),
+        mont_row_4!(3, "{inv}", "r11", "r12", "r8", "r9", "r10"),
+        a = in(reg) a.as_ptr(),
+        b = in(reg) b.as_ptr(),
+        m = in(reg) modulus.as_ptr(),
+        inv = in(reg) inv,
+        out("rax") _, out("rdx") _,
+        out("r12") r0, out("r8") r1, out("r9") r2, out("r10") r3,
+        out("r11") _, out("r15") _,
+        options(nostack, readonly),
+    );
+    *res = [r0, r1, r2, r3];
+}
+
+#[cfg(test)]
+use super::{fq, fr, Fq, Fr};
+#[cfg(test)]
+use rand::{Rand, Rng, SeedableRng, XorShiftRng};
+#[cfg(test)]
+use {PrimeField, PrimeFieldRepr};
+
+/// Returns random and edge-case values below the modulus of `F`, including
+/// values with runs of all-ones limbs that maximize the carries.
+#[cfg(test)]
+fn samples<F: PrimeField>() -> Vec<F::Repr> {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let modulus = F::char();
+    let mut max = modulus;
+    max.sub_noborrow(&F::Repr::from(1));
+
+    let mut samples = vec![F::Repr::from(0), F::Repr::from(1), max];
+    samples.extend((0..200).map(|_| F::rand(&mut rng).into_repr()));
+    samples.extend((0..200).map(|_| {
+        let mut r = F::Repr::rand(&mut rng);
+        for l in r.as_mut().iter_mut() {
+            if rng.gen() {
+                *l = !0;
+            }
+        }
+        r.div_rem(&modulus).unwrap().1
+    }));
+    samples
+}
+
+#[test]
+fn test_fq_adx() {
+    if !enabled() {
+        eprintln!("skipping test_fq_adx: the processor does not support BMI2 and ADX");
+        return;
+    }
+
+    let modulus = Fq::char();
+    let samples = samples::<Fq>();
+
+    for a in &samples {
+        for b in samples.iter().take(50) {
+            let c = fq::mul_portable(&a.0, &b.0);
+            let portable = fq::mont_reduce_portable(c);
+            let mut res = [0; 6];
+            unsafe { mont_reduce_6(&c, &modulus.0, fq::INV, &mut res) };
+            assert_eq!(res, portable);
+            unsafe { mont_mul_6(&a.0, &b.0, &modulus.0, fq::INV, &mut res) };
+            assert_eq!(res, portable);
+        }
+
+        // Unreduced inputs up to the largest the reduction accepts.
+        let c = fq::mul_portable(&a.0, &modulus.0);
+        let mut res = [0; 6];
+        unsafe { mont_reduce_6(&c, &modulus.0, fq::INV, &mut res) };
+        assert_eq!(res, fq::mont_reduce_portable(c));
+    }
+}
+
+#[test]
+fn test_fr_adx() {
+    if !enabled() {
+        eprintln!("skipping test_fr_adx: the processor does not support BMI2 and ADX");
+        return;
+    }
+
+    let modulus = Fr::char();
+    let samples = samples::<Fr>();
+
+    for a in &samples {
+        for b in samples.iter().take(50) {
+            let c = fr::mul_portable(&a.0, &b.0);
+            let portable = fr::mont_reduce_portable(c);
+            let mut res = [0; 4];
+            unsafe { mont_reduce_4(&c, &modulus.0, fr::INV, &mut res) };
+            assert_eq!(res, portable);
+            unsafe { mont_mul_4(&a.0, &b.0, &modulus.0, fr::INV, &mut res) };
+            assert_eq!(res, portable);
+        }
+
+        let c = fr::square_portable(&a.0);
+        let mut res = [0; 4];
+        unsafe { mont_mul_4(&a.0, &a.0, &modulus.0, fr::INV, &mut res) };
+        assert_eq!(res, fr::mont_reduce_portable(c));
+
+        let c = fr::mul_portable(&a.0, &modulus.0);
+        unsafe { mont_reduce_4(&c, &modulus.0, fr::INV, &mut res) };
+        assert_eq!(res, fr::mont_reduce_portable(c));
+    }
+}
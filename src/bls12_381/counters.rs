@@ -0,0 +1,206 @@
+//! Thread-local counters of field operations, for comparing algorithms by
+//! their exact operation counts rather than by their running time.
+//!
+//! Counting is only performed when the `op-counting` feature is enabled.
+//! Each field counts its own operations, so a multiplication in `Fq2` is
+//! counted once in `Fq2` and again as the `Fq` operations it is built from.
+//! Specialized operations, such as `Fq12::mul_by_014` or cyclotomic
+//! squaring, are only counted through the operations they are built from.
+
+use std::cell::Cell;
+use std::fmt;
+use std::ops::Sub;
+
+/// The number of operations performed in a single field.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldCounts {
+    /// Multiplications.
+    pub mul: u64,
+    /// Squarings.
+    pub square: u64,
+    /// Additions, subtractions and doublings.
+    pub add: u64,
+    /// Inversions.
+    pub inverse: u64,
+    /// Square roots.
+    pub sqrt: u64,
+}
+
+impl Sub for FieldCounts {
+    type Output = FieldCounts;
+
+    fn sub(self, other: FieldCounts) -> FieldCounts {
+        FieldCounts {
+            mul: self.mul - other.mul,
+            square: self.square - other.square,
+            add: self.add - other.add,
+            inverse: self.inverse - other.inverse,
+            sqrt: self.sqrt - other.sqrt,
+        }
+    }
+}
+
+/// The number of operations performed in each field of BLS12-381.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    pub fq: FieldCounts,
+    pub fr: FieldCounts,
+    pub fq2: FieldCounts,
+    pub fq6: FieldCounts,
+    pub fq12: FieldCounts,
+}
+
+impl Sub for OpCounts {
+    type Output = OpCounts;
+
+    fn sub(self, other: OpCounts) -> OpCounts {
+        OpCounts {
+            fq: self.fq - other.fq,
+            fr: self.fr - other.fr,
+            fq2: self.fq2 - other.fq2,
+            fq6: self.fq6 - other.fq6,
+            fq12: self.fq12 - other.fq12,
+        }
+    }
+}
+
+impl fmt::Display for OpCounts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<6}{:>10}{:>10}{:>10}{:>10}{:>10}",
+            "field", "mul", "square", "add", "inverse", "sqrt"
+        )?;
+
+        for (name, c) in &[
+            ("Fq", self.fq),
+            ("Fr", self.fr),
+            ("Fq2", self.fq2),
+            ("Fq6", self.fq6),
+            ("Fq12", self.fq12),
+        ] {
+            writeln!(
+                f,
+                "{:<6}{:>10}{:>10}{:>10}{:>10}{:>10}",
+                name, c.mul, c.square, c.add, c.inverse, c.sqrt
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+thread_local! {
+    static COUNTS: Cell<OpCounts> = Cell::new(OpCounts::default());
+}
+
+/// Records an operation in the counters of the current thread.
+#[inline]
+pub(crate) fn record<F: FnOnce(&mut OpCounts)>(f: F) {
+    COUNTS.with(|c| {
+        let mut counts = c.get();
+        f(&mut counts);
+        c.set(counts);
+    })
+}
+
+/// Returns the operations counted on the current thread since it started,
+/// or since the counters were last reset.
+pub fn snapshot() -> OpCounts {
+    COUNTS.with(|c| c.get())
+}
+
+/// Resets the counters of the current thread.
+pub fn reset() {
+    COUNTS.with(|c| c.set(OpCounts::default()))
+}
+
+/// Runs a closure, returning its result along with the operations it
+/// performed on the current thread.
+pub fn count<R, F: FnOnce() -> R>(f: F) -> (R, OpCounts) {
+    let before = snapshot();
+    let res = f();
+
+    (res, snapshot() - before)
+}
+
+#[test]
+fn test_op_counts() {
+    use super::{Bls12, Fq, Fq12, Fq2, Fr, G1, G1Affine, G2Affine, G2Prepared};
+    use {CurveAffine, CurveProjective, Engine, Field, SqrtField};
+
+    let (_, counts) = count(|| {
+        let mut a = Fq::one();
+        a.mul_assign(&Fq::one());
+        a.square();
+        a.add_assign(&Fq::one());
+        a.sub_assign(&Fq::one());
+        a.double();
+    });
+    assert_eq!(
+        counts.fq,
+        FieldCounts {
+            mul: 1,
+            square: 1,
+            add: 3,
+            inverse: 0,
+            sqrt: 0,
+        }
+    );
+    assert_eq!(counts.fr, FieldCounts::default());
+    assert_eq!(counts.fq2, FieldCounts::default());
+
+    let (_, counts) = count(|| Fq::one().inverse());
+    assert_eq!(counts.fq.inverse, 1);
+
+    let (_, counts) = count(|| Fq::one().sqrt());
+    assert_eq!(counts.fq.sqrt, 1);
+
+    let (_, counts) = count(|| {
+        let mut a = Fr::one();
+        a.mul_assign(&Fr::one());
+        a.square();
+    });
+    assert_eq!(counts.fr.mul, 1);
+    assert_eq!(counts.fr.square, 1);
+    assert_eq!(counts.fq, FieldCounts::default());
+
+    // A multiplication in Fq2 takes three multiplications in Fq.
+    let (_, counts) = count(|| {
+        let mut a = Fq2::one();
+        a.mul_assign(&Fq2::one());
+    });
+    assert_eq!(counts.fq2.mul, 1);
+    assert_eq!(counts.fq.mul, 3);
+
+    let (_, counts) = count(|| {
+        let mut a = Fq12::one();
+        a.mul_assign(&Fq12::one());
+    });
+    assert_eq!(counts.fq12.mul, 1);
+    assert_eq!(counts.fq6.mul, 3);
+    assert_eq!(counts.fq2.mul, 18);
+    assert_eq!(counts.fq.mul, 54);
+
+    // Counts are deterministic.
+    let (_, a) = count(|| G2Prepared::from_affine(G2Affine::one()));
+    let (_, b) = count(|| G2Prepared::from_affine(G2Affine::one()));
+    assert_eq!(a, b);
+    assert_eq!(a.fq12, FieldCounts::default());
+
+    let (_, a) = count(|| Bls12::pairing(G1Affine::one(), G2Affine::one()));
+    assert_eq!(a.fq12.inverse, 1);
+
+    let mut s = Fr::one();
+    s.double();
+    let (_, a) = count(|| {
+        let mut g = G1::one();
+        g.mul_assign(s);
+        g
+    });
+    assert!(a.fq.mul > 0);
+    assert_eq!(a.fq2, FieldCounts::default());
+
+    reset();
+    assert_eq!(snapshot(), OpCounts::default());
+}
@@ -177,8 +177,24 @@ macro_rules! curve_impl {
             }
 
             fn negate(&mut self) {
-                if !self.is_zero() {
-                    self.y.negate();
+                // Without branching on whether this is the point at
+                // infinity, whose coordinates are left unchanged.
+                #[cfg(feature = "constant-time")]
+                {
+                    let mut y = self.y;
+                    y.negate();
+                    self.y = ::subtle::ConditionallySelectable::conditional_select(
+                        &y,
+                        &self.y,
+                        ::subtle::Choice::from(self.infinity as u8),
+                    );
+                }
+
+                #[cfg(not(feature = "constant-time"))]
+                {
+                    if !self.is_zero() {
+                        self.y.negate();
+                    }
                 }
             }
 
@@ -245,49 +261,23 @@ macro_rules! curve_impl {
 
             fn batch_normalization(v: &mut [Self])
             {
-                // Montgomery’s Trick and Fast Implementation of Masked AES
-                // Genelle, Prouff and Quisquater
-                // Section 3.2
-
-                // First pass: compute [a, ab, abc, ...]
-                let mut prod = Vec::with_capacity(v.len());
-                let mut tmp = $basefield::one();
-                for g in v.iter_mut()
-                          // Ignore normalized elements
-                          .filter(|g| !g.is_normalized())
-                {
-                    tmp.mul_assign(&g.z);
-                    prod.push(tmp);
-                }
-
-                // Invert `tmp`.
-                tmp = tmp.inverse().unwrap(); // Guaranteed to be nonzero.
-
-                // Second pass: iterate backwards to compute inverses
-                for (g, s) in v.iter_mut()
-                               // Backwards
-                               .rev()
-                               // Ignore normalized elements
-                               .filter(|g| !g.is_normalized())
-                               // Backwards, skip last element, fill in one for last term.
-                               .zip(prod.into_iter().rev().skip(1).chain(Some($basefield::one())))
-                {
-                    // tmp := tmp * g.z; g.z := tmp * s = 1/z
-                    let mut newtmp = tmp;
-                    newtmp.mul_assign(&g.z);
-                    g.z = tmp;
-                    g.z.mul_assign(&s);
-                    tmp = newtmp;
-                }
+                // Invert the z-coordinates which are not one.
+                let mut zs: Vec<_> = v.iter()
+                                      // Ignore normalized elements
+                                      .filter(|g| !g.is_normalized())
+                                      .map(|g| g.z)
+                                      .collect();
+                $basefield::batch_invert(&mut zs); // Guaranteed to be nonzero.
 
                 // Perform affine transformations
-                for g in v.iter_mut()
-                          .filter(|g| !g.is_normalized())
+                for (g, zinv) in v.iter_mut()
+                                  .filter(|g| !g.is_normalized())
+                                  .zip(zs)
                 {
-                    let mut z = g.z; // 1/z
+                    let mut z = zinv; // 1/z
                     z.square(); // 1/z^2
                     g.x.mul_assign(&z); // x/z^2
-                    z.mul_assign(&g.z); // 1/z^3
+                    z.mul_assign(&zinv); // 1/z^3
                     g.y.mul_assign(&z); // y/z^3
                     g.z = $basefield::one(); // z = 1
                 }
@@ -617,6 +607,41 @@ macro_rules! curve_impl {
                 }
             }
         }
+
+        impl_curve_ops!($projective, $affine, $scalarfield);
+
+        #[cfg(feature = "constant-time")]
+        impl ::subtle::ConstantTimeEq for $affine {
+            fn ct_eq(&self, other: &$affine) -> ::subtle::Choice {
+                // The point at infinity has a single representation, so
+                // this agrees with `PartialEq`.
+                ::subtle::ConstantTimeEq::ct_eq(&self.x, &other.x)
+                    & ::subtle::ConstantTimeEq::ct_eq(&self.y, &other.y)
+                    & ::subtle::ConstantTimeEq::ct_eq(
+                        &(self.infinity as u8),
+                        &(other.infinity as u8),
+                    )
+            }
+        }
+
+        #[cfg(feature = "constant-time")]
+        impl ::subtle::ConditionallySelectable for $affine {
+            fn conditional_select(
+                a: &$affine,
+                b: &$affine,
+                choice: ::subtle::Choice,
+            ) -> $affine {
+                $affine {
+                    x: ::subtle::ConditionallySelectable::conditional_select(&a.x, &b.x, choice),
+                    y: ::subtle::ConditionallySelectable::conditional_select(&a.y, &b.y, choice),
+                    infinity: <u8 as ::subtle::ConditionallySelectable>::conditional_select(
+                        &(a.infinity as u8),
+                        &(b.infinity as u8),
+                        choice,
+                    ) != 0,
+                }
+            }
+        }
     }
 }
 
@@ -625,6 +650,7 @@ pub mod g1 {
     use super::g2::G2Affine;
     use rand::{Rand, Rng};
     use std::fmt;
+    use std::io::{self, Read, Write};
     use {
         BitIterator, CurveAffine, CurveProjective, EncodedPoint, Engine, Field, GroupDecodingError,
         PrimeField, PrimeFieldRepr, SqrtField,
@@ -932,6 +958,37 @@ pub mod g1 {
         pub fn from_affine(p: G1Affine) -> Self {
             G1Prepared(p)
         }
+
+        /// Writes the prepared point as a `G1Uncompressed` encoding.
+        pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+            writer.write_all(self.0.into_uncompressed().as_ref())
+        }
+
+        /// Reads a prepared point written by `write`, checking that it is on
+        /// the curve and in the prime order subgroup.
+        pub fn read<R: Read>(reader: R) -> io::Result<Self> {
+            Self::read_encoded(reader, true)
+        }
+
+        /// Reads a prepared point written by `write` without checking that it
+        /// is in the prime order subgroup.
+        pub fn read_unchecked<R: Read>(reader: R) -> io::Result<Self> {
+            Self::read_encoded(reader, false)
+        }
+
+        fn read_encoded<R: Read>(mut reader: R, checked: bool) -> io::Result<Self> {
+            let mut encoded = G1Uncompressed::empty();
+            reader.read_exact(encoded.as_mut())?;
+
+            let p = if checked {
+                encoded.into_affine()
+            } else {
+                encoded.into_affine_unchecked()
+            };
+
+            p.map(G1Prepared::from_affine)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        }
     }
 
     #[test]
@@ -1264,6 +1321,10 @@ pub mod g1 {
     #[test]
     fn g1_curve_tests() {
         ::tests::curve::curve_tests::<G1>();
+        ::tests::curve::random_operator_tests::<G1>();
+        ::tests::fft::random_group_fft_tests::<G1>();
+        #[cfg(feature = "constant-time")]
+        ::tests::curve::random_ct_tests::<G1Affine>();
     }
 }
 
@@ -1614,10 +1675,22 @@ pub mod g2 {
 
     #[derive(Clone, Debug)]
     pub struct G2Prepared {
-        pub(crate) coeffs: Vec<(Fq2, Fq2, Fq2)>,
+        pub(crate) coeffs: G2PreparedCoeffs,
         pub(crate) infinity: bool,
     }
 
+    /// The line functions of a prepared G2 element, one for each doubling
+    /// and addition step of the miller loop.
+    #[derive(Clone, Debug)]
+    pub(crate) enum G2PreparedCoeffs {
+        /// Projective lines `(c0, c1, c2)`, evaluated at a G1 point `p` as
+        /// `c2 + c1 * p.x * v + c0 * p.y * v * w`.
+        Projective(Vec<(Fq2, Fq2, Fq2)>),
+        /// Affine lines `(c1, c2)`, the projective lines scaled so that `c0`
+        /// is one.
+        Affine(Vec<(Fq2, Fq2)>),
+    }
+
     #[test]
     fn g2_generator() {
         use SqrtField;
@@ -2019,6 +2092,9 @@ pub mod g2 {
     #[test]
     fn g2_curve_tests() {
         ::tests::curve::curve_tests::<G2>();
+        ::tests::curve::random_operator_tests::<G2>();
+        #[cfg(feature = "constant-time")]
+        ::tests::curve::random_ct_tests::<G2Affine>();
     }
 }
 
@@ -1,6 +1,17 @@
+use super::fq12::Fq12;
 use super::fq2::Fq2;
+use super::fq6::Fq6;
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+use super::limb32;
+use super::Limb;
 use std::cmp::Ordering;
-use {Field, PrimeField, PrimeFieldDecodingError, PrimeFieldRepr, SqrtField};
+use {Field, PrimeField, PrimeFieldDecodingError, PrimeFieldRepr, SqrtField, WidePrimeFieldRepr};
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
+#[cfg(feature = "constant-time")]
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "constant-time")]
+use {CtField, CtSqrtField};
 
 // q = 4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787
 const MODULUS: FqRepr = FqRepr([
@@ -40,7 +51,7 @@ const R2: FqRepr = FqRepr([
 ]);
 
 // INV = -(q^{-1} mod 2^64) mod 2^64
-const INV: u64 = 0x89f3fffcfffcfffd;
+pub(super) const INV: u64 = 0x89f3fffcfffcfffd;
 
 // GENERATOR = 2 (multiplicative generator of q-1 order, that is also quadratic nonresidue)
 const GENERATOR: FqRepr = FqRepr([
@@ -66,7 +77,7 @@ const ROOT_OF_UNITY: FqRepr = FqRepr([
 ]);
 
 // B coefficient of BLS12-381 curve, 4.
-pub const B_COEFF: Fq = Fq(FqRepr([
+pub const B_COEFF: Fq = Fq(limbs([
     0xaa270000000cfff3,
     0x53cc0032fc34000a,
     0x478fe97a6b0a807f,
@@ -82,7 +93,7 @@ pub const B_COEFF: Fq = Fq(FqRepr([
 // Generator of G1
 // x = 3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507
 // y = 1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569
-pub const G1_GENERATOR_X: Fq = Fq(FqRepr([
+pub const G1_GENERATOR_X: Fq = Fq(limbs([
     0x5cb38790fd530c16,
     0x7817fc679976fff5,
     0x154f95c7143ba1c1,
@@ -90,7 +101,7 @@ pub const G1_GENERATOR_X: Fq = Fq(FqRepr([
     0xedce6ecc21dbf440,
     0x120177419e0bfb75,
 ]));
-pub const G1_GENERATOR_Y: Fq = Fq(FqRepr([
+pub const G1_GENERATOR_Y: Fq = Fq(limbs([
     0xbaac93d50ce72271,
     0x8c22631a7918fd8e,
     0xdd595f13570725ce,
@@ -102,7 +113,7 @@ pub const G1_GENERATOR_Y: Fq = Fq(FqRepr([
 // Generator of G2
 // x = 3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758*u + 352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160
 // y = 927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582*u + 1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905
-pub const G2_GENERATOR_X_C0: Fq = Fq(FqRepr([
+pub const G2_GENERATOR_X_C0: Fq = Fq(limbs([
     0xf5f28fa202940a10,
     0xb3f5fb2687b4961a,
     0xa1a893b53e2ae580,
@@ -110,7 +121,7 @@ pub const G2_GENERATOR_X_C0: Fq = Fq(FqRepr([
     0x6f67b7631863366b,
     0x58191924350bcd7,
 ]));
-pub const G2_GENERATOR_X_C1: Fq = Fq(FqRepr([
+pub const G2_GENERATOR_X_C1: Fq = Fq(limbs([
     0xa5a9c0759e23f606,
     0xaaa0c59dbccd60c3,
     0x3bb17e18e2867806,
@@ -118,7 +129,7 @@ pub const G2_GENERATOR_X_C1: Fq = Fq(FqRepr([
     0xc2b6ed0ef2158547,
     0x11922a097360edf3,
 ]));
-pub const G2_GENERATOR_Y_C0: Fq = Fq(FqRepr([
+pub const G2_GENERATOR_Y_C0: Fq = Fq(limbs([
     0x4c730af860494c4a,
     0x597cfa1f5e369c5a,
     0xe7e6856caa0a635a,
@@ -126,7 +137,7 @@ pub const G2_GENERATOR_Y_C0: Fq = Fq(FqRepr([
     0x7d3a975f0ef25a2,
     0x83fd8e7e80dae5,
 ]));
-pub const G2_GENERATOR_Y_C1: Fq = Fq(FqRepr([
+pub const G2_GENERATOR_Y_C1: Fq = Fq(limbs([
     0xadc0fc92df64b05d,
     0x18aa270a2b1461dc,
     0x86adac6a3be4eba0,
@@ -135,10 +146,126 @@ pub const G2_GENERATOR_Y_C1: Fq = Fq(FqRepr([
     0xb2bc2a163de1bf2,
 ]));
 
+// Generator of Gt, e(G1::one(), G2::one())
+pub const GT_GENERATOR: Fq12 = Fq12 {
+    c0: Fq6 {
+        c0: Fq2 {
+            c0: Fq(limbs([
+                0x1972e433a01f85c5,
+                0x97d32b76fd772538,
+                0xc8ce546fc96bcdf9,
+                0xcef63e7366d40614,
+                0xa611342781843780,
+                0x13f3448a3fc6d825,
+            ])),
+            c1: Fq(limbs([
+                0xd26331b02e9d6995,
+                0x9d68a482f7797e7d,
+                0x9c9b29248d39ea92,
+                0xf4801ca2e13107aa,
+                0xa16c0732bdbcb066,
+                0x83ca4afba360478,
+            ])),
+        },
+        c1: Fq2 {
+            c0: Fq(limbs([
+                0x59e261db0916b641,
+                0x2716b6f4b23e960d,
+                0xc8e55b10a0bd9c45,
+                0xbdb0bd99c4deda8,
+                0x8cf89ebf57fdaac5,
+                0x12d6b7929e777a5e,
+            ])),
+            c1: Fq(limbs([
+                0x5fc85188b0e15f35,
+                0x34a06e3a8f096365,
+                0xdb3126a6e02ad62c,
+                0xfc6f5aa97d9a990b,
+                0xa12f55f5eb89c210,
+                0x1723703a926f8889,
+            ])),
+        },
+        c2: Fq2 {
+            c0: Fq(limbs([
+                0x93588f2971828778,
+                0x43f65b8611ab7585,
+                0x3183aaf5ec279fdf,
+                0xfa73d7e18ac99df6,
+                0x64e176a6a64c99b0,
+                0x179fa78c58388f1f,
+            ])),
+            c1: Fq(limbs([
+                0x672a0a11ca2aef12,
+                0xd11b9b52aa3f16b,
+                0xa44412d0699d056e,
+                0xc01d0177221a5ba5,
+                0x66e0cede6c735529,
+                0x5f5a71e9fddc339,
+            ])),
+        },
+    },
+    c1: Fq6 {
+        c0: Fq2 {
+            c0: Fq(limbs([
+                0xd30a88a1b062c679,
+                0x5ac56a5d35fc8304,
+                0xd0c834a6a81f290d,
+                0xcd5430c2da3707c7,
+                0xf0c27ff780500af0,
+                0x9245da6e2d72eae,
+            ])),
+            c1: Fq(limbs([
+                0x9f2e0676791b5156,
+                0xe2d1c8234918fe13,
+                0x4c9e459f3c561bf4,
+                0xa3e85e53b9d3e3c1,
+                0x820a121e21a70020,
+                0x15af618341c59acc,
+            ])),
+        },
+        c1: Fq2 {
+            c0: Fq(limbs([
+                0x7c95658c24993ab1,
+                0x73eb38721ca886b9,
+                0x5256d749477434bc,
+                0x8ba41902ea504a8b,
+                0x4a3d3f80c86ce6d,
+                0x18a64a87fb686eaa,
+            ])),
+            c1: Fq(limbs([
+                0xbb83e71bb920cf26,
+                0x2a5277ac92a73945,
+                0xfc0ee59f94f046a0,
+                0x7158cdf3786058f7,
+                0x7cc1061b82f945f6,
+                0x3f847aa9fdbe567,
+            ])),
+        },
+        c2: Fq2 {
+            c0: Fq(limbs([
+                0x8078dba56134e657,
+                0x1cd7ec9a43998a6e,
+                0xb1aa599a1a993766,
+                0xc9a0f62f0842ee44,
+                0x8e159be3b605dffa,
+                0xc86ba0d4af13fc2,
+            ])),
+            c1: Fq(limbs([
+                0xe80ff2a06a52ffb1,
+                0x7694ca48721a906c,
+                0x7583183e03b08514,
+                0xf567afdd40cee4e2,
+                0x9a6d96d2e526a5fc,
+                0x197e9f49861f2242,
+            ])),
+        },
+    },
+};
+
 // Coefficients for the Frobenius automorphism.
 pub const FROBENIUS_COEFF_FQ2_C1: [Fq; 2] = [
     // Fq(-1)**(((q^0) - 1) / 2)
-    Fq(FqRepr([
+    Fq(limbs([
         0x760900000002fffd,
         0xebf4000bc40c0002,
         0x5f48985753c758ba,
@@ -147,7 +274,7 @@ pub const FROBENIUS_COEFF_FQ2_C1: [Fq; 2] = [
         0x15f65ec3fa80e493,
     ])),
     // Fq(-1)**(((q^1) - 1) / 2)
-    Fq(FqRepr([
+    Fq(limbs([
         0x43f5fffffffcaaae,
         0x32b7fff2ed47fffd,
         0x7e83a49a2e99d69,
@@ -160,7 +287,7 @@ pub const FROBENIUS_COEFF_FQ2_C1: [Fq; 2] = [
 pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
     // Fq2(u + 1)**(((q^0) - 1) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x760900000002fffd,
             0xebf4000bc40c0002,
             0x5f48985753c758ba,
@@ -168,12 +295,12 @@ pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
             0x5c071a97a256ec6d,
             0x15f65ec3fa80e493,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^1) - 1) / 3)
     Fq2 {
-        c0: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
-        c1: Fq(FqRepr([
+        c0: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([
             0xcd03c9e48671f071,
             0x5dab22461fcda5d2,
             0x587042afd3851b95,
@@ -184,7 +311,7 @@ pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
     },
     // Fq2(u + 1)**(((q^2) - 1) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x30f1361b798a64e8,
             0xf3b8ddab7ece5a2a,
             0x16a8ca3ac61577f7,
@@ -192,12 +319,12 @@ pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
             0x3636b76660701c6e,
             0x51ba4ab241b6160,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^3) - 1) / 3)
     Fq2 {
-        c0: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
-        c1: Fq(FqRepr([
+        c0: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([
             0x760900000002fffd,
             0xebf4000bc40c0002,
             0x5f48985753c758ba,
@@ -208,7 +335,7 @@ pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
     },
     // Fq2(u + 1)**(((q^4) - 1) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0xcd03c9e48671f071,
             0x5dab22461fcda5d2,
             0x587042afd3851b95,
@@ -216,12 +343,12 @@ pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
             0x3f97d6e83d050d2,
             0x18f0206554638741,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^5) - 1) / 3)
     Fq2 {
-        c0: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
-        c1: Fq(FqRepr([
+        c0: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([
             0x30f1361b798a64e8,
             0xf3b8ddab7ece5a2a,
             0x16a8ca3ac61577f7,
@@ -235,7 +362,7 @@ pub const FROBENIUS_COEFF_FQ6_C1: [Fq2; 6] = [
 pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
     // Fq2(u + 1)**(((2q^0) - 2) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x760900000002fffd,
             0xebf4000bc40c0002,
             0x5f48985753c758ba,
@@ -243,11 +370,11 @@ pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
             0x5c071a97a256ec6d,
             0x15f65ec3fa80e493,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((2q^1) - 2) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x890dc9e4867545c3,
             0x2af322533285a5d5,
             0x50880866309b7e2c,
@@ -255,11 +382,11 @@ pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
             0x14e4f04fe2db9068,
             0x14e56d3f1564853a,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((2q^2) - 2) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0xcd03c9e48671f071,
             0x5dab22461fcda5d2,
             0x587042afd3851b95,
@@ -267,11 +394,11 @@ pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
             0x3f97d6e83d050d2,
             0x18f0206554638741,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((2q^3) - 2) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x43f5fffffffcaaae,
             0x32b7fff2ed47fffd,
             0x7e83a49a2e99d69,
@@ -279,11 +406,11 @@ pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
             0xef148d1ea0f4c069,
             0x40ab3263eff0206,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((2q^4) - 2) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x30f1361b798a64e8,
             0xf3b8ddab7ece5a2a,
             0x16a8ca3ac61577f7,
@@ -291,11 +418,11 @@ pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
             0x3636b76660701c6e,
             0x51ba4ab241b6160,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((2q^5) - 2) / 3)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0xecfb361b798dba3a,
             0xc100ddb891865a2c,
             0xec08ff1232bda8e,
@@ -303,7 +430,7 @@ pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
             0x47222a47bf7b5c04,
             0x110f184e51c5f59,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
 ];
 
@@ -311,7 +438,7 @@ pub const FROBENIUS_COEFF_FQ6_C2: [Fq2; 6] = [
 pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
     // Fq2(u + 1)**(((q^0) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x760900000002fffd,
             0xebf4000bc40c0002,
             0x5f48985753c758ba,
@@ -319,11 +446,11 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x5c071a97a256ec6d,
             0x15f65ec3fa80e493,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^1) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x7089552b319d465,
             0xc6695f92b50a8313,
             0x97e83cccd117228f,
@@ -331,7 +458,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x1ce393ea5daace4d,
             0x8f2220fb0fb66eb,
         ])),
-        c1: Fq(FqRepr([
+        c1: Fq(limbs([
             0xb2f66aad4ce5d646,
             0x5842a06bfc497cec,
             0xcf4895d42599d394,
@@ -342,7 +469,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
     },
     // Fq2(u + 1)**(((q^2) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0xecfb361b798dba3a,
             0xc100ddb891865a2c,
             0xec08ff1232bda8e,
@@ -350,11 +477,11 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x47222a47bf7b5c04,
             0x110f184e51c5f59,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^3) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x3e2f585da55c9ad1,
             0x4294213d86c18183,
             0x382844c88b623732,
@@ -362,7 +489,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x1d794e4fac7cf0b9,
             0xbd592fc7d825ec8,
         ])),
-        c1: Fq(FqRepr([
+        c1: Fq(limbs([
             0x7bcfa7a25aa30fda,
             0xdc17dec12a927e7c,
             0x2f088dd86b4ebef1,
@@ -373,7 +500,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
     },
     // Fq2(u + 1)**(((q^4) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x30f1361b798a64e8,
             0xf3b8ddab7ece5a2a,
             0x16a8ca3ac61577f7,
@@ -381,11 +508,11 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x3636b76660701c6e,
             0x51ba4ab241b6160,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^5) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x3726c30af242c66c,
             0x7c2ac1aad1b6fe70,
             0xa04007fbba4b14a2,
@@ -393,7 +520,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x95ba654ed2226b,
             0x2e370eccc86f7dd,
         ])),
-        c1: Fq(FqRepr([
+        c1: Fq(limbs([
             0x82d83cf50dbce43f,
             0xa2813e53df9d018f,
             0xc6f0caa53c65e181,
@@ -404,7 +531,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
     },
     // Fq2(u + 1)**(((q^6) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x43f5fffffffcaaae,
             0x32b7fff2ed47fffd,
             0x7e83a49a2e99d69,
@@ -412,11 +539,11 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0xef148d1ea0f4c069,
             0x40ab3263eff0206,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^7) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0xb2f66aad4ce5d646,
             0x5842a06bfc497cec,
             0xcf4895d42599d394,
@@ -424,7 +551,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x2e3813cbe5a0de89,
             0x110eefda88847faf,
         ])),
-        c1: Fq(FqRepr([
+        c1: Fq(limbs([
             0x7089552b319d465,
             0xc6695f92b50a8313,
             0x97e83cccd117228f,
@@ -435,7 +562,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
     },
     // Fq2(u + 1)**(((q^8) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0xcd03c9e48671f071,
             0x5dab22461fcda5d2,
             0x587042afd3851b95,
@@ -443,11 +570,11 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x3f97d6e83d050d2,
             0x18f0206554638741,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^9) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x7bcfa7a25aa30fda,
             0xdc17dec12a927e7c,
             0x2f088dd86b4ebef1,
@@ -455,7 +582,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x2da2596696cebc1d,
             0xe2b7eedbbfd87d2,
         ])),
-        c1: Fq(FqRepr([
+        c1: Fq(limbs([
             0x3e2f585da55c9ad1,
             0x4294213d86c18183,
             0x382844c88b623732,
@@ -466,7 +593,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
     },
     // Fq2(u + 1)**(((q^10) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x890dc9e4867545c3,
             0x2af322533285a5d5,
             0x50880866309b7e2c,
@@ -474,11 +601,11 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x14e4f04fe2db9068,
             0x14e56d3f1564853a,
         ])),
-        c1: Fq(FqRepr([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
+        c1: Fq(limbs([0x0, 0x0, 0x0, 0x0, 0x0, 0x0])),
     },
     // Fq2(u + 1)**(((q^11) - 1) / 6)
     Fq2 {
-        c0: Fq(FqRepr([
+        c0: Fq(limbs([
             0x82d83cf50dbce43f,
             0xa2813e53df9d018f,
             0xc6f0caa53c65e181,
@@ -486,7 +613,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
             0x4a85ed50f4798a6b,
             0x171da0fd6cf8eebd,
         ])),
-        c1: Fq(FqRepr([
+        c1: Fq(limbs([
             0x3726c30af242c66c,
             0x7c2ac1aad1b6fe70,
             0xa04007fbba4b14a2,
@@ -498,7 +625,7 @@ pub const FROBENIUS_COEFF_FQ12_C1: [Fq2; 12] = [
 ];
 
 // -((2**384) mod q) mod q
-pub const NEGATIVE_ONE: Fq = Fq(FqRepr([
+pub const NEGATIVE_ONE: Fq = Fq(limbs([
     0x43f5fffffffcaaae,
     0x32b7fff2ed47fffd,
     0x7e83a49a2e99d69,
@@ -507,6 +634,16 @@ pub const NEGATIVE_ONE: Fq = Fq(FqRepr([
     0x40ab3263eff0206,
 ]));
 
+// (2**383) mod q, the inverse of two
+pub const HALF: Fq = Fq(limbs([
+    0x1804000000015554,
+    0x855000053ab00001,
+    0x633cb57c253c276f,
+    0x6e22d1ec31ebb502,
+    0xd3916126f2d14ca2,
+    0x17fbb8571a006596,
+]));
+
 #[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
 pub struct FqRepr(pub [u64; 6]);
 
@@ -696,8 +833,107 @@ impl PrimeFieldRepr for FqRepr {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-pub struct Fq(FqRepr);
+impl WidePrimeFieldRepr for FqRepr {
+    type Wide = [u64; 12];
+}
+
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+impl FqRepr {
+    /// Adds `MODULUS & mask` to this value, where `mask` is either zero or
+    /// all ones, so that the modulus can be added without branching.
+    #[inline(always)]
+    fn add_masked_modulus(&mut self, mask: u64) {
+        let mut carry = 0;
+
+        for (a, b) in self.0.iter_mut().zip(MODULUS.0.iter()) {
+            *a = ::adc(*a, *b & mask, &mut carry);
+        }
+    }
+
+    /// Subtracts the modulus from this value if it is not below the
+    /// modulus, without branching on the value.
+    #[inline(always)]
+    fn reduce_once(&mut self) {
+        let mut tmp = *self;
+        let borrow = u64::from(tmp.sub_with_borrow(&MODULUS));
+
+        // The subtraction only borrowed if this value was already reduced.
+        let mask = borrow.wrapping_sub(1);
+        for (a, b) in self.0.iter_mut().zip(tmp.0.iter()) {
+            *a ^= (*a ^ *b) & mask;
+        }
+    }
+}
+
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+impl limb32::Limbs<LIMBS> {
+    /// Adds `MODULUS & mask` to this value, where `mask` is either zero or
+    /// all ones, so that the modulus can be added without branching.
+    #[inline(always)]
+    fn add_masked_modulus(&mut self, mask: u32) {
+        self.add_masked(&MODULUS_LIMBS, mask);
+    }
+
+    /// Subtracts the modulus from this value if it is not below the
+    /// modulus, without branching on the value.
+    #[inline(always)]
+    fn reduce_once(&mut self) {
+        self.sub_if_not_below(&MODULUS_LIMBS);
+    }
+}
+
+/// The number of limbs `Fq` elements are stored in.
+const LIMBS: usize = 384 / Limb::BITS as usize;
+
+/// The limbs `Fq` elements are stored in: those of `FqRepr`, or twice as
+/// many 32-bit limbs on 32-bit targets or with the `u32-limbs` feature.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+type FqLimbs = FqRepr;
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+type FqLimbs = limb32::Limbs<LIMBS>;
+
+const MODULUS_LIMBS: FqLimbs = limbs(MODULUS.0);
+
+/// Converts the 64-bit limbs of a representation to `FqLimbs`.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+const fn limbs(l: [u64; 6]) -> FqLimbs {
+    FqRepr(l)
+}
+
+/// Converts the 64-bit limbs of a representation to `FqLimbs`.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+const fn limbs(l: [u64; 6]) -> FqLimbs {
+    limb32::Limbs::split(&l)
+}
+
+/// Converts `FqLimbs` back to a representation.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+fn repr(l: FqLimbs) -> FqRepr {
+    l
+}
+
+/// Converts `FqLimbs` back to a representation.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+fn repr(l: FqLimbs) -> FqRepr {
+    let mut r = FqRepr::default();
+    l.join(&mut r.0);
+    r
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Fq(FqLimbs);
+
+/// Shows the Montgomery form as an `FqRepr`, whichever limbs it is stored
+/// in.
+impl ::std::fmt::Debug for Fq {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.debug_tuple("Fq").field(&repr(self.0)).finish()
+    }
+}
 
 /// `Fq` elements are ordered lexicographically.
 impl Ord for Fq {
@@ -720,14 +956,55 @@ impl ::std::fmt::Display for Fq {
     }
 }
 
+#[cfg(feature = "bigint")]
+impl From<FqRepr> for BigUint {
+    fn from(repr: FqRepr) -> BigUint {
+        repr.to_biguint()
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl From<Fq> for BigUint {
+    fn from(a: Fq) -> BigUint {
+        a.to_biguint()
+    }
+}
+
+/// Reduces an integer of any size into the field.
+#[cfg(feature = "bigint")]
+impl<'a> From<&'a BigUint> for Fq {
+    fn from(n: &'a BigUint) -> Fq {
+        Fq::from_biguint(n)
+    }
+}
+
+/// Formats the canonical representative in hexadecimal, prefixed with `0x`
+/// in the alternate form.
+impl ::std::fmt::LowerHex for Fq {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+/// Formats the canonical representative in uppercase hexadecimal, prefixed
+/// with `0x` in the alternate form.
+impl ::std::fmt::UpperHex for Fq {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16).to_uppercase())
+    }
+}
+
+impl_field_ops!(Fq);
+
 impl ::rand::Rand for Fq {
     fn rand<R: ::rand::Rng>(rng: &mut R) -> Self {
         loop {
-            let mut tmp = Fq(FqRepr::rand(rng));
+            let mut r = FqRepr::rand(rng);
 
             // Mask away the unused bits at the beginning.
-            tmp.0.as_mut()[5] &= 0xffffffffffffffff >> REPR_SHAVE_BITS;
+            r.0[5] &= 0xffffffffffffffff >> REPR_SHAVE_BITS;
 
+            let tmp = Fq(limbs(r.0));
             if tmp.is_valid() {
                 return tmp;
             }
@@ -745,33 +1022,22 @@ impl PrimeField for Fq {
     type Repr = FqRepr;
 
     fn from_repr(r: FqRepr) -> Result<Fq, PrimeFieldDecodingError> {
-        let mut r = Fq(r);
+        let mut r = Fq(limbs(r.0));
         if r.is_valid() {
-            r.mul_assign(&Fq(R2));
+            r.mul_assign(&Fq(limbs(R2.0)));
 
             Ok(r)
         } else {
-            Err(PrimeFieldDecodingError::NotInField(format!("{}", r.0)))
+            Err(PrimeFieldDecodingError::NotInField(format!("{}", repr(r.0))))
         }
     }
 
     fn into_repr(&self) -> FqRepr {
+        let mut t = [0; 2 * LIMBS];
+        t[..LIMBS].copy_from_slice(&(self.0).0);
         let mut r = *self;
-        r.mont_reduce(
-            (self.0).0[0],
-            (self.0).0[1],
-            (self.0).0[2],
-            (self.0).0[3],
-            (self.0).0[4],
-            (self.0).0[5],
-            0,
-            0,
-            0,
-            0,
-            0,
-            0,
-        );
-        r.0
+        r.mont_reduce(t);
+        repr(r.0)
     }
 
     fn char() -> FqRepr {
@@ -783,25 +1049,25 @@ impl PrimeField for Fq {
     const CAPACITY: u32 = Self::NUM_BITS - 1;
 
     fn multiplicative_generator() -> Self {
-        Fq(GENERATOR)
+        Fq(limbs(GENERATOR.0))
     }
 
     const S: u32 = S;
 
     fn root_of_unity() -> Self {
-        Fq(ROOT_OF_UNITY)
+        Fq(limbs(ROOT_OF_UNITY.0))
     }
 }
 
 impl Field for Fq {
     #[inline]
     fn zero() -> Self {
-        Fq(FqRepr::from(0))
+        Fq(FqLimbs::default())
     }
 
     #[inline]
     fn one() -> Self {
-        Fq(R)
+        Fq(limbs(R.0))
     }
 
     #[inline]
@@ -811,6 +1077,7 @@ impl Field for Fq {
 
     #[inline]
     fn add_assign(&mut self, other: &Fq) {
+        count_op!(fq.add);
         // This cannot exceed the backing capacity.
         self.0.add_nocarry(&other.0);
 
@@ -820,6 +1087,7 @@ impl Field for Fq {
 
     #[inline]
     fn double(&mut self) {
+        count_op!(fq.add);
         // This cannot exceed the backing capacity.
         self.0.mul2();
 
@@ -829,24 +1097,29 @@ impl Field for Fq {
 
     #[inline]
     fn sub_assign(&mut self, other: &Fq) {
-        // If `other` is larger than `self`, we'll need to add the modulus to self first.
-        if other.0 > self.0 {
-            self.0.add_nocarry(&MODULUS);
-        }
+        count_op!(fq.add);
+        let borrow = Limb::from(self.0.sub_with_borrow(&other.0));
 
-        self.0.sub_noborrow(&other.0);
+        // If `other` was larger than `self`, the difference wrapped around
+        // and the modulus must be added back.
+        self.0.add_masked_modulus(borrow.wrapping_neg());
     }
 
     #[inline]
     fn negate(&mut self) {
-        if !self.is_zero() {
-            let mut tmp = MODULUS;
-            tmp.sub_noborrow(&self.0);
-            self.0 = tmp;
+        // Zero is its own negation, so the difference is masked away for it.
+        let nonzero = (self.0).0.iter().fold(0, |acc, limb| acc | limb);
+        let mask = ((nonzero | nonzero.wrapping_neg()) >> (Limb::BITS - 1)).wrapping_neg();
+
+        let mut tmp = MODULUS_LIMBS;
+        tmp.sub_noborrow(&self.0);
+        for (a, b) in (self.0).0.iter_mut().zip(tmp.0.iter()) {
+            *a = *b & mask;
         }
     }
 
     fn inverse(&self) -> Option<Self> {
+        count_op!(fq.inverse);
         if self.is_zero() {
             None
         } else {
@@ -854,11 +1127,11 @@ impl Field for Fq {
             // Efficient Software-Implementation of Finite Fields with Applications to Cryptography
             // Algorithm 16 (BEA for Inversion in Fp)
 
-            let one = FqRepr::from(1);
+            let one = limbs([1, 0, 0, 0, 0, 0]);
 
             let mut u = self.0;
-            let mut v = MODULUS;
-            let mut b = Fq(R2); // Avoids unnecessary reduction step.
+            let mut v = MODULUS_LIMBS;
+            let mut b = Fq(limbs(R2.0)); // Avoids unnecessary reduction step.
             let mut c = Self::zero();
 
             while u != one && v != one {
@@ -868,7 +1141,7 @@ impl Field for Fq {
                     if b.0.is_even() {
                         b.0.div2();
                     } else {
-                        b.0.add_nocarry(&MODULUS);
+                        b.0.add_nocarry(&MODULUS_LIMBS);
                         b.0.div2();
                     }
                 }
@@ -879,7 +1152,7 @@ impl Field for Fq {
                     if c.0.is_even() {
                         c.0.div2();
                     } else {
-                        c.0.add_nocarry(&MODULUS);
+                        c.0.add_nocarry(&MODULUS_LIMBS);
                         c.0.div2();
                     }
                 }
@@ -908,243 +1181,324 @@ impl Field for Fq {
 
     #[inline]
     fn mul_assign(&mut self, other: &Fq) {
-        let mut carry = 0;
-        let r0 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[0], &mut carry);
-        let r1 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[1], &mut carry);
-        let r2 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[2], &mut carry);
-        let r3 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[3], &mut carry);
-        let r4 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[4], &mut carry);
-        let r5 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[5], &mut carry);
-        let r6 = carry;
-        let mut carry = 0;
-        let r1 = ::mac_with_carry(r1, (self.0).0[1], (other.0).0[0], &mut carry);
-        let r2 = ::mac_with_carry(r2, (self.0).0[1], (other.0).0[1], &mut carry);
-        let r3 = ::mac_with_carry(r3, (self.0).0[1], (other.0).0[2], &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[1], (other.0).0[3], &mut carry);
-        let r5 = ::mac_with_carry(r5, (self.0).0[1], (other.0).0[4], &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[1], (other.0).0[5], &mut carry);
-        let r7 = carry;
-        let mut carry = 0;
-        let r2 = ::mac_with_carry(r2, (self.0).0[2], (other.0).0[0], &mut carry);
-        let r3 = ::mac_with_carry(r3, (self.0).0[2], (other.0).0[1], &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[2], (other.0).0[2], &mut carry);
-        let r5 = ::mac_with_carry(r5, (self.0).0[2], (other.0).0[3], &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[2], (other.0).0[4], &mut carry);
-        let r7 = ::mac_with_carry(r7, (self.0).0[2], (other.0).0[5], &mut carry);
-        let r8 = carry;
-        let mut carry = 0;
-        let r3 = ::mac_with_carry(r3, (self.0).0[3], (other.0).0[0], &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[3], (other.0).0[1], &mut carry);
-        let r5 = ::mac_with_carry(r5, (self.0).0[3], (other.0).0[2], &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[3], (other.0).0[3], &mut carry);
-        let r7 = ::mac_with_carry(r7, (self.0).0[3], (other.0).0[4], &mut carry);
-        let r8 = ::mac_with_carry(r8, (self.0).0[3], (other.0).0[5], &mut carry);
-        let r9 = carry;
-        let mut carry = 0;
-        let r4 = ::mac_with_carry(r4, (self.0).0[4], (other.0).0[0], &mut carry);
-        let r5 = ::mac_with_carry(r5, (self.0).0[4], (other.0).0[1], &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[4], (other.0).0[2], &mut carry);
-        let r7 = ::mac_with_carry(r7, (self.0).0[4], (other.0).0[3], &mut carry);
-        let r8 = ::mac_with_carry(r8, (self.0).0[4], (other.0).0[4], &mut carry);
-        let r9 = ::mac_with_carry(r9, (self.0).0[4], (other.0).0[5], &mut carry);
-        let r10 = carry;
-        let mut carry = 0;
-        let r5 = ::mac_with_carry(r5, (self.0).0[5], (other.0).0[0], &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[5], (other.0).0[1], &mut carry);
-        let r7 = ::mac_with_carry(r7, (self.0).0[5], (other.0).0[2], &mut carry);
-        let r8 = ::mac_with_carry(r8, (self.0).0[5], (other.0).0[3], &mut carry);
-        let r9 = ::mac_with_carry(r9, (self.0).0[5], (other.0).0[4], &mut carry);
-        let r10 = ::mac_with_carry(r10, (self.0).0[5], (other.0).0[5], &mut carry);
-        let r11 = carry;
-        self.mont_reduce(r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11);
+        count_op!(fq.mul);
+        adx_or!(
+            {
+                let a = (self.0).0;
+                adx::mont_mul_6(&a, &(other.0).0, &MODULUS.0, INV, &mut (self.0).0)
+            },
+            (self.0).0 = mont_reduce_portable(mul_portable(&(self.0).0, &(other.0).0))
+        );
+        self.reduce();
     }
 
     #[inline]
     fn square(&mut self) {
-        let mut carry = 0;
-        let r1 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[1], &mut carry);
-        let r2 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[2], &mut carry);
-        let r3 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[3], &mut carry);
-        let r4 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[4], &mut carry);
-        let r5 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[5], &mut carry);
-        let r6 = carry;
-        let mut carry = 0;
-        let r3 = ::mac_with_carry(r3, (self.0).0[1], (self.0).0[2], &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[1], (self.0).0[3], &mut carry);
-        let r5 = ::mac_with_carry(r5, (self.0).0[1], (self.0).0[4], &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[1], (self.0).0[5], &mut carry);
-        let r7 = carry;
-        let mut carry = 0;
-        let r5 = ::mac_with_carry(r5, (self.0).0[2], (self.0).0[3], &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[2], (self.0).0[4], &mut carry);
-        let r7 = ::mac_with_carry(r7, (self.0).0[2], (self.0).0[5], &mut carry);
-        let r8 = carry;
-        let mut carry = 0;
-        let r7 = ::mac_with_carry(r7, (self.0).0[3], (self.0).0[4], &mut carry);
-        let r8 = ::mac_with_carry(r8, (self.0).0[3], (self.0).0[5], &mut carry);
-        let r9 = carry;
-        let mut carry = 0;
-        let r9 = ::mac_with_carry(r9, (self.0).0[4], (self.0).0[5], &mut carry);
-        let r10 = carry;
-
-        let r11 = r10 >> 63;
-        let r10 = (r10 << 1) | (r9 >> 63);
-        let r9 = (r9 << 1) | (r8 >> 63);
-        let r8 = (r8 << 1) | (r7 >> 63);
-        let r7 = (r7 << 1) | (r6 >> 63);
-        let r6 = (r6 << 1) | (r5 >> 63);
-        let r5 = (r5 << 1) | (r4 >> 63);
-        let r4 = (r4 << 1) | (r3 >> 63);
-        let r3 = (r3 << 1) | (r2 >> 63);
-        let r2 = (r2 << 1) | (r1 >> 63);
-        let r1 = r1 << 1;
-
-        let mut carry = 0;
-        let r0 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[0], &mut carry);
-        let r1 = ::adc(r1, 0, &mut carry);
-        let r2 = ::mac_with_carry(r2, (self.0).0[1], (self.0).0[1], &mut carry);
-        let r3 = ::adc(r3, 0, &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[2], (self.0).0[2], &mut carry);
-        let r5 = ::adc(r5, 0, &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[3], (self.0).0[3], &mut carry);
-        let r7 = ::adc(r7, 0, &mut carry);
-        let r8 = ::mac_with_carry(r8, (self.0).0[4], (self.0).0[4], &mut carry);
-        let r9 = ::adc(r9, 0, &mut carry);
-        let r10 = ::mac_with_carry(r10, (self.0).0[5], (self.0).0[5], &mut carry);
-        let r11 = ::adc(r11, 0, &mut carry);
-        self.mont_reduce(r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11);
+        count_op!(fq.square);
+        adx_or!(
+            {
+                let a = (self.0).0;
+                adx::mont_mul_6(&a, &a, &MODULUS.0, INV, &mut (self.0).0)
+            },
+            (self.0).0 = mont_reduce_portable(square_portable(&(self.0).0))
+        );
+        self.reduce();
     }
 }
 
+/// Parses a list of `Fq` components as accepted by
+/// `PrimeField::from_str_radix`, separated by commas or whitespace. Brackets
+/// and parentheses are ignored, so that nested tuples and arrays printed by
+/// other libraries can be pasted in as they are.
+pub(crate) fn parse_components(s: &str, radix: u32) -> Option<Vec<Fq>> {
+    s.split(|c: char| c == ',' || c.is_whitespace() || "[]()".contains(c))
+        .filter(|t| !t.is_empty())
+        .map(|t| Fq::from_str_radix(t, radix))
+        .collect()
+}
+
 impl Fq {
+    /// Computes the Legendre symbol by exponentiation with Euler's
+    /// criterion. This is slower than `SqrtField::legendre` and only
+    /// retained as a reference for testing it.
+    #[cfg(test)]
+    fn legendre_reference(&self) -> ::LegendreSymbol {
+        use LegendreSymbol::*;
+
+        // s = self^((q - 1) // 2)
+        let s = self.pow([
+            0xdcff7fffffffd555,
+            0xf55ffff58a9ffff,
+            0xb39869507b587b12,
+            0xb23ba5c279c2895f,
+            0x258dd3db21a5d66b,
+            0xd0088f51cbff34d,
+        ]);
+        if s == Fq::zero() {
+            Zero
+        } else if s == Fq::one() {
+            QuadraticResidue
+        } else {
+            QuadraticNonResidue
+        }
+    }
+
     /// Determines if the element is really in the field. This is only used
     /// internally.
     #[inline(always)]
     fn is_valid(&self) -> bool {
-        self.0 < MODULUS
+        self.0 < MODULUS_LIMBS
     }
 
     /// Subtracts the modulus from this element if this element is not in the
-    /// field. Only used internally.
+    /// field, without branching on its value. Only used internally.
     #[inline(always)]
     fn reduce(&mut self) {
-        if !self.is_valid() {
-            self.0.sub_noborrow(&MODULUS);
-        }
+        self.0.reduce_once();
     }
 
     #[inline(always)]
-    fn mont_reduce(
-        &mut self,
-        r0: u64,
-        mut r1: u64,
-        mut r2: u64,
-        mut r3: u64,
-        mut r4: u64,
-        mut r5: u64,
-        mut r6: u64,
-        mut r7: u64,
-        mut r8: u64,
-        mut r9: u64,
-        mut r10: u64,
-        mut r11: u64,
-    ) {
-        // The Montgomery reduction here is based on Algorithm 14.32 in
-        // Handbook of Applied Cryptography
-        // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
-
-        let k = r0.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r0, k, MODULUS.0[0], &mut carry);
-        r1 = ::mac_with_carry(r1, k, MODULUS.0[1], &mut carry);
-        r2 = ::mac_with_carry(r2, k, MODULUS.0[2], &mut carry);
-        r3 = ::mac_with_carry(r3, k, MODULUS.0[3], &mut carry);
-        r4 = ::mac_with_carry(r4, k, MODULUS.0[4], &mut carry);
-        r5 = ::mac_with_carry(r5, k, MODULUS.0[5], &mut carry);
-        r6 = ::adc(r6, 0, &mut carry);
-        let carry2 = carry;
-        let k = r1.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r1, k, MODULUS.0[0], &mut carry);
-        r2 = ::mac_with_carry(r2, k, MODULUS.0[1], &mut carry);
-        r3 = ::mac_with_carry(r3, k, MODULUS.0[2], &mut carry);
-        r4 = ::mac_with_carry(r4, k, MODULUS.0[3], &mut carry);
-        r5 = ::mac_with_carry(r5, k, MODULUS.0[4], &mut carry);
-        r6 = ::mac_with_carry(r6, k, MODULUS.0[5], &mut carry);
-        r7 = ::adc(r7, carry2, &mut carry);
-        let carry2 = carry;
-        let k = r2.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r2, k, MODULUS.0[0], &mut carry);
-        r3 = ::mac_with_carry(r3, k, MODULUS.0[1], &mut carry);
-        r4 = ::mac_with_carry(r4, k, MODULUS.0[2], &mut carry);
-        r5 = ::mac_with_carry(r5, k, MODULUS.0[3], &mut carry);
-        r6 = ::mac_with_carry(r6, k, MODULUS.0[4], &mut carry);
-        r7 = ::mac_with_carry(r7, k, MODULUS.0[5], &mut carry);
-        r8 = ::adc(r8, carry2, &mut carry);
-        let carry2 = carry;
-        let k = r3.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r3, k, MODULUS.0[0], &mut carry);
-        r4 = ::mac_with_carry(r4, k, MODULUS.0[1], &mut carry);
-        r5 = ::mac_with_carry(r5, k, MODULUS.0[2], &mut carry);
-        r6 = ::mac_with_carry(r6, k, MODULUS.0[3], &mut carry);
-        r7 = ::mac_with_carry(r7, k, MODULUS.0[4], &mut carry);
-        r8 = ::mac_with_carry(r8, k, MODULUS.0[5], &mut carry);
-        r9 = ::adc(r9, carry2, &mut carry);
-        let carry2 = carry;
-        let k = r4.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r4, k, MODULUS.0[0], &mut carry);
-        r5 = ::mac_with_carry(r5, k, MODULUS.0[1], &mut carry);
-        r6 = ::mac_with_carry(r6, k, MODULUS.0[2], &mut carry);
-        r7 = ::mac_with_carry(r7, k, MODULUS.0[3], &mut carry);
-        r8 = ::mac_with_carry(r8, k, MODULUS.0[4], &mut carry);
-        r9 = ::mac_with_carry(r9, k, MODULUS.0[5], &mut carry);
-        r10 = ::adc(r10, carry2, &mut carry);
-        let carry2 = carry;
-        let k = r5.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r5, k, MODULUS.0[0], &mut carry);
-        r6 = ::mac_with_carry(r6, k, MODULUS.0[1], &mut carry);
-        r7 = ::mac_with_carry(r7, k, MODULUS.0[2], &mut carry);
-        r8 = ::mac_with_carry(r8, k, MODULUS.0[3], &mut carry);
-        r9 = ::mac_with_carry(r9, k, MODULUS.0[4], &mut carry);
-        r10 = ::mac_with_carry(r10, k, MODULUS.0[5], &mut carry);
-        r11 = ::adc(r11, carry2, &mut carry);
-        (self.0).0[0] = r6;
-        (self.0).0[1] = r7;
-        (self.0).0[2] = r8;
-        (self.0).0[3] = r9;
-        (self.0).0[4] = r10;
-        (self.0).0[5] = r11;
+    fn mont_reduce(&mut self, t: [Limb; 2 * LIMBS]) {
+        adx_or!(
+            adx::mont_reduce_6(&t, &MODULUS.0, INV, &mut (self.0).0),
+            (self.0).0 = mont_reduce_portable(t)
+        );
         self.reduce();
     }
 }
 
+/// Returns the full product of `a` and `b`, computed by the portable
+/// arithmetic.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+pub(super) fn mul_portable(a: &[u32; 12], b: &[u32; 12]) -> [u32; 24] {
+    let mut res = [0; 24];
+    limb32::mul(a, b, &mut res);
+    res
+}
+
+/// Returns the square of `a`, computed by the portable arithmetic.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+pub(super) fn square_portable(a: &[u32; 12]) -> [u32; 24] {
+    let mut res = [0; 24];
+    limb32::square(a, &mut res);
+    res
+}
+
+/// Returns the full product of `a` and `b`, computed by the portable
+/// arithmetic.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+pub(super) fn mul_portable(a: &[u64; 6], b: &[u64; 6]) -> [u64; 12] {
+    let mut carry = 0;
+    let r0 = ::mac_with_carry(0, a[0], b[0], &mut carry);
+    let r1 = ::mac_with_carry(0, a[0], b[1], &mut carry);
+    let r2 = ::mac_with_carry(0, a[0], b[2], &mut carry);
+    let r3 = ::mac_with_carry(0, a[0], b[3], &mut carry);
+    let r4 = ::mac_with_carry(0, a[0], b[4], &mut carry);
+    let r5 = ::mac_with_carry(0, a[0], b[5], &mut carry);
+    let r6 = carry;
+    let mut carry = 0;
+    let r1 = ::mac_with_carry(r1, a[1], b[0], &mut carry);
+    let r2 = ::mac_with_carry(r2, a[1], b[1], &mut carry);
+    let r3 = ::mac_with_carry(r3, a[1], b[2], &mut carry);
+    let r4 = ::mac_with_carry(r4, a[1], b[3], &mut carry);
+    let r5 = ::mac_with_carry(r5, a[1], b[4], &mut carry);
+    let r6 = ::mac_with_carry(r6, a[1], b[5], &mut carry);
+    let r7 = carry;
+    let mut carry = 0;
+    let r2 = ::mac_with_carry(r2, a[2], b[0], &mut carry);
+    let r3 = ::mac_with_carry(r3, a[2], b[1], &mut carry);
+    let r4 = ::mac_with_carry(r4, a[2], b[2], &mut carry);
+    let r5 = ::mac_with_carry(r5, a[2], b[3], &mut carry);
+    let r6 = ::mac_with_carry(r6, a[2], b[4], &mut carry);
+    let r7 = ::mac_with_carry(r7, a[2], b[5], &mut carry);
+    let r8 = carry;
+    let mut carry = 0;
+    let r3 = ::mac_with_carry(r3, a[3], b[0], &mut carry);
+    let r4 = ::mac_with_carry(r4, a[3], b[1], &mut carry);
+    let r5 = ::mac_with_carry(r5, a[3], b[2], &mut carry);
+    let r6 = ::mac_with_carry(r6, a[3], b[3], &mut carry);
+    let r7 = ::mac_with_carry(r7, a[3], b[4], &mut carry);
+    let r8 = ::mac_with_carry(r8, a[3], b[5], &mut carry);
+    let r9 = carry;
+    let mut carry = 0;
+    let r4 = ::mac_with_carry(r4, a[4], b[0], &mut carry);
+    let r5 = ::mac_with_carry(r5, a[4], b[1], &mut carry);
+    let r6 = ::mac_with_carry(r6, a[4], b[2], &mut carry);
+    let r7 = ::mac_with_carry(r7, a[4], b[3], &mut carry);
+    let r8 = ::mac_with_carry(r8, a[4], b[4], &mut carry);
+    let r9 = ::mac_with_carry(r9, a[4], b[5], &mut carry);
+    let r10 = carry;
+    let mut carry = 0;
+    let r5 = ::mac_with_carry(r5, a[5], b[0], &mut carry);
+    let r6 = ::mac_with_carry(r6, a[5], b[1], &mut carry);
+    let r7 = ::mac_with_carry(r7, a[5], b[2], &mut carry);
+    let r8 = ::mac_with_carry(r8, a[5], b[3], &mut carry);
+    let r9 = ::mac_with_carry(r9, a[5], b[4], &mut carry);
+    let r10 = ::mac_with_carry(r10, a[5], b[5], &mut carry);
+    let r11 = carry;
+    [r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11]
+}
+
+/// Returns the square of `a`, computed by the portable arithmetic.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+pub(super) fn square_portable(a: &[u64; 6]) -> [u64; 12] {
+    let mut carry = 0;
+    let r1 = ::mac_with_carry(0, a[0], a[1], &mut carry);
+    let r2 = ::mac_with_carry(0, a[0], a[2], &mut carry);
+    let r3 = ::mac_with_carry(0, a[0], a[3], &mut carry);
+    let r4 = ::mac_with_carry(0, a[0], a[4], &mut carry);
+    let r5 = ::mac_with_carry(0, a[0], a[5], &mut carry);
+    let r6 = carry;
+    let mut carry = 0;
+    let r3 = ::mac_with_carry(r3, a[1], a[2], &mut carry);
+    let r4 = ::mac_with_carry(r4, a[1], a[3], &mut carry);
+    let r5 = ::mac_with_carry(r5, a[1], a[4], &mut carry);
+    let r6 = ::mac_with_carry(r6, a[1], a[5], &mut carry);
+    let r7 = carry;
+    let mut carry = 0;
+    let r5 = ::mac_with_carry(r5, a[2], a[3], &mut carry);
+    let r6 = ::mac_with_carry(r6, a[2], a[4], &mut carry);
+    let r7 = ::mac_with_carry(r7, a[2], a[5], &mut carry);
+    let r8 = carry;
+    let mut carry = 0;
+    let r7 = ::mac_with_carry(r7, a[3], a[4], &mut carry);
+    let r8 = ::mac_with_carry(r8, a[3], a[5], &mut carry);
+    let r9 = carry;
+    let mut carry = 0;
+    let r9 = ::mac_with_carry(r9, a[4], a[5], &mut carry);
+    let r10 = carry;
+
+    let r11 = r10 >> 63;
+    let r10 = (r10 << 1) | (r9 >> 63);
+    let r9 = (r9 << 1) | (r8 >> 63);
+    let r8 = (r8 << 1) | (r7 >> 63);
+    let r7 = (r7 << 1) | (r6 >> 63);
+    let r6 = (r6 << 1) | (r5 >> 63);
+    let r5 = (r5 << 1) | (r4 >> 63);
+    let r4 = (r4 << 1) | (r3 >> 63);
+    let r3 = (r3 << 1) | (r2 >> 63);
+    let r2 = (r2 << 1) | (r1 >> 63);
+    let r1 = r1 << 1;
+
+    let mut carry = 0;
+    let r0 = ::mac_with_carry(0, a[0], a[0], &mut carry);
+    let r1 = ::adc(r1, 0, &mut carry);
+    let r2 = ::mac_with_carry(r2, a[1], a[1], &mut carry);
+    let r3 = ::adc(r3, 0, &mut carry);
+    let r4 = ::mac_with_carry(r4, a[2], a[2], &mut carry);
+    let r5 = ::adc(r5, 0, &mut carry);
+    let r6 = ::mac_with_carry(r6, a[3], a[3], &mut carry);
+    let r7 = ::adc(r7, 0, &mut carry);
+    let r8 = ::mac_with_carry(r8, a[4], a[4], &mut carry);
+    let r9 = ::adc(r9, 0, &mut carry);
+    let r10 = ::mac_with_carry(r10, a[5], a[5], &mut carry);
+    let r11 = ::adc(r11, 0, &mut carry);
+    [r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11]
+}
+
+/// Performs Montgomery reduction of `t` by the portable arithmetic,
+/// leaving a result that may exceed the modulus by one multiple of it.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+pub(super) fn mont_reduce_portable(t: [u32; 24]) -> [u32; 12] {
+    let mut res = [0; 12];
+    limb32::mont_reduce(&t, &MODULUS_LIMBS.0, INV, &mut res);
+    res
+}
+
+/// Performs Montgomery reduction of `t` by the portable arithmetic,
+/// leaving a result that may exceed the modulus by one multiple of it.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+pub(super) fn mont_reduce_portable(t: [u64; 12]) -> [u64; 6] {
+    let [r0, mut r1, mut r2, mut r3, mut r4, mut r5, mut r6, mut r7, mut r8, mut r9, mut r10, mut r11] =
+        t;
+
+    // The Montgomery reduction here is based on Algorithm 14.32 in
+    // Handbook of Applied Cryptography
+    // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
+
+    let k = r0.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r0, k, MODULUS.0[0], &mut carry);
+    r1 = ::mac_with_carry(r1, k, MODULUS.0[1], &mut carry);
+    r2 = ::mac_with_carry(r2, k, MODULUS.0[2], &mut carry);
+    r3 = ::mac_with_carry(r3, k, MODULUS.0[3], &mut carry);
+    r4 = ::mac_with_carry(r4, k, MODULUS.0[4], &mut carry);
+    r5 = ::mac_with_carry(r5, k, MODULUS.0[5], &mut carry);
+    r6 = ::adc(r6, 0, &mut carry);
+    let carry2 = carry;
+    let k = r1.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r1, k, MODULUS.0[0], &mut carry);
+    r2 = ::mac_with_carry(r2, k, MODULUS.0[1], &mut carry);
+    r3 = ::mac_with_carry(r3, k, MODULUS.0[2], &mut carry);
+    r4 = ::mac_with_carry(r4, k, MODULUS.0[3], &mut carry);
+    r5 = ::mac_with_carry(r5, k, MODULUS.0[4], &mut carry);
+    r6 = ::mac_with_carry(r6, k, MODULUS.0[5], &mut carry);
+    r7 = ::adc(r7, carry2, &mut carry);
+    let carry2 = carry;
+    let k = r2.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r2, k, MODULUS.0[0], &mut carry);
+    r3 = ::mac_with_carry(r3, k, MODULUS.0[1], &mut carry);
+    r4 = ::mac_with_carry(r4, k, MODULUS.0[2], &mut carry);
+    r5 = ::mac_with_carry(r5, k, MODULUS.0[3], &mut carry);
+    r6 = ::mac_with_carry(r6, k, MODULUS.0[4], &mut carry);
+    r7 = ::mac_with_carry(r7, k, MODULUS.0[5], &mut carry);
+    r8 = ::adc(r8, carry2, &mut carry);
+    let carry2 = carry;
+    let k = r3.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r3, k, MODULUS.0[0], &mut carry);
+    r4 = ::mac_with_carry(r4, k, MODULUS.0[1], &mut carry);
+    r5 = ::mac_with_carry(r5, k, MODULUS.0[2], &mut carry);
+    r6 = ::mac_with_carry(r6, k, MODULUS.0[3], &mut carry);
+    r7 = ::mac_with_carry(r7, k, MODULUS.0[4], &mut carry);
+    r8 = ::mac_with_carry(r8, k, MODULUS.0[5], &mut carry);
+    r9 = ::adc(r9, carry2, &mut carry);
+    let carry2 = carry;
+    let k = r4.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r4, k, MODULUS.0[0], &mut carry);
+    r5 = ::mac_with_carry(r5, k, MODULUS.0[1], &mut carry);
+    r6 = ::mac_with_carry(r6, k, MODULUS.0[2], &mut carry);
+    r7 = ::mac_with_carry(r7, k, MODULUS.0[3], &mut carry);
+    r8 = ::mac_with_carry(r8, k, MODULUS.0[4], &mut carry);
+    r9 = ::mac_with_carry(r9, k, MODULUS.0[5], &mut carry);
+    r10 = ::adc(r10, carry2, &mut carry);
+    let carry2 = carry;
+    let k = r5.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r5, k, MODULUS.0[0], &mut carry);
+    r6 = ::mac_with_carry(r6, k, MODULUS.0[1], &mut carry);
+    r7 = ::mac_with_carry(r7, k, MODULUS.0[2], &mut carry);
+    r8 = ::mac_with_carry(r8, k, MODULUS.0[3], &mut carry);
+    r9 = ::mac_with_carry(r9, k, MODULUS.0[4], &mut carry);
+    r10 = ::mac_with_carry(r10, k, MODULUS.0[5], &mut carry);
+    r11 = ::adc(r11, carry2, &mut carry);
+    [r6, r7, r8, r9, r10, r11]
+}
+
 impl SqrtField for Fq {
     fn legendre(&self) -> ::LegendreSymbol {
         use LegendreSymbol::*;
 
-        // s = self^((q - 1) // 2)
-        let s = self.pow([
-            0xdcff7fffffffd555,
-            0xf55ffff58a9ffff,
-            0xb39869507b587b12,
-            0xb23ba5c279c2895f,
-            0x258dd3db21a5d66b,
-            0xd0088f51cbff34d,
-        ]);
-        if s == Fq::zero() {
-            Zero
-        } else if s == Fq::one() {
-            QuadraticResidue
-        } else {
-            QuadraticNonResidue
+        // The Montgomery form is this element times 2^384, a square, so it
+        // has the same symbol.
+        match repr(self.0).jacobi(&MODULUS) {
+            0 => Zero,
+            1 => QuadraticResidue,
+            _ => QuadraticNonResidue,
         }
     }
 
     fn sqrt(&self) -> Option<Self> {
+        count_op!(fq.sqrt);
         // Shank's algorithm for q mod 4 = 3
         // https://eprint.iacr.org/2012/685.pdf (page 9, algorithm 2)
 
@@ -1170,6 +1524,63 @@ impl SqrtField for Fq {
     }
 }
 
+#[cfg(feature = "constant-time")]
+impl ConstantTimeEq for Fq {
+    fn ct_eq(&self, other: &Fq) -> Choice {
+        (self.0).0.ct_eq(&(other.0).0)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl ConditionallySelectable for Fq {
+    fn conditional_select(a: &Fq, b: &Fq, choice: Choice) -> Fq {
+        let mut res = a.0;
+        for (r, b) in res.0.iter_mut().zip((b.0).0.iter()) {
+            *r = Limb::conditional_select(r, b, choice);
+        }
+
+        Fq(res)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl CtField for Fq {
+    fn ct_inverse(&self) -> CtOption<Self> {
+        count_op!(fq.inverse);
+        // By Fermat's little theorem, self^(q - 2) is the inverse of any
+        // nonzero element.
+        let mut exp = MODULUS;
+        exp.sub_noborrow(&FqRepr::from(2));
+
+        CtOption::new(self.pow(exp), !self.ct_is_zero())
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl CtSqrtField for Fq {
+    fn ct_sqrt(&self) -> CtOption<Self> {
+        count_op!(fq.sqrt);
+        // As q = 3 mod 4, self^((q + 1) // 4) is a square root of self
+        // whenever one exists.
+
+        // a1 = self^((q - 3) // 4)
+        let mut a1 = self.pow([
+            0xee7fbfffffffeaaa,
+            0x7aaffffac54ffff,
+            0xd9cc34a83dac3d89,
+            0xd91dd2e13ce144af,
+            0x92c6e9ed90d2eb35,
+            0x680447a8e5ff9a6,
+        ]);
+        a1.mul_assign(self);
+
+        let mut check = a1;
+        check.square();
+
+        CtOption::new(a1, check.ct_eq(self))
+    }
+}
+
 #[test]
 fn test_b_coeff() {
     assert_eq!(Fq::from_repr(FqRepr::from(4)).unwrap(), B_COEFF);
@@ -2289,13 +2700,13 @@ fn test_fq_repr_add_nocarry() {
 
 #[test]
 fn test_fq_is_valid() {
-    let mut a = Fq(MODULUS);
+    let mut a = Fq(limbs(MODULUS.0));
     assert!(!a.is_valid());
-    a.0.sub_noborrow(&FqRepr::from(1));
+    a.0.sub_noborrow(&limbs([1, 0, 0, 0, 0, 0]));
     assert!(a.is_valid());
-    assert!(Fq(FqRepr::from(0)).is_valid());
+    assert!(Fq(FqLimbs::default()).is_valid());
     assert!(
-        Fq(FqRepr([
+        Fq(limbs([
             0xdf4671abd14dab3e,
             0xe2dc0c9f534fbd33,
             0x31ca6c880cc444a6,
@@ -2304,7 +2715,7 @@ fn test_fq_is_valid() {
             0x17c8be1800b9f059
         ])).is_valid()
     );
-    assert!(!Fq(FqRepr([
+    assert!(!Fq(limbs([
         0xffffffffffffffff,
         0xffffffffffffffff,
         0xffffffffffffffff,
@@ -2325,7 +2736,7 @@ fn test_fq_is_valid() {
 fn test_fq_add_assign() {
     {
         // Random number
-        let mut tmp = Fq(FqRepr([
+        let mut tmp = Fq(limbs([
             0x624434821df92b69,
             0x503260c04fd2e2ea,
             0xd9df726e0d16e8ce,
@@ -2335,10 +2746,10 @@ fn test_fq_add_assign() {
         ]));
         assert!(tmp.is_valid());
         // Test that adding zero has no effect.
-        tmp.add_assign(&Fq(FqRepr::from(0)));
+        tmp.add_assign(&Fq(FqLimbs::default()));
         assert_eq!(
             tmp,
-            Fq(FqRepr([
+            Fq(limbs([
                 0x624434821df92b69,
                 0x503260c04fd2e2ea,
                 0xd9df726e0d16e8ce,
@@ -2348,10 +2759,10 @@ fn test_fq_add_assign() {
             ]))
         );
         // Add one and test for the result.
-        tmp.add_assign(&Fq(FqRepr::from(1)));
+        tmp.add_assign(&Fq(limbs([1, 0, 0, 0, 0, 0])));
         assert_eq!(
             tmp,
-            Fq(FqRepr([
+            Fq(limbs([
                 0x624434821df92b6a,
                 0x503260c04fd2e2ea,
                 0xd9df726e0d16e8ce,
@@ -2361,7 +2772,7 @@ fn test_fq_add_assign() {
             ]))
         );
         // Add another random number that exercises the reduction.
-        tmp.add_assign(&Fq(FqRepr([
+        tmp.add_assign(&Fq(limbs([
             0x374d8f8ea7a648d8,
             0xe318bb0ebb8bfa9b,
             0x613d996f0a95b400,
@@ -2371,7 +2782,7 @@ fn test_fq_add_assign() {
         ])));
         assert_eq!(
             tmp,
-            Fq(FqRepr([
+            Fq(limbs([
                 0xdf92c410c59fc997,
                 0x149f1bd05a0add85,
                 0xd3ec393c20fba6ab,
@@ -2381,7 +2792,7 @@ fn test_fq_add_assign() {
             ]))
         );
         // Add one to (q - 1) and test for the result.
-        tmp = Fq(FqRepr([
+        tmp = Fq(limbs([
             0xb9feffffffffaaaa,
             0x1eabfffeb153ffff,
             0x6730d2a0f6b0f624,
@@ -2389,10 +2800,10 @@ fn test_fq_add_assign() {
             0x4b1ba7b6434bacd7,
             0x1a0111ea397fe69a,
         ]));
-        tmp.add_assign(&Fq(FqRepr::from(1)));
+        tmp.add_assign(&Fq(limbs([1, 0, 0, 0, 0, 0])));
         assert!(tmp.0.is_zero());
         // Add a random number to another one such that the result is q - 1
-        tmp = Fq(FqRepr([
+        tmp = Fq(limbs([
             0x531221a410efc95b,
             0x72819306027e9717,
             0x5ecefb937068b746,
@@ -2400,7 +2811,7 @@ fn test_fq_add_assign() {
             0xdc35c51158644588,
             0xb2d176c04f2100,
         ]));
-        tmp.add_assign(&Fq(FqRepr([
+        tmp.add_assign(&Fq(limbs([
             0x66ecde5bef0fe14f,
             0xac2a6cf8aed568e8,
             0x861d70d86483edd,
@@ -2410,7 +2821,7 @@ fn test_fq_add_assign() {
         ])));
         assert_eq!(
             tmp,
-            Fq(FqRepr([
+            Fq(limbs([
                 0xb9feffffffffaaaa,
                 0x1eabfffeb153ffff,
                 0x6730d2a0f6b0f624,
@@ -2420,7 +2831,7 @@ fn test_fq_add_assign() {
             ]))
         );
         // Add one to the result and test for it.
-        tmp.add_assign(&Fq(FqRepr::from(1)));
+        tmp.add_assign(&Fq(limbs([1, 0, 0, 0, 0, 0])));
         assert!(tmp.0.is_zero());
     }
 
@@ -2452,7 +2863,7 @@ fn test_fq_add_assign() {
 fn test_fq_sub_assign() {
     {
         // Test arbitrary subtraction that tests reduction.
-        let mut tmp = Fq(FqRepr([
+        let mut tmp = Fq(limbs([
             0x531221a410efc95b,
             0x72819306027e9717,
             0x5ecefb937068b746,
@@ -2460,7 +2871,7 @@ fn test_fq_sub_assign() {
             0xdc35c51158644588,
             0xb2d176c04f2100,
         ]));
-        tmp.sub_assign(&Fq(FqRepr([
+        tmp.sub_assign(&Fq(limbs([
             0x98910d20877e4ada,
             0x940c983013f4b8ba,
             0xf677dc9b8345ba33,
@@ -2470,7 +2881,7 @@ fn test_fq_sub_assign() {
         ])));
         assert_eq!(
             tmp,
-            Fq(FqRepr([
+            Fq(limbs([
                 0x748014838971292c,
                 0xfd20fad49fddde5c,
                 0xcf87f198e3d3f336,
@@ -2481,7 +2892,7 @@ fn test_fq_sub_assign() {
         );
 
         // Test the opposite subtraction which doesn't test reduction.
-        tmp = Fq(FqRepr([
+        tmp = Fq(limbs([
             0x98910d20877e4ada,
             0x940c983013f4b8ba,
             0xf677dc9b8345ba33,
@@ -2489,7 +2900,7 @@ fn test_fq_sub_assign() {
             0xe1ae288ac3222c44,
             0x5968bb602790806,
         ]));
-        tmp.sub_assign(&Fq(FqRepr([
+        tmp.sub_assign(&Fq(limbs([
             0x531221a410efc95b,
             0x72819306027e9717,
             0x5ecefb937068b746,
@@ -2499,7 +2910,7 @@ fn test_fq_sub_assign() {
         ])));
         assert_eq!(
             tmp,
-            Fq(FqRepr([
+            Fq(limbs([
                 0x457eeb7c768e817f,
                 0x218b052a117621a3,
                 0x97a8e10812dd02ed,
@@ -2510,11 +2921,11 @@ fn test_fq_sub_assign() {
         );
 
         // Test for sensible results with zero
-        tmp = Fq(FqRepr::from(0));
-        tmp.sub_assign(&Fq(FqRepr::from(0)));
+        tmp = Fq(FqLimbs::default());
+        tmp.sub_assign(&Fq(FqLimbs::default()));
         assert!(tmp.is_zero());
 
-        tmp = Fq(FqRepr([
+        tmp = Fq(limbs([
             0x98910d20877e4ada,
             0x940c983013f4b8ba,
             0xf677dc9b8345ba33,
@@ -2522,10 +2933,10 @@ fn test_fq_sub_assign() {
             0xe1ae288ac3222c44,
             0x5968bb602790806,
         ]));
-        tmp.sub_assign(&Fq(FqRepr::from(0)));
+        tmp.sub_assign(&Fq(FqLimbs::default()));
         assert_eq!(
             tmp,
-            Fq(FqRepr([
+            Fq(limbs([
                 0x98910d20877e4ada,
                 0x940c983013f4b8ba,
                 0xf677dc9b8345ba33,
@@ -2556,7 +2967,7 @@ fn test_fq_sub_assign() {
 
 #[test]
 fn test_fq_mul_assign() {
-    let mut tmp = Fq(FqRepr([
+    let mut tmp = Fq(limbs([
         0xcc6200000020aa8a,
         0x422800801dd8001a,
         0x7f4f5e619041c62c,
@@ -2564,7 +2975,7 @@ fn test_fq_mul_assign() {
         0x3f69cc3a3d07d58b,
         0xb972455fd09b8ef,
     ]));
-    tmp.mul_assign(&Fq(FqRepr([
+    tmp.mul_assign(&Fq(limbs([
         0x329300000030ffcf,
         0x633c00c02cc40028,
         0xbef70d925862a942,
@@ -2573,7 +2984,7 @@ fn test_fq_mul_assign() {
         0x1162b680fb8e9566,
     ])));
     assert!(
-        tmp == Fq(FqRepr([
+        tmp == Fq(limbs([
             0x9dc4000001ebfe14,
             0x2850078997b00193,
             0xa8197f1abb4d7bf,
@@ -2628,7 +3039,7 @@ fn test_fq_mul_assign() {
 
 #[test]
 fn test_fq_squaring() {
-    let mut a = Fq(FqRepr([
+    let mut a = Fq(limbs([
         0xffffffffffffffff,
         0xffffffffffffffff,
         0xffffffffffffffff,
@@ -2870,6 +3281,60 @@ fn test_fq_display() {
     );
 }
 
+#[test]
+fn test_fq_hex_and_decimal_formatting() {
+    let a = Fq::from_repr(FqRepr([0xe28e79396ac2bbf8, 0x413f6f7f06ea87eb, 0xa4b62af4a792a689, 0xb7f89f88f59c1dc5, 0x9a551859b1e43a9a, 0x6c9f5a1060de974])).unwrap();
+
+    assert_eq!(
+        format!("{:x}", a),
+        "6c9f5a1060de9749a551859b1e43a9ab7f89f88f59c1dc5a4b62af4a792a689413f6f7f06ea87ebe28e79396ac2bbf8"
+    );
+    assert_eq!(
+        format!("{:#X}", a),
+        "0x6C9F5A1060DE9749A551859B1E43A9AB7F89F88F59C1DC5A4B62AF4A792A689413F6F7F06EA87EBE28E79396AC2BBF8"
+    );
+    // Zero-padded to the width of the modulus, this matches `Display`.
+    assert_eq!(format!("Fq({:#098x})", a), format!("{}", a));
+    assert_eq!(
+        a.to_str_radix(10),
+        "1044907997530057200127964133840438908938125194297122100873630793970783085678854211057958467594702010059735555030008"
+    );
+    assert_eq!(format!("{:#x}", Fq::zero()), "0x0");
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_fq_constants_bigint() {
+    let q = BigUint::parse_bytes(b"4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787", 10).unwrap();
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+
+    assert_eq!(Fq::modulus_biguint(), q);
+    assert_eq!(q.bits(), u64::from(MODULUS_BITS));
+    assert_eq!(BigUint::from(R), (&one << 384) % &q);
+    assert_eq!(BigUint::from(R2), (&one << 768) % &q);
+    assert_eq!(
+        (BigUint::from(INV) * &q + 1u32) % (&one << 64),
+        BigUint::from(0u32)
+    );
+    assert_eq!(BigUint::from(HALF), (&q + 1u32) / 2u32);
+    assert_eq!(BigUint::from(B_COEFF), BigUint::from(4u32));
+
+    let t = (&q - 1u32) >> S;
+    assert!(t.bit(0));
+    assert_eq!(BigUint::from(Fq(limbs(GENERATOR.0))), two);
+    assert_eq!(BigUint::from(Fq(limbs(ROOT_OF_UNITY.0))), two.modpow(&t, &q));
+
+    assert_eq!(
+        BigUint::from(G1_GENERATOR_X),
+        BigUint::parse_bytes(b"3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507", 10).unwrap()
+    );
+    assert_eq!(
+        BigUint::from(G1_GENERATOR_Y),
+        BigUint::parse_bytes(b"1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569", 10).unwrap()
+    );
+}
+
 #[test]
 fn test_fq_num_bits() {
     assert_eq!(Fq::NUM_BITS, 381);
@@ -2904,6 +3369,18 @@ fn fq_field_tests() {
     ::tests::field::random_sqrt_tests::<Fq>();
     ::tests::field::random_frobenius_tests::<Fq, _>(Fq::char(), 13);
     ::tests::field::from_str_tests::<Fq>();
+    ::tests::field::from_str_radix_tests::<Fq>();
+    ::tests::field::random_encoding_tests::<Fq>();
+    #[cfg(feature = "bigint")]
+    ::tests::field::random_bigint_tests::<Fq>();
+    #[cfg(feature = "constant-time")]
+    ::tests::field::random_ct_tests::<Fq>();
+    #[cfg(feature = "constant-time")]
+    ::tests::field::random_ct_sqrt_tests::<Fq>();
+    ::tests::field::random_operator_tests::<Fq>();
+    ::tests::field::from_bytes_reduce_tests::<Fq>();
+    ::tests::fft::random_fft_tests::<Fq>();
+    ::tests::poly::random_poly_tests::<Fq>();
 }
 
 #[test]
@@ -2920,6 +3397,7 @@ fn test_fq_ordering() {
 #[test]
 fn fq_repr_tests() {
     ::tests::repr::random_repr_tests::<FqRepr>();
+    ::tests::repr::random_from_wide_repr_tests::<Fq>();
 }
 
 #[test]
@@ -2957,3 +3435,24 @@ fn test_fq_legendre() {
     ]);
     assert_eq!(QuadraticResidue, Fq::from_repr(e).unwrap().legendre());
 }
+
+#[test]
+fn test_fq_legendre_reference() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let mut samples = vec![Fq::zero(), Fq::one(), Fq::multiplicative_generator()];
+    // Elements whose Montgomery forms are just below the modulus.
+    for k in 1..100 {
+        let mut r = MODULUS;
+        r.sub_noborrow(&FqRepr::from(k));
+        samples.push(Fq(limbs(r.0)));
+    }
+    samples.extend((0..1000).map(|_| Fq::rand(&mut rng)));
+
+    for a in samples {
+        assert_eq!(a.legendre(), a.legendre_reference());
+        let mut b = a;
+        b.square();
+        assert_eq!(b.legendre(), b.legendre_reference());
+    }
+}
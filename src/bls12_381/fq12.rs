@@ -1,8 +1,12 @@
-use super::fq::FROBENIUS_COEFF_FQ12_C1;
+use super::fq::{parse_components, FROBENIUS_COEFF_FQ12_C1};
 use super::fq2::Fq2;
 use super::fq6::Fq6;
 use rand::{Rand, Rng};
-use Field;
+use {check_encoded_len, BitIterator, Field, FieldEncoding, PrimeFieldDecodingError};
+#[cfg(feature = "constant-time")]
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "constant-time")]
+use CtField;
 
 /// An element of Fq12, represented by c0 + c1 * w.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -17,6 +21,30 @@ impl ::std::fmt::Display for Fq12 {
     }
 }
 
+impl FieldEncoding for Fq12 {
+    fn encoded_len() -> usize {
+        2 * Fq6::encoded_len()
+    }
+
+    fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.c1.to_bytes_be();
+        bytes.extend(self.c0.to_bytes_be());
+        bytes
+    }
+
+    fn from_bytes_be(bytes: &[u8]) -> Result<Self, PrimeFieldDecodingError> {
+        check_encoded_len::<Self>(bytes)?;
+
+        let (c1, c0) = bytes.split_at(Fq6::encoded_len());
+        Ok(Fq12 {
+            c0: Fq6::from_bytes_be(c0)?,
+            c1: Fq6::from_bytes_be(c1)?,
+        })
+    }
+}
+
+impl_field_ops!(Fq12);
+
 impl Rand for Fq12 {
     fn rand<R: Rng>(rng: &mut R) -> Self {
         Fq12 {
@@ -31,6 +59,20 @@ impl Fq12 {
         self.c1.negate();
     }
 
+    /// Parses the list of twelve `Fq` components `c0.c0.c0, c0.c0.c1, ...,
+    /// c1.c2.c1` in the given radix, separated as for `Fq2::from_str_radix`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Fq12> {
+        let c = parse_components(s, radix)?;
+        if c.len() != 12 {
+            return None;
+        }
+
+        Some(Fq12 {
+            c0: Fq6::from_components(&c[..6]),
+            c1: Fq6::from_components(&c[6..]),
+        })
+    }
+
     pub fn mul_by_014(&mut self, c0: &Fq2, c1: &Fq2, c4: &Fq2) {
         let mut aa = self.c0;
         aa.mul_by_01(c0, c1);
@@ -46,6 +88,224 @@ impl Fq12 {
         self.c0.mul_by_nonresidue();
         self.c0.add_assign(&aa);
     }
+
+    /// Determines if this element lies in the cyclotomic subgroup of order
+    /// `q^4 - q^2 + 1`, by checking that `self^(q^4) * self = self^(q^2)`.
+    pub fn is_cyclotomic(&self) -> bool {
+        if self.is_zero() {
+            return false;
+        }
+
+        let mut lhs = *self;
+        lhs.frobenius_map(4);
+        lhs.mul_assign(self);
+
+        let mut rhs = *self;
+        rhs.frobenius_map(2);
+
+        lhs == rhs
+    }
+
+    /// Compresses this element of the torus T2(Fq6), the subgroup of norm
+    /// one elements over Fq6 which contains the cyclotomic subgroup, into
+    /// a single `Fq6` element `g` such that `self = (g + w) / (g - w)`.
+    ///
+    /// Returns `None` if this element is 1 or -1, which have no such
+    /// representation. The result is meaningless for elements outside of
+    /// the torus.
+    pub fn compress_t2(&self) -> Option<Fq6> {
+        // g = (1 + c0) / c1
+        self.c1.inverse().map(|mut g| {
+            let mut c0 = self.c0;
+            c0.add_assign(&Fq6::one());
+            g.mul_assign(&c0);
+            g
+        })
+    }
+
+    /// Decompresses an element of the torus T2(Fq6) from its representation
+    /// `g`, computing `(g + w) / (g - w)`. Every `g` yields an element of
+    /// norm one over Fq6, but not necessarily of the cyclotomic subgroup.
+    pub fn decompress_t2(g: &Fq6) -> Self {
+        // (g + w) / (g - w) = (g^2 + v + 2 g w) / (g^2 - v), where g^2 - v
+        // is never zero because v is not a square in Fq6.
+        let mut g2 = *g;
+        g2.square();
+        let mut v = Fq6::zero();
+        v.c1 = Fq2::one();
+
+        let mut d = g2;
+        d.sub_assign(&v);
+        let d = d.inverse().unwrap();
+
+        let mut c0 = g2;
+        c0.add_assign(&v);
+        c0.mul_assign(&d);
+        let mut c1 = *g;
+        c1.double();
+        c1.mul_assign(&d);
+
+        Fq12 { c0, c1 }
+    }
+
+    /// Compresses this element of the cyclotomic subgroup into two `Fq2`
+    /// elements, a third of its size, in the manner of CEILIDH. This drops
+    /// the constant coefficient of the T2 representation `g`, which is
+    /// recovered by `decompress_t6` from the condition that the norm of
+    /// `g + w` down to Fq4 lies in Fq2.
+    ///
+    /// Returns `None` for the elements which have no T2 representation,
+    /// and for those whose T2 representation has a zero `v` coefficient.
+    /// The result is meaningless for elements outside of the cyclotomic
+    /// subgroup.
+    pub fn compress_t6(&self) -> Option<(Fq2, Fq2)> {
+        self.compress_t2()
+            .and_then(|g| if g.c1.is_zero() { None } else { Some((g.c1, g.c2)) })
+    }
+
+    /// Decompresses an element of the cyclotomic subgroup compressed by
+    /// `compress_t6`, returning `None` if `g1` is zero. Every nonzero `g1`
+    /// yields an element of the cyclotomic subgroup, but not necessarily
+    /// of the subgroup of order `r`.
+    pub fn decompress_t6(g1: &Fq2, g2: &Fq2) -> Option<Self> {
+        // With g = g0 + g1 * v + g2 * v^2, the coefficient of v * w in
+        // the norm N(g + w) = (g + w)^(1 + q^4 + q^8) is e0 - 3 * g0 * g1,
+        // where e0 is that coefficient for g0 = 0. It vanishes exactly when
+        // the norm lies in Fq2, which determines g0.
+        let mut three_g1 = *g1;
+        three_g1.double();
+        three_g1.add_assign(g1);
+
+        three_g1.inverse().map(|t| {
+            let h = Fq12 {
+                c0: Fq6 {
+                    c0: Fq2::zero(),
+                    c1: *g1,
+                    c2: *g2,
+                },
+                c1: Fq6::one(),
+            };
+            let mut h4 = h;
+            h4.frobenius_map(4);
+            let mut h8 = h;
+            h8.frobenius_map(8);
+            let mut norm = h;
+            norm.mul_assign(&h4);
+            norm.mul_assign(&h8);
+
+            let mut g0 = norm.c1.c1;
+            g0.mul_assign(&t);
+
+            Self::decompress_t2(&Fq6 {
+                c0: g0,
+                c1: *g1,
+                c2: *g2,
+            })
+        })
+    }
+
+    /// Squares this element, assuming it lies in the cyclotomic subgroup
+    /// of order `q^4 - q^2 + 1`, such as the output of the easy part of the
+    /// final exponentiation. The result is meaningless for other elements.
+    pub fn cyclotomic_square(&mut self) {
+        // Granger-Scott squaring, https://eprint.iacr.org/2009/565.pdf
+        // Fq12 is viewed as a cubic extension of Fq4 = Fq2[w^3], so that
+        // the pairs (z0, z1), (z2, z3) and (z4, z5) are Fq4 elements.
+        fn fq4_square(a: &Fq2, b: &Fq2) -> (Fq2, Fq2) {
+            let mut t0 = *a;
+            t0.square();
+            let mut t1 = *b;
+            t1.square();
+
+            let mut c0 = t1;
+            c0.mul_by_nonresidue();
+            c0.add_assign(&t0);
+
+            let mut c1 = *a;
+            c1.add_assign(b);
+            c1.square();
+            c1.sub_assign(&t0);
+            c1.sub_assign(&t1);
+
+            (c0, c1)
+        }
+
+        let mut z0 = self.c0.c0;
+        let mut z4 = self.c0.c1;
+        let mut z3 = self.c0.c2;
+        let mut z2 = self.c1.c0;
+        let mut z1 = self.c1.c1;
+        let mut z5 = self.c1.c2;
+
+        let (t0, t1) = fq4_square(&z0, &z1);
+
+        // z0 = 3 * t0 - 2 * z0
+        z0.negate();
+        z0.add_assign(&t0);
+        z0.double();
+        z0.add_assign(&t0);
+
+        // z1 = 3 * t1 + 2 * z1
+        z1.add_assign(&t1);
+        z1.double();
+        z1.add_assign(&t1);
+
+        let (t0, t1) = fq4_square(&z2, &z3);
+        let (t2, mut t3) = fq4_square(&z4, &z5);
+
+        // z4 = 3 * t0 - 2 * z4
+        z4.negate();
+        z4.add_assign(&t0);
+        z4.double();
+        z4.add_assign(&t0);
+
+        // z5 = 3 * t1 + 2 * z5
+        z5.add_assign(&t1);
+        z5.double();
+        z5.add_assign(&t1);
+
+        // z2 = 3 * t3 * nonresidue + 2 * z2
+        t3.mul_by_nonresidue();
+        z2.add_assign(&t3);
+        z2.double();
+        z2.add_assign(&t3);
+
+        // z3 = 3 * t2 - 2 * z3
+        z3.negate();
+        z3.add_assign(&t2);
+        z3.double();
+        z3.add_assign(&t2);
+
+        self.c0.c0 = z0;
+        self.c0.c1 = z4;
+        self.c0.c2 = z3;
+        self.c1.c0 = z2;
+        self.c1.c1 = z1;
+        self.c1.c2 = z5;
+    }
+
+    /// Exponentiates this element by a number represented with `u64` limbs,
+    /// least significant digit first, using cyclotomic squaring. As with
+    /// `cyclotomic_square`, this element must lie in the cyclotomic subgroup.
+    pub fn cyclotomic_exp<S: AsRef<[u64]>>(&self, exp: S) -> Self {
+        let mut res = Self::one();
+
+        let mut found_one = false;
+
+        for i in BitIterator::new(exp) {
+            if found_one {
+                res.cyclotomic_square();
+            } else {
+                found_one = i;
+            }
+
+            if i {
+                res.mul_assign(self);
+            }
+        }
+
+        res
+    }
 }
 
 impl Field for Fq12 {
@@ -68,6 +328,7 @@ impl Field for Fq12 {
     }
 
     fn double(&mut self) {
+        count_op!(fq12.add);
         self.c0.double();
         self.c1.double();
     }
@@ -78,11 +339,13 @@ impl Field for Fq12 {
     }
 
     fn add_assign(&mut self, other: &Self) {
+        count_op!(fq12.add);
         self.c0.add_assign(&other.c0);
         self.c1.add_assign(&other.c1);
     }
 
     fn sub_assign(&mut self, other: &Self) {
+        count_op!(fq12.add);
         self.c0.sub_assign(&other.c0);
         self.c1.sub_assign(&other.c1);
     }
@@ -97,6 +360,7 @@ impl Field for Fq12 {
     }
 
     fn square(&mut self) {
+        count_op!(fq12.square);
         let mut ab = self.c0;
         ab.mul_assign(&self.c1);
         let mut c0c1 = self.c0;
@@ -114,6 +378,7 @@ impl Field for Fq12 {
     }
 
     fn mul_assign(&mut self, other: &Self) {
+        count_op!(fq12.mul);
         let mut aa = self.c0;
         aa.mul_assign(&other.c0);
         let mut bb = self.c1;
@@ -130,6 +395,7 @@ impl Field for Fq12 {
     }
 
     fn inverse(&self) -> Option<Self> {
+        count_op!(fq12.inverse);
         let mut c0s = self.c0;
         c0s.square();
         let mut c1s = self.c1;
@@ -146,6 +412,94 @@ impl Field for Fq12 {
             tmp
         })
     }
+
+    fn batch_invert_with_scratch(v: &mut [Self], scratch: &mut Vec<Self>) {
+        // The inverse of an element is its conjugate divided by its norm in
+        // Fq6, whose inverse is its adjugate divided by its norm in Fq2, so
+        // only norms in Fq2 need to be inverted. Each entry of `scratch`
+        // holds the adjugate of the norm of an element in Fq6, followed by
+        // the product of the preceding norms in Fq2 and its norm in Fq2.
+        scratch.clear();
+        let mut acc = Fq2::one();
+        for e in v.iter() {
+            let mut c0s = e.c0;
+            c0s.square();
+            let mut c1s = e.c1;
+            c1s.square();
+            c1s.mul_by_nonresidue();
+            c0s.sub_assign(&c1s);
+
+            let (adjugate, norm) = c0s.inverse_parts();
+            scratch.push(Fq12 {
+                c0: adjugate,
+                c1: Fq6 {
+                    c0: acc,
+                    c1: norm,
+                    c2: Fq2::zero(),
+                },
+            });
+            acc.mul_assign(&norm);
+        }
+
+        let mut acc = acc.inverse().expect("cannot invert zero");
+
+        for (e, s) in v.iter_mut().zip(scratch.iter()).rev() {
+            let mut t = acc;
+            t.mul_assign(&s.c1.c0);
+            acc.mul_assign(&s.c1.c1);
+
+            // The inverse of the norm in Fq6.
+            let mut inv = s.c0;
+            inv.c0.mul_assign(&t);
+            inv.c1.mul_assign(&t);
+            inv.c2.mul_assign(&t);
+
+            e.c0.mul_assign(&inv);
+            e.c1.mul_assign(&inv);
+            e.c1.negate();
+        }
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl ConstantTimeEq for Fq12 {
+    fn ct_eq(&self, other: &Fq12) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl ConditionallySelectable for Fq12 {
+    fn conditional_select(a: &Fq12, b: &Fq12, choice: Choice) -> Fq12 {
+        Fq12 {
+            c0: Fq6::conditional_select(&a.c0, &b.c0, choice),
+            c1: Fq6::conditional_select(&a.c1, &b.c1, choice),
+        }
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl CtField for Fq12 {
+    fn ct_inverse(&self) -> CtOption<Self> {
+        count_op!(fq12.inverse);
+        let mut c0s = self.c0;
+        c0s.square();
+        let mut c1s = self.c1;
+        c1s.square();
+        c1s.mul_by_nonresidue();
+        c0s.sub_assign(&c1s);
+
+        let t = c0s.ct_inverse();
+        let is_some = t.is_some();
+        let t = t.unwrap_or(Fq6::zero());
+
+        let mut tmp = Fq12 { c0: t, c1: t };
+        tmp.c0.mul_assign(&self.c0);
+        tmp.c1.mul_assign(&self.c1);
+        tmp.c1.negate();
+
+        CtOption::new(tmp, is_some)
+    }
 }
 
 #[cfg(test)]
@@ -186,4 +540,101 @@ fn fq12_field_tests() {
 
     ::tests::field::random_field_tests::<Fq12>();
     ::tests::field::random_frobenius_tests::<Fq12, _>(super::fq::Fq::char(), 13);
+    ::tests::field::random_encoding_tests::<Fq12>();
+    #[cfg(feature = "constant-time")]
+    ::tests::field::random_ct_tests::<Fq12>();
+    ::tests::field::random_operator_tests::<Fq12>();
+}
+
+#[test]
+fn test_fq12_cyclotomic_square() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..100 {
+        // Map a random element into the cyclotomic subgroup by raising it
+        // to (q^6 - 1)(q^2 + 1), the easy part of the final exponentiation.
+        let a = Fq12::rand(&mut rng);
+        let mut f = a;
+        f.conjugate();
+        f.mul_assign(&a.inverse().unwrap());
+        let mut g = f;
+        g.frobenius_map(2);
+        f.mul_assign(&g);
+
+        assert!(f.is_cyclotomic());
+        assert!(!a.is_cyclotomic());
+
+        let mut a = f;
+        let mut b = f;
+        a.cyclotomic_square();
+        b.square();
+        assert_eq!(a, b);
+
+        let e = [rng.gen(), rng.gen()];
+        assert_eq!(f.cyclotomic_exp(e), f.pow(e));
+    }
+
+    assert_eq!(Fq12::one().cyclotomic_exp([0xd201000000010000]), Fq12::one());
+    assert!(Fq12::one().is_cyclotomic());
+    assert!(!Fq12::zero().is_cyclotomic());
+}
+
+#[test]
+fn test_fq12_torus_compression() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..100 {
+        // Map a random element into the cyclotomic subgroup.
+        let a = Fq12::rand(&mut rng);
+        let mut f = a;
+        f.conjugate();
+        f.mul_assign(&a.inverse().unwrap());
+        let mut g = f;
+        g.frobenius_map(2);
+        f.mul_assign(&g);
+
+        let g = f.compress_t2().unwrap();
+        assert_eq!(Fq12::decompress_t2(&g), f);
+
+        let (g1, g2) = f.compress_t6().unwrap();
+        assert_eq!(Fq12::decompress_t6(&g1, &g2).unwrap(), f);
+
+        // Any representation decompresses to an element of the torus,
+        // and any nonzero g1 to an element of the cyclotomic subgroup.
+        let mut h = Fq12::decompress_t2(&Fq6::rand(&mut rng));
+        let mut conj = h;
+        conj.conjugate();
+        h.mul_assign(&conj);
+        assert_eq!(h, Fq12::one());
+
+        let g1 = Fq2::rand(&mut rng);
+        let g2 = Fq2::rand(&mut rng);
+        assert!(Fq12::decompress_t6(&g1, &g2).unwrap().is_cyclotomic());
+    }
+
+    let mut minus_one = Fq12::one();
+    minus_one.negate();
+    assert!(Fq12::one().compress_t2().is_none());
+    assert!(minus_one.compress_t2().is_none());
+    assert!(Fq12::one().compress_t6().is_none());
+    assert!(Fq12::decompress_t6(&Fq2::zero(), &Fq2::one()).is_none());
+}
+
+#[test]
+fn test_fq12_from_str_radix() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..100 {
+        let a = Fq12::rand(&mut rng);
+
+        // Nested tuples, as printed by other libraries.
+        let fq2 = |c: &Fq2| format!("({:#x}, {:#x})", c.c0, c.c1);
+        let fq6 = |c: &Fq6| format!("({}, {}, {})", fq2(&c.c0), fq2(&c.c1), fq2(&c.c2));
+        let s = format!("({}, {})", fq6(&a.c0), fq6(&a.c1));
+        assert_eq!(Fq12::from_str_radix(&s, 16), Some(a));
+
+        let s = format!("[{}]", fq6(&a.c0));
+        assert_eq!(Fq6::from_str_radix(&s, 16), Some(a.c0));
+        assert!(Fq12::from_str_radix(&s, 16).is_none());
+    }
 }
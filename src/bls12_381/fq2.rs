@@ -1,6 +1,12 @@
-use super::fq::{FROBENIUS_COEFF_FQ2_C1, Fq, NEGATIVE_ONE};
+use super::fq::{parse_components, FROBENIUS_COEFF_FQ2_C1, Fq, HALF};
+#[cfg(any(test, feature = "constant-time"))]
+use super::fq::NEGATIVE_ONE;
 use rand::{Rand, Rng};
-use {Field, SqrtField};
+use {check_encoded_len, Field, FieldEncoding, PrimeFieldDecodingError, SqrtField};
+#[cfg(feature = "constant-time")]
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "constant-time")]
+use {CtField, CtSqrtField};
 
 use std::cmp::Ordering;
 
@@ -54,8 +60,43 @@ impl Fq2 {
 
         t1
     }
+
+    /// Parses the list of components `c0, c1` in the given radix. See
+    /// `PrimeField::from_str_radix` for the accepted components; they are
+    /// separated by commas or whitespace, and brackets and parentheses are
+    /// ignored, e.g. `"[0x1a, -2]"`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Fq2> {
+        match *parse_components(s, radix)? {
+            [c0, c1] => Some(Fq2 { c0, c1 }),
+            _ => None,
+        }
+    }
+}
+
+impl FieldEncoding for Fq2 {
+    fn encoded_len() -> usize {
+        2 * Fq::encoded_len()
+    }
+
+    fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.c1.to_bytes_be();
+        bytes.extend(self.c0.to_bytes_be());
+        bytes
+    }
+
+    fn from_bytes_be(bytes: &[u8]) -> Result<Self, PrimeFieldDecodingError> {
+        check_encoded_len::<Self>(bytes)?;
+
+        let (c1, c0) = bytes.split_at(Fq::encoded_len());
+        Ok(Fq2 {
+            c0: Fq::from_bytes_be(c0)?,
+            c1: Fq::from_bytes_be(c1)?,
+        })
+    }
 }
 
+impl_field_ops!(Fq2);
+
 impl Rand for Fq2 {
     fn rand<R: Rng>(rng: &mut R) -> Self {
         Fq2 {
@@ -85,6 +126,7 @@ impl Field for Fq2 {
     }
 
     fn square(&mut self) {
+        count_op!(fq2.square);
         let mut ab = self.c0;
         ab.mul_assign(&self.c1);
         let mut c0c1 = self.c0;
@@ -101,6 +143,7 @@ impl Field for Fq2 {
     }
 
     fn double(&mut self) {
+        count_op!(fq2.add);
         self.c0.double();
         self.c1.double();
     }
@@ -111,16 +154,19 @@ impl Field for Fq2 {
     }
 
     fn add_assign(&mut self, other: &Self) {
+        count_op!(fq2.add);
         self.c0.add_assign(&other.c0);
         self.c1.add_assign(&other.c1);
     }
 
     fn sub_assign(&mut self, other: &Self) {
+        count_op!(fq2.add);
         self.c0.sub_assign(&other.c0);
         self.c1.sub_assign(&other.c1);
     }
 
     fn mul_assign(&mut self, other: &Self) {
+        count_op!(fq2.mul);
         let mut aa = self.c0;
         aa.mul_assign(&other.c0);
         let mut bb = self.c1;
@@ -136,6 +182,7 @@ impl Field for Fq2 {
     }
 
     fn inverse(&self) -> Option<Self> {
+        count_op!(fq2.inverse);
         let mut t1 = self.c1;
         t1.square();
         let mut t0 = self.c0;
@@ -154,6 +201,32 @@ impl Field for Fq2 {
         })
     }
 
+    fn batch_invert_with_scratch(v: &mut [Self], scratch: &mut Vec<Self>) {
+        // The inverse of an element is its conjugate divided by its norm,
+        // so only the norms need to be inverted, in Fq. Each entry of
+        // `scratch` holds the product of the preceding norms and the norm of
+        // the element.
+        scratch.clear();
+        let mut acc = Fq::one();
+        for e in v.iter() {
+            let norm = e.norm();
+            scratch.push(Fq2 { c0: acc, c1: norm });
+            acc.mul_assign(&norm);
+        }
+
+        let mut acc = acc.inverse().expect("cannot invert zero");
+
+        for (e, s) in v.iter_mut().zip(scratch.iter()).rev() {
+            let mut t = acc;
+            t.mul_assign(&s.c0);
+            acc.mul_assign(&s.c1);
+
+            e.c0.mul_assign(&t);
+            e.c1.mul_assign(&t);
+            e.c1.negate();
+        }
+    }
+
     fn frobenius_map(&mut self, power: usize) {
         self.c1.mul_assign(&FROBENIUS_COEFF_FQ2_C1[power % 2]);
     }
@@ -165,8 +238,63 @@ impl SqrtField for Fq2 {
     }
 
     fn sqrt(&self) -> Option<Self> {
-        // Algorithm 9, https://eprint.iacr.org/2012/685.pdf
+        count_op!(fq2.sqrt);
+        // Algorithm 8, https://eprint.iacr.org/2012/685.pdf
+        //
+        // The complex method reduces to two square roots in Fq: one of the
+        // norm, and one of delta = (c0 + sqrt(norm)) / 2. When delta is not a
+        // square, -delta is, and its root becomes the c1 coefficient instead.
+
+        if self.c1.is_zero() {
+            return match self.c0.sqrt() {
+                Some(c0) => Some(Fq2 { c0, c1: Fq::zero() }),
+                None => {
+                    let mut neg = self.c0;
+                    neg.negate();
+                    neg.sqrt().map(|c1| Fq2 { c0: Fq::zero(), c1 })
+                }
+            };
+        }
+
+        let gamma = self.norm().sqrt()?;
+        let mut delta = self.c0;
+        delta.add_assign(&gamma);
+        delta.mul_assign(&HALF);
 
+        // x0 = delta^((q + 1) / 4)
+        let x0 = delta.pow([
+            0xee7fbfffffffeaab,
+            0x7aaffffac54ffff,
+            0xd9cc34a83dac3d89,
+            0xd91dd2e13ce144af,
+            0x92c6e9ed90d2eb35,
+            0x680447a8e5ff9a6,
+        ]);
+
+        // x1 = c1 / (2 * x0), which is nonzero since c1 is.
+        let mut x1 = x0;
+        x1.double();
+        let mut x1 = x1.inverse().unwrap();
+        x1.mul_assign(&self.c1);
+
+        let mut check = x0;
+        check.square();
+        if check == delta {
+            Some(Fq2 { c0: x0, c1: x1 })
+        } else {
+            // x0^2 = -delta, so (x1 + x0 u)^2 = x1^2 + delta + c1 u = self.
+            Some(Fq2 { c0: x1, c1: x0 })
+        }
+    }
+}
+
+#[cfg(test)]
+impl Fq2 {
+    /// Computes a square root with Algorithm 9 of
+    /// https://eprint.iacr.org/2012/685.pdf, exponentiating in Fq2 twice.
+    /// This is slower than `SqrtField::sqrt` and only retained as a
+    /// reference for testing it.
+    fn sqrt_reference(&self) -> Option<Fq2> {
         if self.is_zero() {
             Some(Self::zero())
         } else {
@@ -221,6 +349,93 @@ impl SqrtField for Fq2 {
     }
 }
 
+#[cfg(feature = "constant-time")]
+impl ConstantTimeEq for Fq2 {
+    fn ct_eq(&self, other: &Fq2) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl ConditionallySelectable for Fq2 {
+    fn conditional_select(a: &Fq2, b: &Fq2, choice: Choice) -> Fq2 {
+        Fq2 {
+            c0: Fq::conditional_select(&a.c0, &b.c0, choice),
+            c1: Fq::conditional_select(&a.c1, &b.c1, choice),
+        }
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl CtField for Fq2 {
+    fn ct_inverse(&self) -> CtOption<Self> {
+        count_op!(fq2.inverse);
+        let t = self.norm().ct_inverse();
+        let is_some = t.is_some();
+        let t = t.unwrap_or(Fq::zero());
+
+        let mut tmp = *self;
+        tmp.c0.mul_assign(&t);
+        tmp.c1.mul_assign(&t);
+        tmp.c1.negate();
+
+        CtOption::new(tmp, is_some)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl CtSqrtField for Fq2 {
+    fn ct_sqrt(&self) -> CtOption<Self> {
+        count_op!(fq2.sqrt);
+        // Algorithm 9, https://eprint.iacr.org/2012/685.pdf, computing both
+        // candidate roots and selecting one.
+
+        // a1 = self^((q - 3) / 4)
+        let mut a1 = self.pow([
+            0xee7fbfffffffeaaa,
+            0x7aaffffac54ffff,
+            0xd9cc34a83dac3d89,
+            0xd91dd2e13ce144af,
+            0x92c6e9ed90d2eb35,
+            0x680447a8e5ff9a6,
+        ]);
+        let mut alpha = a1;
+        alpha.square();
+        alpha.mul_assign(self);
+        a1.mul_assign(self);
+
+        // If alpha = -1, the root is a1 * u.
+        let a1u = Fq2 {
+            c0: -a1.c1,
+            c1: a1.c0,
+        };
+
+        // Otherwise it is a1 * (1 + alpha)^((q - 1) / 2).
+        let mut b = alpha;
+        b.add_assign(&Fq2::one());
+        let mut b = b.pow([
+            0xdcff7fffffffd555,
+            0xf55ffff58a9ffff,
+            0xb39869507b587b12,
+            0xb23ba5c279c2895f,
+            0x258dd3db21a5d66b,
+            0xd0088f51cbff34d,
+        ]);
+        b.mul_assign(&a1);
+
+        let neg1 = Fq2 {
+            c0: NEGATIVE_ONE,
+            c1: Fq::zero(),
+        };
+        let root = Fq2::conditional_select(&b, &a1u, alpha.ct_eq(&neg1));
+
+        let mut check = root;
+        check.square();
+
+        CtOption::new(root, check.ct_eq(self))
+    }
+}
+
 #[test]
 fn test_fq2_ordering() {
     let mut a = Fq2 {
@@ -796,7 +1011,18 @@ fn test_fq2_sqrt() {
     use super::fq::FqRepr;
     use PrimeField;
 
-    assert_eq!(
+    // The reference implementation returns exactly these roots, while
+    // `sqrt` may return their negations.
+    fn check(a: Fq2, expected: Fq2) {
+        assert_eq!(a.sqrt_reference().unwrap(), expected);
+
+        let b = a.sqrt().unwrap();
+        let mut neg = expected;
+        neg.negate();
+        assert!(b == expected || b == neg);
+    }
+
+    check(
         Fq2 {
             c0: Fq::from_repr(FqRepr([
                 0x476b4c309720e227,
@@ -814,8 +1040,7 @@ fn test_fq2_sqrt() {
                 0x8ee5394d77afb3d,
                 0xec92336650e49d5
             ])).unwrap(),
-        }.sqrt()
-            .unwrap(),
+        },
         Fq2 {
             c0: Fq::from_repr(FqRepr([
                 0x40b299b2704258c5,
@@ -836,7 +1061,7 @@ fn test_fq2_sqrt() {
         }
     );
 
-    assert_eq!(
+    check(
         Fq2 {
             c0: Fq::from_repr(FqRepr([
                 0xb9f78429d1517a6b,
@@ -847,8 +1072,7 @@ fn test_fq2_sqrt() {
                 0x1a0111ea397fe69a
             ])).unwrap(),
             c1: Fq::zero(),
-        }.sqrt()
-            .unwrap(),
+        },
         Fq2 {
             c0: Fq::zero(),
             c1: Fq::from_repr(FqRepr([
@@ -863,6 +1087,61 @@ fn test_fq2_sqrt() {
     );
 }
 
+#[test]
+fn test_fq2_sqrt_reference() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert_eq!(Fq2::zero().sqrt_reference(), Some(Fq2::zero()));
+
+    for i in 0..1000 {
+        let mut a = Fq2::rand(&mut rng);
+        // Elements of Fq and of Fq * u take a separate path.
+        match i % 4 {
+            0 => a.c1 = Fq::zero(),
+            1 => a.c0 = Fq::zero(),
+            _ => {}
+        }
+
+        match (a.sqrt(), a.sqrt_reference()) {
+            (Some(b), Some(c)) => {
+                let mut negc = c;
+                negc.negate();
+                assert!(b == c || b == negc);
+            }
+            (None, None) => {}
+            _ => panic!("sqrt and sqrt_reference disagree on {}", a),
+        }
+    }
+}
+
+#[test]
+fn test_fq2_from_str_radix() {
+    use PrimeField;
+
+    let a = Fq2 {
+        c0: Fq::from_str("26").unwrap(),
+        c1: Fq::from_str("2").unwrap(),
+    };
+    let mut b = a;
+    b.c1.negate();
+
+    assert_eq!(Fq2::from_str_radix("[0x1a, 2]", 16), Some(a));
+    assert_eq!(Fq2::from_str_radix("(26,-2)", 10), Some(b));
+    assert_eq!(Fq2::from_str_radix("  11010\n10 ", 2), Some(a));
+    assert!(Fq2::from_str_radix("26", 10).is_none());
+    assert!(Fq2::from_str_radix("26, 2, 0", 10).is_none());
+    assert!(Fq2::from_str_radix("26, 2a", 10).is_none());
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+    for _ in 0..100 {
+        let a = Fq2::rand(&mut rng);
+        let s = format!("[{:#x}, {:#x}]", a.c0, a.c1);
+        assert_eq!(Fq2::from_str_radix(&s, 16), Some(a));
+    }
+}
+
 #[test]
 fn test_fq2_legendre() {
     use LegendreSymbol::*;
@@ -905,4 +1184,10 @@ fn fq2_field_tests() {
     ::tests::field::random_field_tests::<Fq2>();
     ::tests::field::random_sqrt_tests::<Fq2>();
     ::tests::field::random_frobenius_tests::<Fq2, _>(super::fq::Fq::char(), 13);
+    ::tests::field::random_encoding_tests::<Fq2>();
+    #[cfg(feature = "constant-time")]
+    ::tests::field::random_ct_tests::<Fq2>();
+    #[cfg(feature = "constant-time")]
+    ::tests::field::random_ct_sqrt_tests::<Fq2>();
+    ::tests::field::random_operator_tests::<Fq2>();
 }
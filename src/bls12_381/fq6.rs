@@ -1,7 +1,11 @@
-use super::fq::{FROBENIUS_COEFF_FQ6_C1, FROBENIUS_COEFF_FQ6_C2};
+use super::fq::{parse_components, Fq, FROBENIUS_COEFF_FQ6_C1, FROBENIUS_COEFF_FQ6_C2};
 use super::fq2::Fq2;
 use rand::{Rand, Rng};
-use Field;
+use {check_encoded_len, Field, FieldEncoding, PrimeFieldDecodingError};
+#[cfg(feature = "constant-time")]
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "constant-time")]
+use CtField;
 
 /// An element of Fq6, represented by c0 + c1 * v + c2 * v^(2).
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -17,6 +21,32 @@ impl ::std::fmt::Display for Fq6 {
     }
 }
 
+impl FieldEncoding for Fq6 {
+    fn encoded_len() -> usize {
+        3 * Fq2::encoded_len()
+    }
+
+    fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.c2.to_bytes_be();
+        bytes.extend(self.c1.to_bytes_be());
+        bytes.extend(self.c0.to_bytes_be());
+        bytes
+    }
+
+    fn from_bytes_be(bytes: &[u8]) -> Result<Self, PrimeFieldDecodingError> {
+        check_encoded_len::<Self>(bytes)?;
+
+        let mut c = bytes.chunks(Fq2::encoded_len());
+        let c2 = Fq2::from_bytes_be(c.next().unwrap())?;
+        let c1 = Fq2::from_bytes_be(c.next().unwrap())?;
+        let c0 = Fq2::from_bytes_be(c.next().unwrap())?;
+
+        Ok(Fq6 { c0, c1, c2 })
+    }
+}
+
+impl_field_ops!(Fq6);
+
 impl Rand for Fq6 {
     fn rand<R: Rng>(rng: &mut R) -> Self {
         Fq6 {
@@ -37,6 +67,27 @@ impl Fq6 {
         self.c0.mul_by_nonresidue();
     }
 
+    /// Parses the list of components `c0.c0, c0.c1, c1.c0, c1.c1, c2.c0,
+    /// c2.c1` in the given radix, separated as for `Fq2::from_str_radix`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Option<Fq6> {
+        let c = parse_components(s, radix)?;
+        if c.len() != 6 {
+            return None;
+        }
+
+        Some(Fq6::from_components(&c))
+    }
+
+    /// Builds an element from its six `Fq` components, in the order parsed
+    /// by `from_str_radix`.
+    pub(crate) fn from_components(c: &[Fq]) -> Fq6 {
+        Fq6 {
+            c0: Fq2 { c0: c[0], c1: c[1] },
+            c1: Fq2 { c0: c[2], c1: c[3] },
+            c2: Fq2 { c0: c[4], c1: c[5] },
+        }
+    }
+
     pub fn mul_by_1(&mut self, c1: &Fq2) {
         let mut b_b = self.c1;
         b_b.mul_assign(c1);
@@ -107,6 +158,7 @@ impl Fq6 {
         self.c1 = t2;
         self.c2 = t3;
     }
+
 }
 
 impl Field for Fq6 {
@@ -131,6 +183,7 @@ impl Field for Fq6 {
     }
 
     fn double(&mut self) {
+        count_op!(fq6.add);
         self.c0.double();
         self.c1.double();
         self.c2.double();
@@ -143,12 +196,14 @@ impl Field for Fq6 {
     }
 
     fn add_assign(&mut self, other: &Self) {
+        count_op!(fq6.add);
         self.c0.add_assign(&other.c0);
         self.c1.add_assign(&other.c1);
         self.c2.add_assign(&other.c2);
     }
 
     fn sub_assign(&mut self, other: &Self) {
+        count_op!(fq6.add);
         self.c0.sub_assign(&other.c0);
         self.c1.sub_assign(&other.c1);
         self.c2.sub_assign(&other.c2);
@@ -164,6 +219,7 @@ impl Field for Fq6 {
     }
 
     fn square(&mut self) {
+        count_op!(fq6.square);
         let mut s0 = self.c0;
         s0.square();
         let mut ab = self.c0;
@@ -197,6 +253,7 @@ impl Field for Fq6 {
     }
 
     fn mul_assign(&mut self, other: &Self) {
+        count_op!(fq6.mul);
         let mut a_a = self.c0;
         let mut b_b = self.c1;
         let mut c_c = self.c2;
@@ -248,6 +305,54 @@ impl Field for Fq6 {
     }
 
     fn inverse(&self) -> Option<Self> {
+        count_op!(fq6.inverse);
+        let (mut tmp, norm) = self.inverse_parts();
+
+        norm.inverse().map(|t| {
+            tmp.c0.mul_assign(&t);
+            tmp.c1.mul_assign(&t);
+            tmp.c2.mul_assign(&t);
+
+            tmp
+        })
+    }
+
+    fn batch_invert_with_scratch(v: &mut [Self], scratch: &mut Vec<Self>) {
+        // Only the norms of the elements need to be inverted, in Fq2. For
+        // each element, `scratch` holds its adjugate, followed by the product
+        // of the preceding norms and its norm.
+        scratch.clear();
+        let mut acc = Fq2::one();
+        for e in v.iter() {
+            let (adjugate, norm) = e.inverse_parts();
+            scratch.push(adjugate);
+            scratch.push(Fq6 {
+                c0: acc,
+                c1: norm,
+                c2: Fq2::zero(),
+            });
+            acc.mul_assign(&norm);
+        }
+
+        let mut acc = acc.inverse().expect("cannot invert zero");
+
+        for (e, s) in v.iter_mut().zip(scratch.chunks(2)).rev() {
+            let mut t = acc;
+            t.mul_assign(&s[1].c0);
+            acc.mul_assign(&s[1].c1);
+
+            *e = s[0];
+            e.c0.mul_assign(&t);
+            e.c1.mul_assign(&t);
+            e.c2.mul_assign(&t);
+        }
+    }
+}
+
+impl Fq6 {
+    /// Returns the adjugate of this element and its norm in Fq2, whose
+    /// quotient is the inverse of this element.
+    pub(crate) fn inverse_parts(&self) -> (Fq6, Fq2) {
         let mut c0 = self.c2;
         c0.mul_by_nonresidue();
         c0.mul_assign(&self.c1);
@@ -283,24 +388,45 @@ impl Field for Fq6 {
         tmp2.mul_assign(&c0);
         tmp1.add_assign(&tmp2);
 
-        match tmp1.inverse() {
-            Some(t) => {
-                let mut tmp = Fq6 {
-                    c0: t,
-                    c1: t,
-                    c2: t,
-                };
-                tmp.c0.mul_assign(&c0);
-                tmp.c1.mul_assign(&c1);
-                tmp.c2.mul_assign(&c2);
-
-                Some(tmp)
-            }
-            None => None,
+        (Fq6 { c0, c1, c2 }, tmp1)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl ConstantTimeEq for Fq6 {
+    fn ct_eq(&self, other: &Fq6) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl ConditionallySelectable for Fq6 {
+    fn conditional_select(a: &Fq6, b: &Fq6, choice: Choice) -> Fq6 {
+        Fq6 {
+            c0: Fq2::conditional_select(&a.c0, &b.c0, choice),
+            c1: Fq2::conditional_select(&a.c1, &b.c1, choice),
+            c2: Fq2::conditional_select(&a.c2, &b.c2, choice),
         }
     }
 }
 
+#[cfg(feature = "constant-time")]
+impl CtField for Fq6 {
+    fn ct_inverse(&self) -> CtOption<Self> {
+        count_op!(fq6.inverse);
+        let (mut tmp, norm) = self.inverse_parts();
+        let t = norm.ct_inverse();
+        let is_some = t.is_some();
+        let t = t.unwrap_or(Fq2::zero());
+
+        tmp.c0.mul_assign(&t);
+        tmp.c1.mul_assign(&t);
+        tmp.c2.mul_assign(&t);
+
+        CtOption::new(tmp, is_some)
+    }
+}
+
 #[cfg(test)]
 use rand::{SeedableRng, XorShiftRng};
 
@@ -371,4 +497,8 @@ fn fq6_field_tests() {
 
     ::tests::field::random_field_tests::<Fq6>();
     ::tests::field::random_frobenius_tests::<Fq6, _>(super::fq::Fq::char(), 13);
+    ::tests::field::random_encoding_tests::<Fq6>();
+    #[cfg(feature = "constant-time")]
+    ::tests::field::random_ct_tests::<Fq6>();
+    ::tests::field::random_operator_tests::<Fq6>();
 }
@@ -1,5 +1,17 @@
 use LegendreSymbol::*;
-use {Field, PrimeField, PrimeFieldDecodingError, PrimeFieldRepr, SqrtField};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Once;
+use {Field, PrimeField, PrimeFieldDecodingError, PrimeFieldRepr, SqrtField, WidePrimeFieldRepr};
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+use super::limb32;
+use super::Limb;
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
+#[cfg(feature = "constant-time")]
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+#[cfg(feature = "constant-time")]
+use {CtField, CtSqrtField};
 
 // r = 52435875175126190479447740508185965837690552500527637822603658699938581184513
 const MODULUS: FrRepr = FrRepr([
@@ -33,7 +45,7 @@ const R2: FrRepr = FrRepr([
 ]);
 
 // INV = -(r^{-1} mod 2^64) mod 2^64
-const INV: u64 = 0xfffffffeffffffff;
+pub(super) const INV: u64 = 0xfffffffeffffffff;
 
 // GENERATOR = 7 (multiplicative generator of r-1 order, that is also quadratic nonresidue)
 const GENERATOR: FrRepr = FrRepr([
@@ -243,8 +255,107 @@ impl PrimeFieldRepr for FrRepr {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-pub struct Fr(FrRepr);
+impl WidePrimeFieldRepr for FrRepr {
+    type Wide = [u64; 8];
+}
+
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+impl FrRepr {
+    /// Adds `MODULUS & mask` to this value, where `mask` is either zero or
+    /// all ones, so that the modulus can be added without branching.
+    #[inline(always)]
+    fn add_masked_modulus(&mut self, mask: u64) {
+        let mut carry = 0;
+
+        for (a, b) in self.0.iter_mut().zip(MODULUS.0.iter()) {
+            *a = ::adc(*a, *b & mask, &mut carry);
+        }
+    }
+
+    /// Subtracts the modulus from this value if it is not below the
+    /// modulus, without branching on the value.
+    #[inline(always)]
+    fn reduce_once(&mut self) {
+        let mut tmp = *self;
+        let borrow = u64::from(tmp.sub_with_borrow(&MODULUS));
+
+        // The subtraction only borrowed if this value was already reduced.
+        let mask = borrow.wrapping_sub(1);
+        for (a, b) in self.0.iter_mut().zip(tmp.0.iter()) {
+            *a ^= (*a ^ *b) & mask;
+        }
+    }
+}
+
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+impl limb32::Limbs<LIMBS> {
+    /// Adds `MODULUS & mask` to this value, where `mask` is either zero or
+    /// all ones, so that the modulus can be added without branching.
+    #[inline(always)]
+    fn add_masked_modulus(&mut self, mask: u32) {
+        self.add_masked(&MODULUS_LIMBS, mask);
+    }
+
+    /// Subtracts the modulus from this value if it is not below the
+    /// modulus, without branching on the value.
+    #[inline(always)]
+    fn reduce_once(&mut self) {
+        self.sub_if_not_below(&MODULUS_LIMBS);
+    }
+}
+
+/// The number of limbs `Fr` elements are stored in.
+const LIMBS: usize = 256 / Limb::BITS as usize;
+
+/// The limbs `Fr` elements are stored in: those of `FrRepr`, or twice as
+/// many 32-bit limbs on 32-bit targets or with the `u32-limbs` feature.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+type FrLimbs = FrRepr;
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+type FrLimbs = limb32::Limbs<LIMBS>;
+
+const MODULUS_LIMBS: FrLimbs = limbs(MODULUS.0);
+
+/// Converts the 64-bit limbs of a representation to `FrLimbs`.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+const fn limbs(l: [u64; 4]) -> FrLimbs {
+    FrRepr(l)
+}
+
+/// Converts the 64-bit limbs of a representation to `FrLimbs`.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+const fn limbs(l: [u64; 4]) -> FrLimbs {
+    limb32::Limbs::split(&l)
+}
+
+/// Converts `FrLimbs` back to a representation.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+fn repr(l: FrLimbs) -> FrRepr {
+    l
+}
+
+/// Converts `FrLimbs` back to a representation.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+fn repr(l: FrLimbs) -> FrRepr {
+    let mut r = FrRepr::default();
+    l.join(&mut r.0);
+    r
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Fr(FrLimbs);
+
+/// Shows the Montgomery form as an `FrRepr`, whichever limbs it is stored
+/// in.
+impl ::std::fmt::Debug for Fr {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.debug_tuple("Fr").field(&repr(self.0)).finish()
+    }
+}
 
 impl ::std::fmt::Display for Fr {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -252,14 +363,55 @@ impl ::std::fmt::Display for Fr {
     }
 }
 
+#[cfg(feature = "bigint")]
+impl From<FrRepr> for BigUint {
+    fn from(repr: FrRepr) -> BigUint {
+        repr.to_biguint()
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl From<Fr> for BigUint {
+    fn from(a: Fr) -> BigUint {
+        a.to_biguint()
+    }
+}
+
+/// Reduces an integer of any size into the field.
+#[cfg(feature = "bigint")]
+impl<'a> From<&'a BigUint> for Fr {
+    fn from(n: &'a BigUint) -> Fr {
+        Fr::from_biguint(n)
+    }
+}
+
+/// Formats the canonical representative in hexadecimal, prefixed with `0x`
+/// in the alternate form.
+impl ::std::fmt::LowerHex for Fr {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16))
+    }
+}
+
+/// Formats the canonical representative in uppercase hexadecimal, prefixed
+/// with `0x` in the alternate form.
+impl ::std::fmt::UpperHex for Fr {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.pad_integral(true, "0x", &self.to_str_radix(16).to_uppercase())
+    }
+}
+
+impl_field_ops!(Fr);
+
 impl ::rand::Rand for Fr {
     fn rand<R: ::rand::Rng>(rng: &mut R) -> Self {
         loop {
-            let mut tmp = Fr(FrRepr::rand(rng));
+            let mut r = FrRepr::rand(rng);
 
             // Mask away the unused bits at the beginning.
-            tmp.0.as_mut()[3] &= 0xffffffffffffffff >> REPR_SHAVE_BITS;
+            r.0[3] &= 0xffffffffffffffff >> REPR_SHAVE_BITS;
 
+            let tmp = Fr(limbs(r.0));
             if tmp.is_valid() {
                 return tmp;
             }
@@ -277,29 +429,22 @@ impl PrimeField for Fr {
     type Repr = FrRepr;
 
     fn from_repr(r: FrRepr) -> Result<Fr, PrimeFieldDecodingError> {
-        let mut r = Fr(r);
+        let mut r = Fr(limbs(r.0));
         if r.is_valid() {
-            r.mul_assign(&Fr(R2));
+            r.mul_assign(&Fr(limbs(R2.0)));
 
             Ok(r)
         } else {
-            Err(PrimeFieldDecodingError::NotInField(format!("{}", r.0)))
+            Err(PrimeFieldDecodingError::NotInField(format!("{}", repr(r.0))))
         }
     }
 
     fn into_repr(&self) -> FrRepr {
+        let mut t = [0; 2 * LIMBS];
+        t[..LIMBS].copy_from_slice(&(self.0).0);
         let mut r = *self;
-        r.mont_reduce(
-            (self.0).0[0],
-            (self.0).0[1],
-            (self.0).0[2],
-            (self.0).0[3],
-            0,
-            0,
-            0,
-            0,
-        );
-        r.0
+        r.mont_reduce(t);
+        repr(r.0)
     }
 
     fn char() -> FrRepr {
@@ -311,25 +456,25 @@ impl PrimeField for Fr {
     const CAPACITY: u32 = Self::NUM_BITS - 1;
 
     fn multiplicative_generator() -> Self {
-        Fr(GENERATOR)
+        Fr(limbs(GENERATOR.0))
     }
 
     const S: u32 = S;
 
     fn root_of_unity() -> Self {
-        Fr(ROOT_OF_UNITY)
+        Fr(limbs(ROOT_OF_UNITY.0))
     }
 }
 
 impl Field for Fr {
     #[inline]
     fn zero() -> Self {
-        Fr(FrRepr::from(0))
+        Fr(FrLimbs::default())
     }
 
     #[inline]
     fn one() -> Self {
-        Fr(R)
+        Fr(limbs(R.0))
     }
 
     #[inline]
@@ -339,6 +484,7 @@ impl Field for Fr {
 
     #[inline]
     fn add_assign(&mut self, other: &Fr) {
+        count_op!(fr.add);
         // This cannot exceed the backing capacity.
         self.0.add_nocarry(&other.0);
 
@@ -348,6 +494,7 @@ impl Field for Fr {
 
     #[inline]
     fn double(&mut self) {
+        count_op!(fr.add);
         // This cannot exceed the backing capacity.
         self.0.mul2();
 
@@ -357,24 +504,29 @@ impl Field for Fr {
 
     #[inline]
     fn sub_assign(&mut self, other: &Fr) {
-        // If `other` is larger than `self`, we'll need to add the modulus to self first.
-        if other.0 > self.0 {
-            self.0.add_nocarry(&MODULUS);
-        }
+        count_op!(fr.add);
+        let borrow = Limb::from(self.0.sub_with_borrow(&other.0));
 
-        self.0.sub_noborrow(&other.0);
+        // If `other` was larger than `self`, the difference wrapped around
+        // and the modulus must be added back.
+        self.0.add_masked_modulus(borrow.wrapping_neg());
     }
 
     #[inline]
     fn negate(&mut self) {
-        if !self.is_zero() {
-            let mut tmp = MODULUS;
-            tmp.sub_noborrow(&self.0);
-            self.0 = tmp;
+        // Zero is its own negation, so the difference is masked away for it.
+        let nonzero = (self.0).0.iter().fold(0, |acc, limb| acc | limb);
+        let mask = ((nonzero | nonzero.wrapping_neg()) >> (Limb::BITS - 1)).wrapping_neg();
+
+        let mut tmp = MODULUS_LIMBS;
+        tmp.sub_noborrow(&self.0);
+        for (a, b) in (self.0).0.iter_mut().zip(tmp.0.iter()) {
+            *a = *b & mask;
         }
     }
 
     fn inverse(&self) -> Option<Self> {
+        count_op!(fr.inverse);
         if self.is_zero() {
             None
         } else {
@@ -382,11 +534,11 @@ impl Field for Fr {
             // Efficient Software-Implementation of Finite Fields with Applications to Cryptography
             // Algorithm 16 (BEA for Inversion in Fp)
 
-            let one = FrRepr::from(1);
+            let one = limbs([1, 0, 0, 0]);
 
             let mut u = self.0;
-            let mut v = MODULUS;
-            let mut b = Fr(R2); // Avoids unnecessary reduction step.
+            let mut v = MODULUS_LIMBS;
+            let mut b = Fr(limbs(R2.0)); // Avoids unnecessary reduction step.
             let mut c = Self::zero();
 
             while u != one && v != one {
@@ -396,7 +548,7 @@ impl Field for Fr {
                     if b.0.is_even() {
                         b.0.div2();
                     } else {
-                        b.0.add_nocarry(&MODULUS);
+                        b.0.add_nocarry(&MODULUS_LIMBS);
                         b.0.div2();
                     }
                 }
@@ -407,7 +559,7 @@ impl Field for Fr {
                     if c.0.is_even() {
                         c.0.div2();
                     } else {
-                        c.0.add_nocarry(&MODULUS);
+                        c.0.add_nocarry(&MODULUS_LIMBS);
                         c.0.div2();
                     }
                 }
@@ -436,66 +588,28 @@ impl Field for Fr {
 
     #[inline]
     fn mul_assign(&mut self, other: &Fr) {
-        let mut carry = 0;
-        let r0 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[0], &mut carry);
-        let r1 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[1], &mut carry);
-        let r2 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[2], &mut carry);
-        let r3 = ::mac_with_carry(0, (self.0).0[0], (other.0).0[3], &mut carry);
-        let r4 = carry;
-        let mut carry = 0;
-        let r1 = ::mac_with_carry(r1, (self.0).0[1], (other.0).0[0], &mut carry);
-        let r2 = ::mac_with_carry(r2, (self.0).0[1], (other.0).0[1], &mut carry);
-        let r3 = ::mac_with_carry(r3, (self.0).0[1], (other.0).0[2], &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[1], (other.0).0[3], &mut carry);
-        let r5 = carry;
-        let mut carry = 0;
-        let r2 = ::mac_with_carry(r2, (self.0).0[2], (other.0).0[0], &mut carry);
-        let r3 = ::mac_with_carry(r3, (self.0).0[2], (other.0).0[1], &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[2], (other.0).0[2], &mut carry);
-        let r5 = ::mac_with_carry(r5, (self.0).0[2], (other.0).0[3], &mut carry);
-        let r6 = carry;
-        let mut carry = 0;
-        let r3 = ::mac_with_carry(r3, (self.0).0[3], (other.0).0[0], &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[3], (other.0).0[1], &mut carry);
-        let r5 = ::mac_with_carry(r5, (self.0).0[3], (other.0).0[2], &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[3], (other.0).0[3], &mut carry);
-        let r7 = carry;
-        self.mont_reduce(r0, r1, r2, r3, r4, r5, r6, r7);
+        count_op!(fr.mul);
+        adx_or!(
+            {
+                let a = (self.0).0;
+                adx::mont_mul_4(&a, &(other.0).0, &MODULUS.0, INV, &mut (self.0).0)
+            },
+            (self.0).0 = mont_reduce_portable(mul_portable(&(self.0).0, &(other.0).0))
+        );
+        self.reduce();
     }
 
     #[inline]
     fn square(&mut self) {
-        let mut carry = 0;
-        let r1 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[1], &mut carry);
-        let r2 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[2], &mut carry);
-        let r3 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[3], &mut carry);
-        let r4 = carry;
-        let mut carry = 0;
-        let r3 = ::mac_with_carry(r3, (self.0).0[1], (self.0).0[2], &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[1], (self.0).0[3], &mut carry);
-        let r5 = carry;
-        let mut carry = 0;
-        let r5 = ::mac_with_carry(r5, (self.0).0[2], (self.0).0[3], &mut carry);
-        let r6 = carry;
-
-        let r7 = r6 >> 63;
-        let r6 = (r6 << 1) | (r5 >> 63);
-        let r5 = (r5 << 1) | (r4 >> 63);
-        let r4 = (r4 << 1) | (r3 >> 63);
-        let r3 = (r3 << 1) | (r2 >> 63);
-        let r2 = (r2 << 1) | (r1 >> 63);
-        let r1 = r1 << 1;
-
-        let mut carry = 0;
-        let r0 = ::mac_with_carry(0, (self.0).0[0], (self.0).0[0], &mut carry);
-        let r1 = ::adc(r1, 0, &mut carry);
-        let r2 = ::mac_with_carry(r2, (self.0).0[1], (self.0).0[1], &mut carry);
-        let r3 = ::adc(r3, 0, &mut carry);
-        let r4 = ::mac_with_carry(r4, (self.0).0[2], (self.0).0[2], &mut carry);
-        let r5 = ::adc(r5, 0, &mut carry);
-        let r6 = ::mac_with_carry(r6, (self.0).0[3], (self.0).0[3], &mut carry);
-        let r7 = ::adc(r7, 0, &mut carry);
-        self.mont_reduce(r0, r1, r2, r3, r4, r5, r6, r7);
+        count_op!(fr.square);
+        adx_or!(
+            {
+                let a = (self.0).0;
+                adx::mont_mul_4(&a, &a, &MODULUS.0, INV, &mut (self.0).0)
+            },
+            (self.0).0 = mont_reduce_portable(square_portable(&(self.0).0))
+        );
+        self.reduce();
     }
 }
 
@@ -504,75 +618,254 @@ impl Fr {
     /// internally.
     #[inline(always)]
     fn is_valid(&self) -> bool {
-        self.0 < MODULUS
+        self.0 < MODULUS_LIMBS
     }
 
     /// Subtracts the modulus from this element if this element is not in the
-    /// field. Only used internally.
+    /// field, without branching on its value. Only used internally.
     #[inline(always)]
     fn reduce(&mut self) {
-        if !self.is_valid() {
-            self.0.sub_noborrow(&MODULUS);
-        }
+        self.0.reduce_once();
     }
 
     #[inline(always)]
-    fn mont_reduce(
-        &mut self,
-        r0: u64,
-        mut r1: u64,
-        mut r2: u64,
-        mut r3: u64,
-        mut r4: u64,
-        mut r5: u64,
-        mut r6: u64,
-        mut r7: u64,
-    ) {
-        // The Montgomery reduction here is based on Algorithm 14.32 in
-        // Handbook of Applied Cryptography
-        // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
-
-        let k = r0.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r0, k, MODULUS.0[0], &mut carry);
-        r1 = ::mac_with_carry(r1, k, MODULUS.0[1], &mut carry);
-        r2 = ::mac_with_carry(r2, k, MODULUS.0[2], &mut carry);
-        r3 = ::mac_with_carry(r3, k, MODULUS.0[3], &mut carry);
-        r4 = ::adc(r4, 0, &mut carry);
-        let carry2 = carry;
-        let k = r1.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r1, k, MODULUS.0[0], &mut carry);
-        r2 = ::mac_with_carry(r2, k, MODULUS.0[1], &mut carry);
-        r3 = ::mac_with_carry(r3, k, MODULUS.0[2], &mut carry);
-        r4 = ::mac_with_carry(r4, k, MODULUS.0[3], &mut carry);
-        r5 = ::adc(r5, carry2, &mut carry);
-        let carry2 = carry;
-        let k = r2.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r2, k, MODULUS.0[0], &mut carry);
-        r3 = ::mac_with_carry(r3, k, MODULUS.0[1], &mut carry);
-        r4 = ::mac_with_carry(r4, k, MODULUS.0[2], &mut carry);
-        r5 = ::mac_with_carry(r5, k, MODULUS.0[3], &mut carry);
-        r6 = ::adc(r6, carry2, &mut carry);
-        let carry2 = carry;
-        let k = r3.wrapping_mul(INV);
-        let mut carry = 0;
-        ::mac_with_carry(r3, k, MODULUS.0[0], &mut carry);
-        r4 = ::mac_with_carry(r4, k, MODULUS.0[1], &mut carry);
-        r5 = ::mac_with_carry(r5, k, MODULUS.0[2], &mut carry);
-        r6 = ::mac_with_carry(r6, k, MODULUS.0[3], &mut carry);
-        r7 = ::adc(r7, carry2, &mut carry);
-        (self.0).0[0] = r4;
-        (self.0).0[1] = r5;
-        (self.0).0[2] = r6;
-        (self.0).0[3] = r7;
+    fn mont_reduce(&mut self, t: [Limb; 2 * LIMBS]) {
+        adx_or!(
+            adx::mont_reduce_4(&t, &MODULUS.0, INV, &mut (self.0).0),
+            (self.0).0 = mont_reduce_portable(t)
+        );
         self.reduce();
     }
 }
 
+/// Returns the full product of `a` and `b`, computed by the portable
+/// arithmetic.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+pub(super) fn mul_portable(a: &[u32; 8], b: &[u32; 8]) -> [u32; 16] {
+    let mut res = [0; 16];
+    limb32::mul(a, b, &mut res);
+    res
+}
+
+/// Returns the square of `a`, computed by the portable arithmetic.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+pub(super) fn square_portable(a: &[u32; 8]) -> [u32; 16] {
+    let mut res = [0; 16];
+    limb32::square(a, &mut res);
+    res
+}
+
+/// Returns the full product of `a` and `b`, computed by the portable
+/// arithmetic.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+pub(super) fn mul_portable(a: &[u64; 4], b: &[u64; 4]) -> [u64; 8] {
+    let mut carry = 0;
+    let r0 = ::mac_with_carry(0, a[0], b[0], &mut carry);
+    let r1 = ::mac_with_carry(0, a[0], b[1], &mut carry);
+    let r2 = ::mac_with_carry(0, a[0], b[2], &mut carry);
+    let r3 = ::mac_with_carry(0, a[0], b[3], &mut carry);
+    let r4 = carry;
+    let mut carry = 0;
+    let r1 = ::mac_with_carry(r1, a[1], b[0], &mut carry);
+    let r2 = ::mac_with_carry(r2, a[1], b[1], &mut carry);
+    let r3 = ::mac_with_carry(r3, a[1], b[2], &mut carry);
+    let r4 = ::mac_with_carry(r4, a[1], b[3], &mut carry);
+    let r5 = carry;
+    let mut carry = 0;
+    let r2 = ::mac_with_carry(r2, a[2], b[0], &mut carry);
+    let r3 = ::mac_with_carry(r3, a[2], b[1], &mut carry);
+    let r4 = ::mac_with_carry(r4, a[2], b[2], &mut carry);
+    let r5 = ::mac_with_carry(r5, a[2], b[3], &mut carry);
+    let r6 = carry;
+    let mut carry = 0;
+    let r3 = ::mac_with_carry(r3, a[3], b[0], &mut carry);
+    let r4 = ::mac_with_carry(r4, a[3], b[1], &mut carry);
+    let r5 = ::mac_with_carry(r5, a[3], b[2], &mut carry);
+    let r6 = ::mac_with_carry(r6, a[3], b[3], &mut carry);
+    let r7 = carry;
+    [r0, r1, r2, r3, r4, r5, r6, r7]
+}
+
+/// Returns the square of `a`, computed by the portable arithmetic.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+pub(super) fn square_portable(a: &[u64; 4]) -> [u64; 8] {
+    let mut carry = 0;
+    let r1 = ::mac_with_carry(0, a[0], a[1], &mut carry);
+    let r2 = ::mac_with_carry(0, a[0], a[2], &mut carry);
+    let r3 = ::mac_with_carry(0, a[0], a[3], &mut carry);
+    let r4 = carry;
+    let mut carry = 0;
+    let r3 = ::mac_with_carry(r3, a[1], a[2], &mut carry);
+    let r4 = ::mac_with_carry(r4, a[1], a[3], &mut carry);
+    let r5 = carry;
+    let mut carry = 0;
+    let r5 = ::mac_with_carry(r5, a[2], a[3], &mut carry);
+    let r6 = carry;
+
+    let r7 = r6 >> 63;
+    let r6 = (r6 << 1) | (r5 >> 63);
+    let r5 = (r5 << 1) | (r4 >> 63);
+    let r4 = (r4 << 1) | (r3 >> 63);
+    let r3 = (r3 << 1) | (r2 >> 63);
+    let r2 = (r2 << 1) | (r1 >> 63);
+    let r1 = r1 << 1;
+
+    let mut carry = 0;
+    let r0 = ::mac_with_carry(0, a[0], a[0], &mut carry);
+    let r1 = ::adc(r1, 0, &mut carry);
+    let r2 = ::mac_with_carry(r2, a[1], a[1], &mut carry);
+    let r3 = ::adc(r3, 0, &mut carry);
+    let r4 = ::mac_with_carry(r4, a[2], a[2], &mut carry);
+    let r5 = ::adc(r5, 0, &mut carry);
+    let r6 = ::mac_with_carry(r6, a[3], a[3], &mut carry);
+    let r7 = ::adc(r7, 0, &mut carry);
+    [r0, r1, r2, r3, r4, r5, r6, r7]
+}
+
+/// Performs Montgomery reduction of `t` by the portable arithmetic,
+/// leaving a result that may exceed the modulus by one multiple of it.
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+#[inline(always)]
+pub(super) fn mont_reduce_portable(t: [u32; 16]) -> [u32; 8] {
+    let mut res = [0; 8];
+    limb32::mont_reduce(&t, &MODULUS_LIMBS.0, INV, &mut res);
+    res
+}
+
+/// Performs Montgomery reduction of `t` by the portable arithmetic,
+/// leaving a result that may exceed the modulus by one multiple of it.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+#[inline(always)]
+pub(super) fn mont_reduce_portable(t: [u64; 8]) -> [u64; 4] {
+    let [r0, mut r1, mut r2, mut r3, mut r4, mut r5, mut r6, mut r7] = t;
+
+    // The Montgomery reduction here is based on Algorithm 14.32 in
+    // Handbook of Applied Cryptography
+    // <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
+
+    let k = r0.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r0, k, MODULUS.0[0], &mut carry);
+    r1 = ::mac_with_carry(r1, k, MODULUS.0[1], &mut carry);
+    r2 = ::mac_with_carry(r2, k, MODULUS.0[2], &mut carry);
+    r3 = ::mac_with_carry(r3, k, MODULUS.0[3], &mut carry);
+    r4 = ::adc(r4, 0, &mut carry);
+    let carry2 = carry;
+    let k = r1.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r1, k, MODULUS.0[0], &mut carry);
+    r2 = ::mac_with_carry(r2, k, MODULUS.0[1], &mut carry);
+    r3 = ::mac_with_carry(r3, k, MODULUS.0[2], &mut carry);
+    r4 = ::mac_with_carry(r4, k, MODULUS.0[3], &mut carry);
+    r5 = ::adc(r5, carry2, &mut carry);
+    let carry2 = carry;
+    let k = r2.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r2, k, MODULUS.0[0], &mut carry);
+    r3 = ::mac_with_carry(r3, k, MODULUS.0[1], &mut carry);
+    r4 = ::mac_with_carry(r4, k, MODULUS.0[2], &mut carry);
+    r5 = ::mac_with_carry(r5, k, MODULUS.0[3], &mut carry);
+    r6 = ::adc(r6, carry2, &mut carry);
+    let carry2 = carry;
+    let k = r3.wrapping_mul(INV);
+    let mut carry = 0;
+    ::mac_with_carry(r3, k, MODULUS.0[0], &mut carry);
+    r4 = ::mac_with_carry(r4, k, MODULUS.0[1], &mut carry);
+    r5 = ::mac_with_carry(r5, k, MODULUS.0[2], &mut carry);
+    r6 = ::mac_with_carry(r6, k, MODULUS.0[3], &mut carry);
+    r7 = ::adc(r7, carry2, &mut carry);
+    [r4, r5, r6, r7]
+}
+
 impl SqrtField for Fr {
     fn legendre(&self) -> ::LegendreSymbol {
+        // The Montgomery form is this element times 2^256, a square, so it
+        // has the same symbol.
+        match repr(self.0).jacobi(&MODULUS) {
+            0 => Zero,
+            1 => QuadraticResidue,
+            _ => QuadraticNonResidue,
+        }
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        count_op!(fr.sqrt);
+        // Table-based Tonelli-Shanks, https://eprint.iacr.org/2020/1407.pdf
+        //
+        // With x = self^((t + 1) / 2) and b = self^t = g^e for the 2^S root of
+        // unity g, x^2 = self * g^e, so x * g^(-e / 2) is a square root when
+        // e is even. The exponent e is recovered 8 bits at a time by looking
+        // up elements of order dividing 2^8 in a table.
+        if self.is_zero() {
+            return Some(*self);
+        }
+
+        let tables = SqrtTables::get();
+
+        // w = self^((t - 1) / 2)
+        let w = self.pow([
+            0x7fff2dff7fffffff,
+            0x4d0ec02a9ded201,
+            0x94cebea4199cec04,
+            0x39f6d3a9,
+        ]);
+        let mut x = *self;
+        x.mul_assign(&w);
+        let mut b = x;
+        b.mul_assign(&w);
+
+        let mut b8 = b;
+        for _ in 0..8 {
+            b8.square();
+        }
+        let mut b16 = b8;
+        for _ in 0..8 {
+            b16.square();
+        }
+        let mut b24 = b16;
+        for _ in 0..8 {
+            b24.square();
+        }
+
+        let e0 = tables.dlog(&b24);
+        if e0 & 1 == 1 {
+            return None;
+        }
+
+        let mut tmp = b16;
+        tmp.mul_assign(&tables.inv_powers[2][e0]);
+        let e1 = tables.dlog(&tmp);
+
+        let mut tmp = b8;
+        tmp.mul_assign(&tables.inv_powers[1][e0]);
+        tmp.mul_assign(&tables.inv_powers[2][e1]);
+        let e2 = tables.dlog(&tmp);
+
+        let mut tmp = b;
+        tmp.mul_assign(&tables.inv_powers[0][e0]);
+        tmp.mul_assign(&tables.inv_powers[1][e1]);
+        tmp.mul_assign(&tables.inv_powers[2][e2]);
+        let e3 = tables.dlog(&tmp);
+
+        let half = (e0 | (e1 << 8) | (e2 << 16) | (e3 << 24)) >> 1;
+        for (k, table) in tables.inv_powers.iter().enumerate() {
+            x.mul_assign(&table[(half >> (8 * k)) & 0xff]);
+        }
+
+        Some(x)
+    }
+}
+
+impl Fr {
+    /// Computes the Legendre symbol by exponentiation with Euler's
+    /// criterion. This is slower than `SqrtField::legendre` and only
+    /// retained as a reference for testing it.
+    #[cfg(test)]
+    fn legendre_reference(&self) -> ::LegendreSymbol {
         // s = self^((r - 1) // 2)
         let s = self.pow([
             0x7fffffff80000000,
@@ -589,14 +882,18 @@ impl SqrtField for Fr {
         }
     }
 
-    fn sqrt(&self) -> Option<Self> {
+    /// Computes a square root with the Tonelli-Shanks loop, squaring up to
+    /// S times per iteration. This is slower than `SqrtField::sqrt` and only
+    /// retained as a reference for testing it.
+    #[cfg(test)]
+    fn sqrt_reference(&self) -> Option<Fr> {
         // Tonelli-Shank's algorithm for q mod 16 = 1
         // https://eprint.iacr.org/2012/685.pdf (page 12, algorithm 5)
         match self.legendre() {
             Zero => Some(*self),
             QuadraticNonResidue => None,
             QuadraticResidue => {
-                let mut c = Fr(ROOT_OF_UNITY);
+                let mut c = Fr(limbs(ROOT_OF_UNITY.0));
                 // r = self^((t + 1) // 2)
                 let mut r = self.pow([
                     0x7fff2dff80000000,
@@ -642,6 +939,159 @@ impl SqrtField for Fr {
     }
 }
 
+/// Precomputed powers of the 2^S root of unity used by `Fr::sqrt`.
+struct SqrtTables {
+    /// `inv_powers[k][i]` is `ROOT_OF_UNITY^(-i * 2^(8k))`.
+    inv_powers: [[Fr; 256]; 4],
+    /// The first Montgomery limb of `ROOT_OF_UNITY^(i * 2^24)` paired with
+    /// `i`, sorted by limb. The limbs of these 256 elements are distinct.
+    dlog: Vec<(Limb, u8)>,
+}
+
+impl SqrtTables {
+    fn get() -> &'static SqrtTables {
+        static INIT: Once = Once::new();
+        static TABLES: AtomicPtr<SqrtTables> = AtomicPtr::new(ptr::null_mut());
+
+        INIT.call_once(|| {
+            let tables = Box::new(SqrtTables::new());
+            TABLES.store(Box::into_raw(tables), Ordering::Release);
+        });
+
+        // The tables are stored once, before `call_once` returns, and are
+        // never freed.
+        unsafe { &*TABLES.load(Ordering::Acquire) }
+    }
+
+    fn new() -> SqrtTables {
+        let mut inv_powers = [[Fr::one(); 256]; 4];
+        let mut base = Fr(limbs(ROOT_OF_UNITY.0)).inverse().unwrap();
+        for table in &mut inv_powers {
+            for i in 1..256 {
+                table[i] = table[i - 1];
+                table[i].mul_assign(&base);
+            }
+            for _ in 0..8 {
+                base.square();
+            }
+        }
+
+        let mut dlog = Vec::with_capacity(256);
+        let mut h = Fr(limbs(ROOT_OF_UNITY.0));
+        for _ in 0..24 {
+            h.square();
+        }
+        let mut cur = Fr::one();
+        for i in 0..256 {
+            dlog.push(((cur.0).0[0], i as u8));
+            cur.mul_assign(&h);
+        }
+        dlog.sort();
+        assert!(dlog.windows(2).all(|w| w[0].0 != w[1].0));
+
+        SqrtTables { inv_powers, dlog }
+    }
+
+    /// Returns `i` such that `a = ROOT_OF_UNITY^(i * 2^24)`, given that `a`
+    /// has order dividing 2^8.
+    fn dlog(&self, a: &Fr) -> usize {
+        let i = self
+            .dlog
+            .binary_search_by_key(&(a.0).0[0], |&(limb, _)| limb)
+            .expect("element has order dividing 2^8");
+
+        self.dlog[i].1 as usize
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl ConstantTimeEq for Fr {
+    fn ct_eq(&self, other: &Fr) -> Choice {
+        (self.0).0.ct_eq(&(other.0).0)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl ConditionallySelectable for Fr {
+    fn conditional_select(a: &Fr, b: &Fr, choice: Choice) -> Fr {
+        let mut res = a.0;
+        for (r, b) in res.0.iter_mut().zip((b.0).0.iter()) {
+            *r = Limb::conditional_select(r, b, choice);
+        }
+
+        Fr(res)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl CtField for Fr {
+    fn ct_inverse(&self) -> CtOption<Self> {
+        count_op!(fr.inverse);
+        // By Fermat's little theorem, self^(r - 2) is the inverse of any
+        // nonzero element.
+        let mut exp = MODULUS;
+        exp.sub_noborrow(&FrRepr::from(2));
+
+        CtOption::new(self.pow(exp), !self.ct_is_zero())
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl CtSqrtField for Fr {
+    fn ct_sqrt(&self) -> CtOption<Self> {
+        count_op!(fr.sqrt);
+        // Tonelli-Shank's algorithm for q mod 16 = 1, with every loop running
+        // for its maximum number of iterations and the steps that would
+        // have been skipped discarded by conditional selection.
+        // https://eprint.iacr.org/2012/685.pdf (page 12, algorithm 5)
+
+        // w = self^((t - 1) // 2)
+        let w = self.pow([
+            0x7fff2dff7fffffff,
+            0x4d0ec02a9ded201,
+            0x94cebea4199cec04,
+            0x39f6d3a9,
+        ]);
+
+        let mut v = S;
+        let mut x = *self;
+        x.mul_assign(&w);
+        let mut b = x;
+        b.mul_assign(&w);
+        let mut z = Fr(limbs(ROOT_OF_UNITY.0));
+
+        for max_v in (1..=S).rev() {
+            let mut k = 1;
+            let mut tmp = b;
+            tmp.square();
+            let mut j_less_than_v = Choice::from(1);
+
+            for j in 2..max_v {
+                let tmp_is_one = tmp.ct_eq(&Fr::one());
+                let mut squared = Fr::conditional_select(&tmp, &z, tmp_is_one);
+                squared.square();
+                tmp = Fr::conditional_select(&squared, &tmp, tmp_is_one);
+                let new_z = Fr::conditional_select(&z, &squared, tmp_is_one);
+                j_less_than_v &= !j.ct_eq(&v);
+                k = u32::conditional_select(&j, &k, tmp_is_one);
+                z = Fr::conditional_select(&z, &new_z, j_less_than_v);
+            }
+
+            let mut result = x;
+            result.mul_assign(&z);
+            x = Fr::conditional_select(&result, &x, b.ct_eq(&Fr::one()));
+            z.square();
+            b.mul_assign(&z);
+            v = k;
+        }
+
+        let mut check = x;
+        check.square();
+
+        CtOption::new(x, check.ct_eq(self))
+    }
+}
+
 #[cfg(test)]
 use rand::{Rand, SeedableRng, XorShiftRng};
 
@@ -928,6 +1378,27 @@ fn test_fr_legendre() {
     assert_eq!(QuadraticNonResidue, Fr::from_repr(e).unwrap().legendre());
 }
 
+#[test]
+fn test_fr_legendre_reference() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let mut samples = vec![Fr::zero(), Fr::one(), Fr::multiplicative_generator()];
+    // Elements whose Montgomery forms are just below the modulus.
+    for k in 1..100 {
+        let mut r = MODULUS;
+        r.sub_noborrow(&FrRepr::from(k));
+        samples.push(Fr(limbs(r.0)));
+    }
+    samples.extend((0..1000).map(|_| Fr::rand(&mut rng)));
+
+    for a in samples {
+        assert_eq!(a.legendre(), a.legendre_reference());
+        let mut b = a;
+        b.square();
+        assert_eq!(b.legendre(), b.legendre_reference());
+    }
+}
+
 #[test]
 fn test_fr_repr_add_nocarry() {
     let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
@@ -1009,20 +1480,20 @@ fn test_fr_repr_add_nocarry() {
 
 #[test]
 fn test_fr_is_valid() {
-    let mut a = Fr(MODULUS);
+    let mut a = Fr(limbs(MODULUS.0));
     assert!(!a.is_valid());
-    a.0.sub_noborrow(&FrRepr::from(1));
+    a.0.sub_noborrow(&limbs([1, 0, 0, 0]));
     assert!(a.is_valid());
-    assert!(Fr(FrRepr::from(0)).is_valid());
+    assert!(Fr(FrLimbs::default()).is_valid());
     assert!(
-        Fr(FrRepr([
+        Fr(limbs([
             0xffffffff00000000,
             0x53bda402fffe5bfe,
             0x3339d80809a1d805,
             0x73eda753299d7d48
         ])).is_valid()
     );
-    assert!(!Fr(FrRepr([
+    assert!(!Fr(limbs([
         0xffffffffffffffff,
         0xffffffffffffffff,
         0xffffffffffffffff,
@@ -1041,7 +1512,7 @@ fn test_fr_is_valid() {
 fn test_fr_add_assign() {
     {
         // Random number
-        let mut tmp = Fr(FrRepr([
+        let mut tmp = Fr(limbs([
             0x437ce7616d580765,
             0xd42d1ccb29d1235b,
             0xed8f753821bd1423,
@@ -1049,10 +1520,10 @@ fn test_fr_add_assign() {
         ]));
         assert!(tmp.is_valid());
         // Test that adding zero has no effect.
-        tmp.add_assign(&Fr(FrRepr::from(0)));
+        tmp.add_assign(&Fr(FrLimbs::default()));
         assert_eq!(
             tmp,
-            Fr(FrRepr([
+            Fr(limbs([
                 0x437ce7616d580765,
                 0xd42d1ccb29d1235b,
                 0xed8f753821bd1423,
@@ -1060,10 +1531,10 @@ fn test_fr_add_assign() {
             ]))
         );
         // Add one and test for the result.
-        tmp.add_assign(&Fr(FrRepr::from(1)));
+        tmp.add_assign(&Fr(limbs([1, 0, 0, 0])));
         assert_eq!(
             tmp,
-            Fr(FrRepr([
+            Fr(limbs([
                 0x437ce7616d580766,
                 0xd42d1ccb29d1235b,
                 0xed8f753821bd1423,
@@ -1071,7 +1542,7 @@ fn test_fr_add_assign() {
             ]))
         );
         // Add another random number that exercises the reduction.
-        tmp.add_assign(&Fr(FrRepr([
+        tmp.add_assign(&Fr(limbs([
             0x946f435944f7dc79,
             0xb55e7ee6533a9b9b,
             0x1e43b84c2f6194ca,
@@ -1079,7 +1550,7 @@ fn test_fr_add_assign() {
         ])));
         assert_eq!(
             tmp,
-            Fr(FrRepr([
+            Fr(limbs([
                 0xd7ec2abbb24fe3de,
                 0x35cdf7ae7d0d62f7,
                 0xd899557c477cd0e9,
@@ -1087,22 +1558,22 @@ fn test_fr_add_assign() {
             ]))
         );
         // Add one to (r - 1) and test for the result.
-        tmp = Fr(FrRepr([
+        tmp = Fr(limbs([
             0xffffffff00000000,
             0x53bda402fffe5bfe,
             0x3339d80809a1d805,
             0x73eda753299d7d48,
         ]));
-        tmp.add_assign(&Fr(FrRepr::from(1)));
+        tmp.add_assign(&Fr(limbs([1, 0, 0, 0])));
         assert!(tmp.0.is_zero());
         // Add a random number to another one such that the result is r - 1
-        tmp = Fr(FrRepr([
+        tmp = Fr(limbs([
             0xade5adacdccb6190,
             0xaa21ee0f27db3ccd,
             0x2550f4704ae39086,
             0x591d1902e7c5ba27,
         ]));
-        tmp.add_assign(&Fr(FrRepr([
+        tmp.add_assign(&Fr(limbs([
             0x521a525223349e70,
             0xa99bb5f3d8231f31,
             0xde8e397bebe477e,
@@ -1110,7 +1581,7 @@ fn test_fr_add_assign() {
         ])));
         assert_eq!(
             tmp,
-            Fr(FrRepr([
+            Fr(limbs([
                 0xffffffff00000000,
                 0x53bda402fffe5bfe,
                 0x3339d80809a1d805,
@@ -1118,7 +1589,7 @@ fn test_fr_add_assign() {
             ]))
         );
         // Add one to the result and test for it.
-        tmp.add_assign(&Fr(FrRepr::from(1)));
+        tmp.add_assign(&Fr(limbs([1, 0, 0, 0])));
         assert!(tmp.0.is_zero());
     }
 
@@ -1150,13 +1621,13 @@ fn test_fr_add_assign() {
 fn test_fr_sub_assign() {
     {
         // Test arbitrary subtraction that tests reduction.
-        let mut tmp = Fr(FrRepr([
+        let mut tmp = Fr(limbs([
             0x6a68c64b6f735a2b,
             0xd5f4d143fe0a1972,
             0x37c17f3829267c62,
             0xa2f37391f30915c,
         ]));
-        tmp.sub_assign(&Fr(FrRepr([
+        tmp.sub_assign(&Fr(limbs([
             0xade5adacdccb6190,
             0xaa21ee0f27db3ccd,
             0x2550f4704ae39086,
@@ -1164,7 +1635,7 @@ fn test_fr_sub_assign() {
         ])));
         assert_eq!(
             tmp,
-            Fr(FrRepr([
+            Fr(limbs([
                 0xbc83189d92a7f89c,
                 0x7f908737d62d38a3,
                 0x45aa62cfe7e4c3e1,
@@ -1173,13 +1644,13 @@ fn test_fr_sub_assign() {
         );
 
         // Test the opposite subtraction which doesn't test reduction.
-        tmp = Fr(FrRepr([
+        tmp = Fr(limbs([
             0xade5adacdccb6190,
             0xaa21ee0f27db3ccd,
             0x2550f4704ae39086,
             0x591d1902e7c5ba27,
         ]));
-        tmp.sub_assign(&Fr(FrRepr([
+        tmp.sub_assign(&Fr(limbs([
             0x6a68c64b6f735a2b,
             0xd5f4d143fe0a1972,
             0x37c17f3829267c62,
@@ -1187,7 +1658,7 @@ fn test_fr_sub_assign() {
         ])));
         assert_eq!(
             tmp,
-            Fr(FrRepr([
+            Fr(limbs([
                 0x437ce7616d580765,
                 0xd42d1ccb29d1235b,
                 0xed8f753821bd1423,
@@ -1196,20 +1667,20 @@ fn test_fr_sub_assign() {
         );
 
         // Test for sensible results with zero
-        tmp = Fr(FrRepr::from(0));
-        tmp.sub_assign(&Fr(FrRepr::from(0)));
+        tmp = Fr(FrLimbs::default());
+        tmp.sub_assign(&Fr(FrLimbs::default()));
         assert!(tmp.is_zero());
 
-        tmp = Fr(FrRepr([
+        tmp = Fr(limbs([
             0x437ce7616d580765,
             0xd42d1ccb29d1235b,
             0xed8f753821bd1423,
             0x4eede1c9c89528ca,
         ]));
-        tmp.sub_assign(&Fr(FrRepr::from(0)));
+        tmp.sub_assign(&Fr(FrLimbs::default()));
         assert_eq!(
             tmp,
-            Fr(FrRepr([
+            Fr(limbs([
                 0x437ce7616d580765,
                 0xd42d1ccb29d1235b,
                 0xed8f753821bd1423,
@@ -1238,20 +1709,20 @@ fn test_fr_sub_assign() {
 
 #[test]
 fn test_fr_mul_assign() {
-    let mut tmp = Fr(FrRepr([
+    let mut tmp = Fr(limbs([
         0x6b7e9b8faeefc81a,
         0xe30a8463f348ba42,
         0xeff3cb67a8279c9c,
         0x3d303651bd7c774d,
     ]));
-    tmp.mul_assign(&Fr(FrRepr([
+    tmp.mul_assign(&Fr(limbs([
         0x13ae28e3bc35ebeb,
         0xa10f4488075cae2c,
         0x8160e95a853c3b5d,
         0x5ae3f03b561a841d,
     ])));
     assert!(
-        tmp == Fr(FrRepr([
+        tmp == Fr(limbs([
             0x23717213ce710f71,
             0xdbee1fe53a16e1af,
             0xf565d3e1c2a48000,
@@ -1304,7 +1775,7 @@ fn test_fr_mul_assign() {
 
 #[test]
 fn test_fr_squaring() {
-    let mut a = Fr(FrRepr([
+    let mut a = Fr(limbs([
         0xffffffffffffffff,
         0xffffffffffffffff,
         0xffffffffffffffff,
@@ -1446,6 +1917,34 @@ fn test_fr_sqrt() {
     }
 }
 
+#[test]
+fn test_fr_sqrt_reference() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert_eq!(Fr::zero().sqrt_reference(), Some(Fr::zero()));
+
+    // Roots of unity of every 2-adic order exercise each table lookup.
+    let mut a = Fr::root_of_unity();
+    for _ in 0..S {
+        assert_eq!(a.sqrt().is_some(), a.sqrt_reference().is_some());
+        a.square();
+    }
+
+    for _ in 0..1000 {
+        let a = Fr::rand(&mut rng);
+
+        match (a.sqrt(), a.sqrt_reference()) {
+            (Some(b), Some(c)) => {
+                let mut negc = c;
+                negc.negate();
+                assert!(b == c || b == negc);
+            }
+            (None, None) => {}
+            _ => panic!("sqrt and sqrt_reference disagree on {}", a),
+        }
+    }
+}
+
 #[test]
 fn test_fr_from_into_repr() {
     // r + 1 should not be in the field
@@ -1574,6 +2073,59 @@ fn test_fr_display() {
     );
 }
 
+#[test]
+fn test_fr_hex_and_decimal_formatting() {
+    let a = Fr::from_repr(FrRepr([
+        0xc3cae746a3b5ecc7,
+        0x185ec8eb3f5b5aee,
+        0x684499ffe4b9dd99,
+        0x7c9bba7afb68faa,
+    ])).unwrap();
+
+    assert_eq!(
+        format!("{:x}", a),
+        "7c9bba7afb68faa684499ffe4b9dd99185ec8eb3f5b5aeec3cae746a3b5ecc7"
+    );
+    assert_eq!(
+        format!("{:#X}", a),
+        "0x7C9BBA7AFB68FAA684499FFE4B9DD99185EC8EB3F5B5AEEC3CAE746A3B5ECC7"
+    );
+    // Zero-padded to the width of the modulus, this matches `Display`.
+    assert_eq!(format!("Fr({:#066x})", a), format!("{}", a));
+    assert_eq!(
+        a.to_str_radix(10),
+        "3522621347486015579604497830251602654856605126151615968121393613010560740551"
+    );
+    assert_eq!(format!("{:#x}", Fr::zero()), "0x0");
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn test_fr_constants_bigint() {
+    let r = BigUint::parse_bytes(
+        b"52435875175126190479447740508185965837690552500527637822603658699938581184513",
+        10,
+    ).unwrap();
+    let one = BigUint::from(1u32);
+
+    assert_eq!(Fr::modulus_biguint(), r);
+    assert_eq!(r.bits(), u64::from(MODULUS_BITS));
+    assert_eq!(BigUint::from(R), (&one << 256) % &r);
+    assert_eq!(BigUint::from(R2), (&one << 512) % &r);
+    assert_eq!(
+        (BigUint::from(INV) * &r + 1u32) % (&one << 64),
+        BigUint::from(0u32)
+    );
+
+    let t = (&r - 1u32) >> S;
+    assert!(t.bit(0));
+    assert_eq!(BigUint::from(Fr(limbs(GENERATOR.0))), BigUint::from(7u32));
+    assert_eq!(
+        BigUint::from(Fr(limbs(ROOT_OF_UNITY.0))),
+        BigUint::from(7u32).modpow(&t, &r)
+    );
+}
+
 #[test]
 fn test_fr_num_bits() {
     assert_eq!(Fr::NUM_BITS, 255);
@@ -1606,9 +2158,22 @@ fn fr_field_tests() {
     ::tests::field::random_sqrt_tests::<Fr>();
     ::tests::field::random_frobenius_tests::<Fr, _>(Fr::char(), 13);
     ::tests::field::from_str_tests::<Fr>();
+    ::tests::field::from_str_radix_tests::<Fr>();
+    ::tests::field::random_encoding_tests::<Fr>();
+    #[cfg(feature = "bigint")]
+    ::tests::field::random_bigint_tests::<Fr>();
+    #[cfg(feature = "constant-time")]
+    ::tests::field::random_ct_tests::<Fr>();
+    #[cfg(feature = "constant-time")]
+    ::tests::field::random_ct_sqrt_tests::<Fr>();
+    ::tests::field::random_operator_tests::<Fr>();
+    ::tests::field::from_bytes_reduce_tests::<Fr>();
+    ::tests::fft::random_fft_tests::<Fr>();
+    ::tests::poly::random_poly_tests::<Fr>();
 }
 
 #[test]
 fn fr_repr_tests() {
     ::tests::repr::random_repr_tests::<FrRepr>();
+    ::tests::repr::random_from_wide_repr_tests::<Fr>();
 }
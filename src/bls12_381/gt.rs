@@ -0,0 +1,535 @@
+use super::fq::{Fq, FqRepr, GT_GENERATOR};
+use super::fq12::Fq12;
+use super::fq2::Fq2;
+use super::fq6::Fq6;
+use super::{Bls12, Fr, FrRepr};
+use rand::{Rand, Rng};
+use std::fmt;
+use {
+    EncodedTarget, Engine, Field, FieldEncoding, GroupDecodingError, PrimeField, PrimeFieldRepr,
+    TargetGroup, Wnaf, WnafGroup,
+};
+
+/// An element of the target group of the BLS12-381 pairing; the subgroup
+/// of `Fq12` of order `r`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Gt(pub(crate) Fq12);
+
+impl fmt::Display for Gt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Gt({})", self.0)
+    }
+}
+
+impl Rand for Gt {
+    fn rand<R: Rng>(rng: &mut R) -> Self {
+        Gt::generator().pow(Fr::rand(rng))
+    }
+}
+
+impl From<Gt> for Fq12 {
+    fn from(gt: Gt) -> Fq12 {
+        gt.0
+    }
+}
+
+/// The result of a miller loop of the BLS12-381 pairing, which is reduced to
+/// an element of `Gt` by final exponentiation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MillerLoopResult(pub(crate) Fq12);
+
+impl From<MillerLoopResult> for Fq12 {
+    fn from(f: MillerLoopResult) -> Fq12 {
+        f.0
+    }
+}
+
+impl ::MillerLoopResult for MillerLoopResult {
+    type Gt = Gt;
+    type Fqk = Fq12;
+
+    fn one() -> Self {
+        MillerLoopResult(Fq12::one())
+    }
+
+    fn mul_assign(&mut self, other: &Self) {
+        self.0.mul_assign(&other.0);
+    }
+
+    fn final_exponentiation(&self) -> Gt {
+        // Every line function is nonzero at points of G1, so the result of a
+        // miller loop is never zero and always has a final exponentiation.
+        Gt(Bls12::final_exponentiation(&self.0).unwrap())
+    }
+
+    fn as_fqk(&self) -> &Fq12 {
+        &self.0
+    }
+
+    fn from_fqk(f: Fq12) -> Option<Self> {
+        if f.is_zero() {
+            None
+        } else {
+            Some(MillerLoopResult(f))
+        }
+    }
+}
+
+impl Gt {
+    /// Determines if an element of `Fq12` lies in the subgroup of order `r`.
+    fn is_in_subgroup(f: &Fq12) -> bool {
+        // Cyclotomic exponentiation is only correct for elements of the
+        // cyclotomic subgroup, which contains the subgroup of order `r`.
+        f.is_cyclotomic() && f.cyclotomic_exp(Fr::char()) == Fq12::one()
+    }
+}
+
+impl TargetGroup for Gt {
+    type Engine = Bls12;
+    type Scalar = Fr;
+    type Fqk = Fq12;
+    type Uncompressed = GtUncompressed;
+    type Compressed = GtCompressed;
+
+    fn one() -> Self {
+        Gt(Fq12::one())
+    }
+
+    fn generator() -> Self {
+        Gt(GT_GENERATOR)
+    }
+
+    fn is_one(&self) -> bool {
+        self.0 == Fq12::one()
+    }
+
+    fn square(&mut self) {
+        self.0.cyclotomic_square();
+    }
+
+    fn mul_assign(&mut self, other: &Self) {
+        self.0.mul_assign(&other.0);
+    }
+
+    fn invert(&mut self) {
+        // Elements of the cyclotomic subgroup have norm one over Fq6, so
+        // their inverse is their conjugate.
+        self.0.conjugate();
+    }
+
+    fn pow<S: Into<<Self::Scalar as PrimeField>::Repr>>(&self, exp: S) -> Self {
+        Wnaf::new().scalar(exp.into()).base(*self)
+    }
+
+    fn as_fqk(&self) -> &Fq12 {
+        &self.0
+    }
+
+    fn from_fqk(f: Fq12) -> Option<Self> {
+        if Self::is_in_subgroup(&f) {
+            Some(Gt(f))
+        } else {
+            None
+        }
+    }
+
+    fn from_fqk_unchecked(f: Fq12) -> Self {
+        Gt(f)
+    }
+}
+
+/// Exponentiation in `Gt` by w-NAF, where doubling is cyclotomic squaring
+/// and subtraction is multiplication by the conjugate.
+impl WnafGroup for Gt {
+    type Scalar = Fr;
+
+    fn group_identity() -> Self {
+        Gt::one()
+    }
+
+    fn group_double(&mut self) {
+        self.square();
+    }
+
+    fn group_add(&mut self, other: &Self) {
+        self.mul_assign(other);
+    }
+
+    fn group_sub(&mut self, other: &Self) {
+        let mut other = *other;
+        other.invert();
+        self.mul_assign(&other);
+    }
+
+    fn wnaf_window_for_scalar(scalar: FrRepr) -> usize {
+        Self::wnaf_window_for_num_scalars_and_bits(1, scalar.num_bits() as usize)
+    }
+
+    fn wnaf_window_for_num_scalars(num_scalars: usize) -> usize {
+        Self::wnaf_window_for_num_scalars_and_bits(num_scalars, Fr::NUM_BITS as usize)
+    }
+}
+
+impl Gt {
+    /// Chooses the window size minimizing the number of multiplications
+    /// needed to build a window table, `2^(w - 1)`, plus those needed to
+    /// exponentiate by `num_scalars` scalars of `num_bits` bits, about
+    /// `num_bits / (w + 1)` each. Squarings do not depend on the window.
+    ///
+    /// Windows are capped at 12, a table of 2^11 elements or about 1 MiB,
+    /// as for the curves.
+    fn wnaf_window_for_num_scalars_and_bits(num_scalars: usize, num_bits: usize) -> usize {
+        let cost = |w: usize| (1 << (w - 1)) + num_scalars * num_bits / (w + 1);
+
+        (2..13).min_by_key(|&w| cost(w)).unwrap()
+    }
+}
+
+/// Reads a big endian `Fq` element, naming it `coordinate` should it fail
+/// to decode.
+fn read_fq(reader: &mut &[u8], coordinate: &'static str) -> Result<Fq, GroupDecodingError> {
+    let mut repr = FqRepr([0; 6]);
+    repr.read_be(reader).unwrap();
+    Fq::from_repr(repr).map_err(|e| GroupDecodingError::CoordinateDecodingError(coordinate, e))
+}
+
+/// Reads an `Fq6` element encoded by `to_bytes_be`, naming each coefficient
+/// in `coordinates`, in the order they are read, should it fail to decode.
+fn read_fq6(
+    reader: &mut &[u8],
+    coordinates: &[&'static str; 6],
+) -> Result<Fq6, GroupDecodingError> {
+    let mut c = [Fq::zero(); 6];
+
+    for (c, coordinate) in c.iter_mut().zip(coordinates.iter()) {
+        *c = read_fq(reader, coordinate)?;
+    }
+
+    Ok(Fq6 {
+        c0: Fq2 { c0: c[5], c1: c[4] },
+        c1: Fq2 { c0: c[3], c1: c[2] },
+        c2: Fq2 { c0: c[1], c1: c[0] },
+    })
+}
+
+/// The canonical encoding of an element of `Gt`: its twelve `Fq`
+/// coefficients as big endian integers, highest degree first, so that
+/// `c1` of `Fq12` precedes `c0`.
+#[derive(Copy, Clone)]
+pub struct GtUncompressed([u8; 576]);
+
+impl AsRef<[u8]> for GtUncompressed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for GtUncompressed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl fmt::Debug for GtUncompressed {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.0[..].fmt(formatter)
+    }
+}
+
+impl EncodedTarget for GtUncompressed {
+    type Target = Gt;
+
+    fn empty() -> Self {
+        GtUncompressed([0; 576])
+    }
+    fn size() -> usize {
+        576
+    }
+    fn to_target(&self) -> Result<Gt, GroupDecodingError> {
+        let gt = self.to_target_unchecked()?;
+
+        if !Gt::is_in_subgroup(&gt.0) {
+            Err(GroupDecodingError::NotInSubgroup)
+        } else {
+            Ok(gt)
+        }
+    }
+    fn to_target_unchecked(&self) -> Result<Gt, GroupDecodingError> {
+        let mut reader = &self.0[..];
+
+        let c1 = read_fq6(
+            &mut reader,
+            &[
+                "c1.c2.c1", "c1.c2.c0", "c1.c1.c1", "c1.c1.c0", "c1.c0.c1", "c1.c0.c0",
+            ],
+        )?;
+        let c0 = read_fq6(
+            &mut reader,
+            &[
+                "c0.c2.c1", "c0.c2.c0", "c0.c1.c1", "c0.c1.c0", "c0.c0.c1", "c0.c0.c0",
+            ],
+        )?;
+
+        Ok(Gt(Fq12 { c0, c1 }))
+    }
+    fn from_target(gt: Gt) -> Self {
+        let mut res = Self::empty();
+        res.0.copy_from_slice(&gt.0.to_bytes_be());
+
+        res
+    }
+}
+
+#[test]
+fn test_gt_generator() {
+    use {CurveAffine, Engine};
+
+    use super::{G1Affine, G2Affine};
+
+    assert_eq!(
+        Gt::generator(),
+        Bls12::pairing_gt(G1Affine::one(), G2Affine::one())
+    );
+    assert!(Gt::is_in_subgroup(&GT_GENERATOR));
+    assert!(!Gt::generator().is_one());
+}
+
+#[test]
+fn test_gt_wnaf_window() {
+    assert_eq!(Gt::wnaf_window_for_num_scalars(1), 5);
+    assert_eq!(Gt::wnaf_window_for_scalar(FrRepr::from(1)), 2);
+    assert_eq!(Gt::wnaf_window_for_num_scalars(usize::MAX / 512), 12);
+}
+
+/// The T2 torus compression of an element of `Gt`, half the size of
+/// `GtUncompressed`. An element `f` other than the identity is written as
+/// the `Fq6` element `g` with `f = (g + w) / (g - w)`, in the same order
+/// as in `GtUncompressed`. The identity is encoded by setting the
+/// second-most significant bit, with every other bit unset.
+#[derive(Copy, Clone)]
+pub struct GtCompressed([u8; 288]);
+
+impl AsRef<[u8]> for GtCompressed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for GtCompressed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl fmt::Debug for GtCompressed {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.0[..].fmt(formatter)
+    }
+}
+
+impl EncodedTarget for GtCompressed {
+    type Target = Gt;
+
+    fn empty() -> Self {
+        GtCompressed([0; 288])
+    }
+    fn size() -> usize {
+        288
+    }
+    fn to_target(&self) -> Result<Gt, GroupDecodingError> {
+        let gt = self.to_target_unchecked()?;
+
+        if !Gt::is_in_subgroup(&gt.0) {
+            Err(GroupDecodingError::NotInSubgroup)
+        } else {
+            Ok(gt)
+        }
+    }
+    fn to_target_unchecked(&self) -> Result<Gt, GroupDecodingError> {
+        // Create a copy of this representation.
+        let mut copy = self.0;
+
+        if copy[0] & (1 << 7) != 0 || copy[0] & (1 << 5) != 0 {
+            // These bits are never set.
+            return Err(GroupDecodingError::UnexpectedInformation);
+        }
+
+        if copy[0] & (1 << 6) != 0 {
+            // This is the identity, which means that if we mask away
+            // the first two bits, the entire representation should consist
+            // of zeroes.
+            copy[0] &= 0x3f;
+
+            if copy.iter().all(|b| *b == 0) {
+                Ok(Gt::one())
+            } else {
+                Err(GroupDecodingError::UnexpectedInformation)
+            }
+        } else {
+            let g = read_fq6(
+                &mut &copy[..],
+                &["g.c2.c1", "g.c2.c0", "g.c1.c1", "g.c1.c0", "g.c0.c1", "g.c0.c0"],
+            )?;
+
+            Ok(Gt(Fq12::decompress_t2(&g)))
+        }
+    }
+    fn from_target(gt: Gt) -> Self {
+        let mut res = Self::empty();
+
+        match gt.0.compress_t2() {
+            Some(g) => res.0.copy_from_slice(&g.to_bytes_be()),
+            None => {
+                // Set the second-most significant bit to indicate this
+                // element is the identity.
+                res.0[0] |= 1 << 6;
+            }
+        }
+
+        res
+    }
+}
+
+/// The CEILIDH-style T6 torus compression of an element of `Gt`, a third
+/// of the size of `GtUncompressed`. An element `f` other than the identity
+/// is written through the coefficients of its T2 representation
+/// `g = g0 + g1 * v + g2 * v^2`; usually `g2` followed by `g1`, from which
+/// `g0` can be recovered. If `g1` is zero, the third-most significant bit is
+/// set and `g2` is followed by `g0` instead. The identity is encoded by
+/// setting the second-most significant bit, with every other bit unset.
+#[derive(Copy, Clone)]
+pub struct GtCompressedT6([u8; 192]);
+
+impl AsRef<[u8]> for GtCompressedT6 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for GtCompressedT6 {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl fmt::Debug for GtCompressedT6 {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.0[..].fmt(formatter)
+    }
+}
+
+impl EncodedTarget for GtCompressedT6 {
+    type Target = Gt;
+
+    fn empty() -> Self {
+        GtCompressedT6([0; 192])
+    }
+    fn size() -> usize {
+        192
+    }
+    fn to_target(&self) -> Result<Gt, GroupDecodingError> {
+        let gt = self.to_target_unchecked()?;
+
+        if !Gt::is_in_subgroup(&gt.0) {
+            Err(GroupDecodingError::NotInSubgroup)
+        } else {
+            Ok(gt)
+        }
+    }
+    fn to_target_unchecked(&self) -> Result<Gt, GroupDecodingError> {
+        // Create a copy of this representation.
+        let mut copy = self.0;
+
+        if copy[0] & (1 << 7) != 0 {
+            // This bit is never set.
+            return Err(GroupDecodingError::UnexpectedInformation);
+        }
+
+        if copy[0] & (1 << 6) != 0 {
+            // This is the identity, which means that if we mask away
+            // the first three bits, the entire representation should consist
+            // of zeroes.
+            if copy[0] & (1 << 5) != 0 {
+                return Err(GroupDecodingError::UnexpectedInformation);
+            }
+
+            copy[0] &= 0x1f;
+
+            if copy.iter().all(|b| *b == 0) {
+                Ok(Gt::one())
+            } else {
+                Err(GroupDecodingError::UnexpectedInformation)
+            }
+        } else {
+            let exceptional = copy[0] & (1 << 5) != 0;
+
+            // Unset the three most significant bits.
+            copy[0] &= 0x1f;
+
+            let mut reader = &copy[..];
+
+            let g2 = Fq2 {
+                c1: read_fq(&mut reader, "g2 (c1)")?,
+                c0: read_fq(&mut reader, "g2 (c0)")?,
+            };
+
+            if exceptional {
+                let g0 = Fq2 {
+                    c1: read_fq(&mut reader, "g0 (c1)")?,
+                    c0: read_fq(&mut reader, "g0 (c0)")?,
+                };
+
+                Ok(Gt(Fq12::decompress_t2(&Fq6 {
+                    c0: g0,
+                    c1: Fq2::zero(),
+                    c2: g2,
+                })))
+            } else {
+                let g1 = Fq2 {
+                    c1: read_fq(&mut reader, "g1 (c1)")?,
+                    c0: read_fq(&mut reader, "g1 (c0)")?,
+                };
+
+                // g1 is only zero in the exceptional encoding.
+                Fq12::decompress_t6(&g1, &g2)
+                    .map(Gt)
+                    .ok_or(GroupDecodingError::UnexpectedInformation)
+            }
+        }
+    }
+    fn from_target(gt: Gt) -> Self {
+        let mut res = Self::empty();
+
+        match gt.0.compress_t2() {
+            Some(g) => {
+                {
+                    let mut writer = &mut res.0[..];
+
+                    g.c2.c1.into_repr().write_be(&mut writer).unwrap();
+                    g.c2.c0.into_repr().write_be(&mut writer).unwrap();
+
+                    if g.c1.is_zero() {
+                        g.c0.c1.into_repr().write_be(&mut writer).unwrap();
+                        g.c0.c0.into_repr().write_be(&mut writer).unwrap();
+                    } else {
+                        g.c1.c1.into_repr().write_be(&mut writer).unwrap();
+                        g.c1.c0.into_repr().write_be(&mut writer).unwrap();
+                    }
+                }
+
+                if g.c1.is_zero() {
+                    // Set the third-most significant bit to indicate that
+                    // g0 is written in place of g1.
+                    res.0[0] |= 1 << 5;
+                }
+            }
+            None => {
+                // Set the second-most significant bit to indicate this
+                // element is the identity.
+                res.0[0] |= 1 << 6;
+            }
+        }
+
+        res
+    }
+}
@@ -0,0 +1,406 @@
+//! Arithmetic on 32-bit limbs, used for `Fq` and `Fr` on 32-bit targets or
+//! with the `u32-limbs` feature, where the 64x64-bit products of
+//! `mac_with_carry` would be emulated with four 32x32-bit products each.
+//!
+//! Elements are stored as `Limbs`, twice as many 32-bit limbs as their
+//! 64-bit representation has, least significant first. The Montgomery radix
+//! is the same `2^(64n)`, so the arithmetic computes exactly the values the
+//! 64-bit arithmetic does, and elements convert to and from `PrimeFieldRepr`
+//! by splitting and joining limbs.
+
+use std::cmp::Ordering;
+
+/// The most 32-bit limbs of an operand.
+const MAX_LIMBS: usize = 12;
+
+/// The limbs of an element, least significant first.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Limbs<const N: usize>(pub [u32; N]);
+
+impl<const N: usize> Default for Limbs<N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Limbs([0; N])
+    }
+}
+
+impl<const N: usize> Ord for Limbs<N> {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> Ordering {
+        for (a, b) in self.0.iter().rev().zip(other.0.iter().rev()) {
+            if a < b {
+                return Ordering::Less;
+            } else if a > b {
+                return Ordering::Greater;
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+impl<const N: usize> PartialOrd for Limbs<N> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Limbs<N> {
+    /// Splits `N / 2` 64-bit limbs into 32-bit ones.
+    #[inline(always)]
+    pub const fn split(limbs: &[u64]) -> Self {
+        let mut res = [0; N];
+        let mut i = 0;
+        while i < N {
+            res[i] = (limbs[i / 2] >> (32 * (i % 2))) as u32;
+            i += 1;
+        }
+
+        Limbs(res)
+    }
+
+    /// Joins the limbs into `N / 2` 64-bit ones.
+    #[inline(always)]
+    pub fn join(&self, out: &mut [u64]) {
+        for (l, out) in self.0.chunks(2).zip(out.iter_mut()) {
+            *out = u64::from(l[0]) | (u64::from(l[1]) << 32);
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_even(&self) -> bool {
+        self.0[0] & 1 == 0
+    }
+
+    #[inline(always)]
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&e| e == 0)
+    }
+
+    #[inline(always)]
+    pub fn div2(&mut self) {
+        let mut t = 0;
+        for i in self.0.iter_mut().rev() {
+            let t2 = *i << 31;
+            *i >>= 1;
+            *i |= t;
+            t = t2;
+        }
+    }
+
+    #[inline(always)]
+    pub fn mul2(&mut self) {
+        let mut last = 0;
+        for i in &mut self.0 {
+            let tmp = *i >> 31;
+            *i <<= 1;
+            *i |= last;
+            last = tmp;
+        }
+    }
+
+    #[inline(always)]
+    pub fn add_nocarry(&mut self, other: &Self) {
+        let mut carry = 0;
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a = adc(*a, *b, &mut carry);
+        }
+    }
+
+    #[inline(always)]
+    pub fn sub_noborrow(&mut self, other: &Self) {
+        self.sub_with_borrow(other);
+    }
+
+    /// Subtracts another value, returning whether the difference wrapped
+    /// around.
+    #[inline(always)]
+    pub fn sub_with_borrow(&mut self, other: &Self) -> bool {
+        let mut borrow = 0;
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a = sbb(*a, *b, &mut borrow);
+        }
+
+        borrow != 0
+    }
+
+    /// Adds `other & mask` to this value, where `mask` is either zero or all
+    /// ones, so that `other` can be added without branching.
+    #[inline(always)]
+    pub fn add_masked(&mut self, other: &Self, mask: u32) {
+        let mut carry = 0;
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a = adc(*a, *b & mask, &mut carry);
+        }
+    }
+
+    /// Subtracts `modulus` from this value if it is not below it, without
+    /// branching on the value.
+    #[inline(always)]
+    pub fn sub_if_not_below(&mut self, modulus: &Self) {
+        let mut tmp = *self;
+        let borrow = u32::from(tmp.sub_with_borrow(modulus));
+
+        // The subtraction only borrowed if this value was already below.
+        let mask = borrow.wrapping_sub(1);
+        for (a, b) in self.0.iter_mut().zip(tmp.0.iter()) {
+            *a ^= (*a ^ *b) & mask;
+        }
+    }
+}
+
+/// Calculate a + (b * c) + carry, returning the least significant digit
+/// and setting carry to the most significant digit.
+#[inline(always)]
+fn mac_with_carry(a: u32, b: u32, c: u32, carry: &mut u32) -> u32 {
+    let tmp = u64::from(a) + u64::from(b) * u64::from(c) + u64::from(*carry);
+
+    *carry = (tmp >> 32) as u32;
+
+    tmp as u32
+}
+
+/// Calculate a + b + carry, returning the sum and modifying the carry value.
+#[inline(always)]
+pub fn adc(a: u32, b: u32, carry: &mut u32) -> u32 {
+    let tmp = u64::from(a) + u64::from(b) + u64::from(*carry);
+
+    *carry = (tmp >> 32) as u32;
+
+    tmp as u32
+}
+
+/// Calculate a - b - borrow, returning the result and modifying the borrow
+/// value.
+#[inline(always)]
+pub fn sbb(a: u32, b: u32, borrow: &mut u32) -> u32 {
+    let tmp = (1u64 << 32) + u64::from(a) - u64::from(b) - u64::from(*borrow);
+
+    *borrow = if tmp >> 32 == 0 { 1 } else { 0 };
+
+    tmp as u32
+}
+
+/// Writes the full product of `a` and `b`, which have the same number of
+/// limbs, to `res`, which has twice as many.
+#[inline(always)]
+pub fn mul(a: &[u32], b: &[u32], res: &mut [u32]) {
+    let n = a.len();
+    let mut t = [0u32; 2 * MAX_LIMBS];
+    for i in 0..n {
+        let mut carry = 0;
+        for j in 0..n {
+            t[i + j] = mac_with_carry(t[i + j], a[i], b[j], &mut carry);
+        }
+        t[i + n] = carry;
+    }
+
+    res.copy_from_slice(&t[..2 * n]);
+}
+
+/// Writes the full square of `a` to `res`, which has twice as many limbs.
+#[inline(always)]
+pub fn square(a: &[u32], res: &mut [u32]) {
+    let n = a.len();
+
+    // The products of distinct limbs, each of which appears twice.
+    let mut t = [0u32; 2 * MAX_LIMBS];
+    for i in 0..n {
+        let mut carry = 0;
+        for j in (i + 1)..n {
+            t[i + j] = mac_with_carry(t[i + j], a[i], a[j], &mut carry);
+        }
+        t[i + n] = carry;
+    }
+
+    let mut top = 0;
+    for l in t[..2 * n].iter_mut() {
+        let next = *l >> 31;
+        *l = (*l << 1) | top;
+        top = next;
+    }
+
+    let mut carry = 0;
+    for i in 0..n {
+        t[2 * i] = mac_with_carry(t[2 * i], a[i], a[i], &mut carry);
+        t[2 * i + 1] = adc(t[2 * i + 1], 0, &mut carry);
+    }
+
+    res.copy_from_slice(&t[..2 * n]);
+}
+
+/// Performs Montgomery reduction of `t`, which has twice as many limbs as
+/// `modulus` and is below `modulus * 2^(32n)`, writing `t / 2^(32n)` modulo
+/// `modulus` to `res`. The result may exceed `modulus` by one multiple of
+/// it, exactly as the 64-bit reduction before its final subtraction. `inv`
+/// is `-modulus^(-1) mod 2^64`, whose low half is the inverse modulo 2^32.
+#[inline(always)]
+pub fn mont_reduce(t: &[u32], modulus: &[u32], inv: u64, res: &mut [u32]) {
+    // The reduction here is based on Algorithm 14.32 in Handbook of Applied
+    // Cryptography <http://cacr.uwaterloo.ca/hac/about/chap14.pdf>.
+    let n = modulus.len();
+    let inv = inv as u32;
+    let mut t32 = [0u32; 2 * MAX_LIMBS];
+    t32[..2 * n].copy_from_slice(t);
+
+    let mut carry2 = 0;
+    for i in 0..n {
+        let k = t32[i].wrapping_mul(inv);
+        let mut carry = 0;
+        for j in 0..n {
+            t32[i + j] = mac_with_carry(t32[i + j], k, modulus[j], &mut carry);
+        }
+        t32[i + n] = adc(t32[i + n], carry2, &mut carry);
+        carry2 = carry;
+    }
+
+    res.copy_from_slice(&t32[n..2 * n]);
+}
+
+#[cfg(test)]
+use rand::{Rand, Rng, SeedableRng, XorShiftRng};
+#[cfg(test)]
+use {PrimeField, PrimeFieldRepr, WidePrimeFieldRepr};
+
+/// Checks the 32-bit routines against the 64-bit arithmetic of
+/// `PrimeFieldRepr`. `N` is the number of 32-bit limbs of `F`, and `W`
+/// twice that.
+#[cfg(test)]
+fn random_limb32_tests<F: PrimeField, const N: usize, const W: usize>(inv: u64)
+where
+    F::Repr: WidePrimeFieldRepr,
+{
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let modulus = F::char();
+    let modulus32 = Limbs::<N>::split(modulus.as_ref()).0;
+    let mut max = modulus;
+    max.sub_noborrow(&F::Repr::from(1));
+
+    let mut samples = vec![F::Repr::from(0), F::Repr::from(1), max];
+    samples.extend((0..200).map(|_| F::rand(&mut rng).into_repr()));
+    samples.extend((0..200).map(|_| {
+        let mut r = F::Repr::rand(&mut rng);
+        for l in r.as_mut().iter_mut() {
+            if rng.gen() {
+                *l = !0;
+            }
+        }
+        r.div_rem(&modulus).unwrap().1
+    }));
+
+    for a in &samples {
+        let a32 = Limbs::<N>::split(a.as_ref());
+        let mut a64 = F::Repr::default();
+        a32.join(a64.as_mut());
+        assert_eq!(a64, *a);
+        assert_eq!(a32.is_even(), a.is_even());
+        assert_eq!(a32.is_zero(), a.is_zero());
+
+        for b in samples.iter().take(50) {
+            let b32 = Limbs::<N>::split(b.as_ref()).0;
+            let expected = a.mul_wide(b);
+
+            let mut c32 = [0; W];
+            mul(&a32.0, &b32, &mut c32);
+            let mut c = <F::Repr as WidePrimeFieldRepr>::Wide::default();
+            Limbs(c32).join(c.as_mut());
+            assert_eq!(c, expected);
+
+            if a == b {
+                let mut s32 = [0; W];
+                square(&a32.0, &mut s32);
+                assert_eq!(s32, c32);
+            }
+
+            // res * 2^(64n) = a * b modulo the modulus, with res < 2 * modulus.
+            let mut res32 = [0; N];
+            mont_reduce(&c32, &modulus32, inv, &mut res32);
+            let mut res = F::Repr::default();
+            Limbs(res32).join(res.as_mut());
+            let mut shifted = <F::Repr as WidePrimeFieldRepr>::Wide::default();
+            let n = res.as_ref().len();
+            shifted.as_mut()[n..].copy_from_slice(res.as_ref());
+            assert_eq!(
+                F::Repr::rem_wide(&shifted, &modulus),
+                F::Repr::rem_wide(&expected, &modulus)
+            );
+            if !res.sub_with_borrow(&modulus) {
+                assert!(res < modulus);
+            }
+
+            // The additive operations agree with those of the 64-bit limbs.
+            let b32 = Limbs(b32);
+            let mut sum = *a;
+            sum.add_nocarry(b);
+            let mut sum32 = a32;
+            sum32.add_nocarry(&b32);
+            assert_eq!(sum32, Limbs::split(sum.as_ref()));
+
+            let mut diff = *a;
+            let borrow = diff.sub_with_borrow(b);
+            let mut diff32 = a32;
+            assert_eq!(diff32.sub_with_borrow(&b32), borrow);
+            assert_eq!(diff32, Limbs::split(diff.as_ref()));
+            assert_eq!(a32.cmp(&b32), a.cmp(b));
+        }
+
+        let mut c32 = [0; W];
+        square(&a32.0, &mut c32);
+        let mut c = <F::Repr as WidePrimeFieldRepr>::Wide::default();
+        Limbs(c32).join(c.as_mut());
+        assert_eq!(c, a.mul_wide(a));
+
+        let (mut half, mut half32) = (*a, a32);
+        half.div2();
+        half32.div2();
+        assert_eq!(half32, Limbs::split(half.as_ref()));
+        half.mul2();
+        half32.mul2();
+        assert_eq!(half32, Limbs::split(half.as_ref()));
+
+        let mut reduced32 = a32;
+        reduced32.add_masked(&Limbs(modulus32), 0);
+        assert_eq!(reduced32, a32);
+        reduced32.add_masked(&Limbs(modulus32), !0);
+        let mut sum32 = a32;
+        sum32.add_nocarry(&Limbs(modulus32));
+        assert_eq!(reduced32, sum32);
+        reduced32.sub_if_not_below(&Limbs(modulus32));
+        assert_eq!(reduced32, a32);
+        reduced32.sub_if_not_below(&Limbs(modulus32));
+        assert_eq!(reduced32, a32);
+    }
+}
+
+#[test]
+fn test_fq_limb32() {
+    random_limb32_tests::<super::Fq, 12, 24>(0x89f3fffcfffcfffd);
+
+    // The largest product the reduction accepts.
+    let modulus = Limbs::<12>::split(super::Fq::char().as_ref());
+    let mut max = modulus;
+    max.sub_noborrow(&Limbs::split(&[1, 0, 0, 0, 0, 0]));
+    let mut t = [0; 24];
+    mul(&max.0, &max.0, &mut t);
+    let mut res = Limbs([0; 12]);
+    mont_reduce(&t, &modulus.0, 0x89f3fffcfffcfffd, &mut res.0);
+    res.sub_if_not_below(&modulus);
+    assert!(res < modulus);
+}
+
+#[test]
+fn test_fr_limb32() {
+    random_limb32_tests::<super::Fr, 8, 16>(0xfffffffeffffffff);
+
+    let modulus = Limbs::<8>::split(super::Fr::char().as_ref());
+    let mut max = modulus;
+    max.sub_noborrow(&Limbs::split(&[1, 0, 0, 0]));
+    let mut t = [0; 16];
+    mul(&max.0, &max.0, &mut t);
+    let mut res = Limbs([0; 8]);
+    mont_reduce(&t, &modulus.0, 0xfffffffeffffffff, &mut res.0);
+    res.sub_if_not_below(&modulus);
+    assert!(res < modulus);
+}
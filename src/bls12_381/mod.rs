@@ -1,13 +1,80 @@
+/// Counts an operation in the thread-local counters of `counters`, if the
+/// `op-counting` feature is enabled.
+macro_rules! count_op {
+    ($field:ident . $op:ident) => {
+        #[cfg(feature = "op-counting")]
+        ::bls12_381::counters::record(|c| c.$field.$op += 1);
+    };
+}
+
+/// Evaluates `$adx`, which calls into the `adx` assembly, if the
+/// `adx-support` feature is enabled and the processor supports it, and
+/// `$portable` otherwise. Each operation dispatches through this once, so
+/// that support is only checked once per operation.
+macro_rules! adx_or {
+    ($adx:expr, $portable:expr) => {{
+        #[cfg(all(
+            target_arch = "x86_64",
+            target_pointer_width = "64",
+            feature = "adx-support",
+            not(feature = "u32-limbs")
+        ))]
+        {
+            use bls12_381::adx;
+            if adx::enabled() {
+                unsafe { $adx }
+            } else {
+                $portable
+            }
+        }
+        #[cfg(not(all(
+            target_arch = "x86_64",
+            target_pointer_width = "64",
+            feature = "adx-support",
+            not(feature = "u32-limbs")
+        )))]
+        {
+            $portable
+        }
+    }};
+}
+
+#[macro_use]
+mod ops;
+
+mod accumulator;
+#[cfg(all(
+    target_arch = "x86_64",
+    target_pointer_width = "64",
+    not(feature = "u32-limbs"),
+    any(test, feature = "adx-support")
+))]
+mod adx;
+#[cfg(feature = "op-counting")]
+pub mod counters;
 mod ec;
 mod fq;
 mod fq12;
 mod fq2;
 mod fq6;
 mod fr;
+mod gt;
+#[cfg(any(test, feature = "u32-limbs", target_pointer_width = "32"))]
+mod limb32;
+
+/// The word `Fq` and `Fr` store their limbs in: 32 bits on 32-bit targets
+/// or with the `u32-limbs` feature, and 64 bits otherwise.
+#[cfg(not(any(feature = "u32-limbs", target_pointer_width = "32")))]
+type Limb = u64;
+#[cfg(any(feature = "u32-limbs", target_pointer_width = "32"))]
+type Limb = u32;
+#[cfg(any(test, feature = "reference-pairing"))]
+pub mod reference;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::accumulator::MillerLoopAccumulator;
 pub use self::ec::{
     G1, G1Affine, G1Compressed, G1Prepared, G1Uncompressed, G2, G2Affine, G2Compressed, G2Prepared,
     G2Uncompressed,
@@ -17,13 +84,24 @@ pub use self::fq12::Fq12;
 pub use self::fq2::Fq2;
 pub use self::fq6::Fq6;
 pub use self::fr::{Fr, FrRepr};
+pub use self::gt::{Gt, GtCompressed, GtCompressedT6, GtUncompressed, MillerLoopResult};
 
-use super::{BitIterator, CurveAffine, Engine, Field};
+use self::ec::G2PreparedCoeffs;
+use super::{
+    BitIterator, CurveAffine, Engine, Field, GroupDecodingError, MultiMillerLoop, PrimeField,
+    PrimeFieldRepr,
+};
+use std::io::{self, Read, Write};
 
 // The BLS parameter x for BLS12-381 is -0xd201000000010000
 const BLS_X: u64 = 0xd201000000010000;
 const BLS_X_IS_NEGATIVE: bool = true;
 
+// The number of doubling and addition steps of the miller loop, each of
+// which contributes one line function.
+const MILLER_LOOP_STEPS: usize =
+    (64 + (BLS_X >> 1).count_ones() - (BLS_X >> 1).leading_zeros() - 1) as usize;
+
 #[derive(Clone, Debug)]
 pub struct Bls12;
 
@@ -36,6 +114,7 @@ impl Engine for Bls12 {
     type Fq = Fq;
     type Fqe = Fq2;
     type Fqk = Fq12;
+    type Gt = Gt;
 
     fn miller_loop<'a, I>(i: I) -> Self::Fqk
     where
@@ -46,29 +125,92 @@ impl Engine for Bls12 {
             ),
         >,
     {
-        let mut pairs = vec![];
-        for &(p, q) in i {
-            if !p.is_zero() && !q.is_zero() {
-                pairs.push((p, q.coeffs.iter()));
-            }
-        }
+        Self::multi_miller_loop(i.into_iter().map(|&(p, q)| (p, q))).0
+    }
+
+    fn final_exponentiation(r: &Fq12) -> Option<Fq12> {
+        let mut f1 = *r;
+        f1.conjugate();
 
-        // Twisting isomorphism from E to E'
-        fn ell(f: &mut Fq12, coeffs: &(Fq2, Fq2, Fq2), p: &G1Affine) {
-            let mut c0 = coeffs.0;
-            let mut c1 = coeffs.1;
+        match r.inverse() {
+            Some(mut f2) => {
+                // Easy part: r^((q^6 - 1)(q^2 + 1)), which lands in the
+                // cyclotomic subgroup.
+                let mut r = f1;
+                r.mul_assign(&f2);
+                f2 = r;
+                r.frobenius_map(2);
+                r.mul_assign(&f2);
+
+                // Hard part, following Hayashida-Hayasaka-Teruya,
+                // https://eprint.iacr.org/2020/875.pdf. Computes
+                // r^((x - 1)^2 (x + q) (x^2 + q^2 - 1) + 3), which is
+                // r raised to 3 (q^4 - q^2 + 1) divided by the group order.
+                //
+                // Because r is in the cyclotomic subgroup, conjugation
+                // is inversion and we can use cyclotomic squaring.
+                fn exp_by_x(f: &Fq12) -> Fq12 {
+                    let mut f = f.cyclotomic_exp([BLS_X]);
+                    if BLS_X_IS_NEGATIVE {
+                        f.conjugate();
+                    }
+                    f
+                }
+
+                fn exp_by_x_minus_one(f: &Fq12) -> Fq12 {
+                    let mut tmp = *f;
+                    tmp.conjugate();
+                    let mut f = exp_by_x(f);
+                    f.mul_assign(&tmp);
+                    f
+                }
 
-            c0.c0.mul_assign(&p.y);
-            c0.c1.mul_assign(&p.y);
+                // t0 = r^((x - 1)^2)
+                let t0 = exp_by_x_minus_one(&exp_by_x_minus_one(&r));
+
+                // t1 = t0^(x + q)
+                let mut t1 = exp_by_x(&t0);
+                let mut tmp = t0;
+                tmp.frobenius_map(1);
+                t1.mul_assign(&tmp);
+
+                // t2 = t1^(x^2 + q^2 - 1)
+                let mut t2 = exp_by_x(&exp_by_x(&t1));
+                tmp = t1;
+                tmp.frobenius_map(2);
+                t2.mul_assign(&tmp);
+                t1.conjugate();
+                t2.mul_assign(&t1);
+
+                // t2 * r^3
+                tmp = r;
+                tmp.cyclotomic_square();
+                tmp.mul_assign(&r);
+                t2.mul_assign(&tmp);
+
+                Some(t2)
+            }
+            None => None,
+        }
+    }
+}
 
-            c1.c0.mul_assign(&p.x);
-            c1.c1.mul_assign(&p.x);
+impl MultiMillerLoop for Bls12 {
+    type Result = MillerLoopResult;
 
-            // Sparse multiplication in Fq12
-            f.mul_by_014(&coeffs.2, &c1, &c0);
+    fn multi_miller_loop<'a, I>(terms: I) -> MillerLoopResult
+    where
+        I: IntoIterator<Item = (&'a G1Prepared, &'a G2Prepared)>,
+    {
+        let mut pairs = vec![];
+        for (p, q) in terms {
+            if !p.is_zero() && !q.is_zero() {
+                pairs.push((p, q));
+            }
         }
 
         let mut f = Fq12::one();
+        let mut step = 0;
 
         let mut found_one = false;
         for i in BitIterator::new(&[BLS_X >> 1]) {
@@ -77,31 +219,40 @@ impl Engine for Bls12 {
                 continue;
             }
 
-            for &mut (p, ref mut coeffs) in &mut pairs {
-                ell(&mut f, coeffs.next().unwrap(), &p.0);
+            for &(p, q) in &pairs {
+                q.ell(&mut f, step, &p.0);
             }
+            step += 1;
 
             if i {
-                for &mut (p, ref mut coeffs) in &mut pairs {
-                    ell(&mut f, coeffs.next().unwrap(), &p.0);
+                for &(p, q) in &pairs {
+                    q.ell(&mut f, step, &p.0);
                 }
+                step += 1;
             }
 
             f.square();
         }
 
-        for &mut (p, ref mut coeffs) in &mut pairs {
-            ell(&mut f, coeffs.next().unwrap(), &p.0);
+        for &(p, q) in &pairs {
+            q.ell(&mut f, step, &p.0);
         }
 
         if BLS_X_IS_NEGATIVE {
             f.conjugate();
         }
 
-        f
+        MillerLoopResult(f)
     }
+}
 
-    fn final_exponentiation(r: &Fq12) -> Option<Fq12> {
+#[cfg(test)]
+impl Bls12 {
+    /// Performs final exponentiation using generic `Fq12` exponentiation
+    /// and squaring throughout the hard part. This is slower than
+    /// `Engine::final_exponentiation` and only retained as a reference
+    /// for testing it.
+    fn final_exponentiation_reference(r: &Fq12) -> Option<Fq12> {
         let mut f1 = *r;
         f1.conjugate();
 
@@ -168,7 +319,7 @@ impl G2Prepared {
     pub fn from_affine(q: G2Affine) -> Self {
         if q.is_zero() {
             return G2Prepared {
-                coeffs: vec![],
+                coeffs: G2PreparedCoeffs::Projective(vec![]),
                 infinity: true,
             };
         }
@@ -352,13 +503,424 @@ impl G2Prepared {
         coeffs.push(doubling_step(&mut r));
 
         G2Prepared {
+            coeffs: G2PreparedCoeffs::Projective(coeffs),
+            infinity: false,
+        }
+    }
+
+    /// Prepares `q` and converts the result into the compact form. See
+    /// `compact`.
+    pub fn from_affine_compact(q: G2Affine) -> Self {
+        Self::from_affine(q).compact()
+    }
+
+    /// Prepares many points at once, producing line functions in the
+    /// compact form. The doubling and addition steps of all points are
+    /// performed in lockstep in affine coordinates, sharing one inversion
+    /// per step, which is cheaper than preparing each point separately.
+    pub fn batch_from_affine(qs: &[G2Affine]) -> Vec<Self> {
+        // Performs a doubling step, or an addition step with the original
+        // point, on every point of the loop at once.
+        fn step(
+            ts: &mut [(Fq2, Fq2)],
+            qs: &[&G2Affine],
+            coeffs: &mut [Vec<(Fq2, Fq2)>],
+            addition: bool,
+        ) {
+            // The slope is 3 * t.x^2 / (2 * t.y) when doubling and
+            // (q.y - t.y) / (q.x - t.x) when adding.
+            let mut denominators: Vec<Fq2> = ts
+                .iter()
+                .zip(qs)
+                .map(|(t, q)| {
+                    if addition {
+                        let mut d = q.x;
+                        d.sub_assign(&t.0);
+                        d
+                    } else {
+                        let mut d = t.1;
+                        d.double();
+                        d
+                    }
+                })
+                .collect();
+            Fq2::batch_invert(&mut denominators);
+
+            for (((t, q), inv), coeffs) in ts.iter_mut().zip(qs).zip(denominators).zip(coeffs) {
+                let mut lambda = if addition {
+                    let mut n = q.y;
+                    n.sub_assign(&t.1);
+                    n
+                } else {
+                    let mut n = t.0;
+                    n.square();
+                    let tmp = n;
+                    n.double();
+                    n.add_assign(&tmp);
+                    n
+                };
+                lambda.mul_assign(&inv);
+
+                let mut c1 = lambda;
+                c1.negate();
+                let mut c2 = lambda;
+                c2.mul_assign(&t.0);
+                c2.sub_assign(&t.1);
+                coeffs.push((c1, c2));
+
+                let mut x = lambda;
+                x.square();
+                x.sub_assign(&t.0);
+                x.sub_assign(if addition { &q.x } else { &t.0 });
+
+                let mut y = t.0;
+                y.sub_assign(&x);
+                y.mul_assign(&lambda);
+                y.sub_assign(&t.1);
+
+                *t = (x, y);
+            }
+        }
+
+        let points: Vec<&G2Affine> = qs.iter().filter(|q| !q.is_zero()).collect();
+        let mut ts: Vec<(Fq2, Fq2)> = points.iter().map(|q| (q.x, q.y)).collect();
+        let mut coeffs = vec![Vec::with_capacity(MILLER_LOOP_STEPS); points.len()];
+
+        let mut found_one = false;
+        for i in BitIterator::new([BLS_X >> 1]) {
+            if !found_one {
+                found_one = i;
+                continue;
+            }
+
+            step(&mut ts, &points, &mut coeffs, false);
+
+            if i {
+                step(&mut ts, &points, &mut coeffs, true);
+            }
+        }
+
+        step(&mut ts, &points, &mut coeffs, false);
+
+        let mut coeffs = coeffs.into_iter();
+        qs.iter()
+            .map(|q| {
+                if q.is_zero() {
+                    G2Prepared {
+                        coeffs: G2PreparedCoeffs::Affine(vec![]),
+                        infinity: true,
+                    }
+                } else {
+                    G2Prepared {
+                        coeffs: G2PreparedCoeffs::Affine(coeffs.next().unwrap()),
+                        infinity: false,
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Determines if the line functions are stored in the compact, affine
+    /// form.
+    pub fn is_compact(&self) -> bool {
+        match self.coeffs {
+            G2PreparedCoeffs::Projective(_) => false,
+            G2PreparedCoeffs::Affine(_) => true,
+        }
+    }
+
+    /// Scales every line function so that its `c0` coefficient is one,
+    /// which only needs two `Fq2` elements per step of the miller loop
+    /// instead of three.
+    ///
+    /// The miller loop then differs by a factor in `Fq2`, which is
+    /// eliminated by the final exponentiation, so pairings are unaffected
+    /// but the raw miller loop results of the two forms are not equal.
+    pub fn compact(&self) -> Self {
+        let coeffs = match self.coeffs {
+            G2PreparedCoeffs::Projective(ref coeffs) => coeffs,
+            G2PreparedCoeffs::Affine(_) => return self.clone(),
+        };
+
+        // The coefficients are nonzero for lines through points of the
+        // prime order subgroup.
+        let mut inverses: Vec<Fq2> = coeffs.iter().map(|c| c.0).collect();
+        Fq2::batch_invert(&mut inverses);
+
+        let affine = coeffs
+            .iter()
+            .zip(inverses)
+            .map(|(c, inv)| {
+                let mut c1 = c.1;
+                c1.mul_assign(&inv);
+                let mut c2 = c.2;
+                c2.mul_assign(&inv);
+                (c1, c2)
+            })
+            .collect();
+
+        G2Prepared {
+            coeffs: G2PreparedCoeffs::Affine(affine),
+            infinity: self.infinity,
+        }
+    }
+
+    // Multiplies `f` by the line function of the given step of the miller
+    // loop, evaluated at `p` through the twisting isomorphism from E to E'.
+    fn ell(&self, f: &mut Fq12, step: usize, p: &G1Affine) {
+        match self.coeffs {
+            G2PreparedCoeffs::Projective(ref coeffs) => {
+                let coeffs = &coeffs[step];
+                let mut c0 = coeffs.0;
+                let mut c1 = coeffs.1;
+
+                c0.c0.mul_assign(&p.y);
+                c0.c1.mul_assign(&p.y);
+
+                c1.c0.mul_assign(&p.x);
+                c1.c1.mul_assign(&p.x);
+
+                // Sparse multiplication in Fq12
+                f.mul_by_014(&coeffs.2, &c1, &c0);
+            }
+            G2PreparedCoeffs::Affine(ref coeffs) => {
+                let coeffs = &coeffs[step];
+                let c0 = Fq2 {
+                    c0: p.y,
+                    c1: Fq::zero(),
+                };
+                let mut c1 = coeffs.0;
+
+                c1.c0.mul_assign(&p.x);
+                c1.c1.mul_assign(&p.x);
+
+                f.mul_by_014(&coeffs.1, &c1, &c0);
+            }
+        }
+    }
+
+    /// Writes the line functions of this element. The encoding is a byte
+    /// giving the form (0 for the point at infinity, 1 for the projective
+    /// form and 2 for the compact form) followed by the coefficients of
+    /// every step, each `Fq2` element written as `c1` then `c0` in big
+    /// endian, as in `G2Uncompressed`.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        fn write_fq2<W: Write>(writer: &mut W, c: &Fq2) -> io::Result<()> {
+            c.c1.into_repr().write_be(&mut *writer)?;
+            c.c0.into_repr().write_be(&mut *writer)
+        }
+
+        if self.infinity {
+            return writer.write_all(&[0]);
+        }
+
+        match self.coeffs {
+            G2PreparedCoeffs::Projective(ref coeffs) => {
+                writer.write_all(&[1])?;
+                for c in coeffs {
+                    write_fq2(&mut writer, &c.0)?;
+                    write_fq2(&mut writer, &c.1)?;
+                    write_fq2(&mut writer, &c.2)?;
+                }
+            }
+            G2PreparedCoeffs::Affine(ref coeffs) => {
+                writer.write_all(&[2])?;
+                for c in coeffs {
+                    write_fq2(&mut writer, &c.0)?;
+                    write_fq2(&mut writer, &c.1)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads line functions written by `write`, checking that they are the
+    /// line functions of `q`. They are checked by walking the miller loop
+    /// in affine coordinates, which is much cheaper than preparing `q`
+    /// again.
+    ///
+    /// Only the compact form can be checked this way, so the projective
+    /// form is rejected with `UnexpectedCompressionMode`; it can only be
+    /// read from trusted input with `read_unchecked`.
+    pub fn read<R: Read>(reader: R, q: &G2Affine) -> io::Result<Self> {
+        let prepared = Self::read_unchecked(reader)?;
+
+        let valid = if prepared.infinity || q.is_zero() {
+            prepared.infinity == q.is_zero()
+        } else {
+            match prepared.coeffs {
+                G2PreparedCoeffs::Projective(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        GroupDecodingError::UnexpectedCompressionMode,
+                    ));
+                }
+                G2PreparedCoeffs::Affine(ref coeffs) => Self::check_affine(coeffs, q),
+            }
+        };
+
+        if valid {
+            Ok(prepared)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                GroupDecodingError::InvalidPrecomputation,
+            ))
+        }
+    }
+
+    /// Reads line functions written by `write` without checking which
+    /// point they belong to. This should only be used with trusted input,
+    /// such as a cache written by this process; pairings computed with
+    /// corrupted line functions are meaningless.
+    pub fn read_unchecked<R: Read>(mut reader: R) -> io::Result<Self> {
+        fn read_fq2<R: Read>(reader: &mut R) -> io::Result<Fq2> {
+            fn read_fq<R: Read>(reader: &mut R, description: &'static str) -> io::Result<Fq> {
+                let mut repr = FqRepr::default();
+                repr.read_be(&mut *reader)?;
+                Fq::from_repr(repr).map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        GroupDecodingError::CoordinateDecodingError(description, e),
+                    )
+                })
+            }
+
+            let c1 = read_fq(reader, "line coefficient (c1)")?;
+            let c0 = read_fq(reader, "line coefficient (c0)")?;
+
+            Ok(Fq2 { c0, c1 })
+        }
+
+        let mut form = [0u8];
+        reader.read_exact(&mut form)?;
+
+        let coeffs = match form[0] {
+            0 => {
+                return Ok(G2Prepared {
+                    coeffs: G2PreparedCoeffs::Projective(vec![]),
+                    infinity: true,
+                });
+            }
+            1 => G2PreparedCoeffs::Projective(
+                (0..MILLER_LOOP_STEPS)
+                    .map(|_| {
+                        Ok((
+                            read_fq2(&mut reader)?,
+                            read_fq2(&mut reader)?,
+                            read_fq2(&mut reader)?,
+                        ))
+                    })
+                    .collect::<io::Result<_>>()?,
+            ),
+            2 => G2PreparedCoeffs::Affine(
+                (0..MILLER_LOOP_STEPS)
+                    .map(|_| Ok((read_fq2(&mut reader)?, read_fq2(&mut reader)?)))
+                    .collect::<io::Result<_>>()?,
+            ),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    GroupDecodingError::UnexpectedCompressionMode,
+                ));
+            }
+        };
+
+        Ok(G2Prepared {
             coeffs,
             infinity: false,
+        })
+    }
+
+    // Checks that compact line functions belong to `q` by tracking the
+    // point of the miller loop in affine coordinates. Each line function
+    // (c1, c2) through t with slope lambda has c1 = -lambda and
+    // c2 = lambda * t.x - t.y.
+    fn check_affine(coeffs: &[(Fq2, Fq2)], q: &G2Affine) -> bool {
+        // Checks the line function, then moves t to 2t or t + q.
+        fn step(t: &mut (Fq2, Fq2), c: &(Fq2, Fq2), q: Option<&G2Affine>) -> bool {
+            let mut lambda = c.0;
+            lambda.negate();
+
+            let (mut lhs, mut rhs) = match q {
+                // lambda * 2y = 3x^2
+                None => {
+                    let mut lhs = t.1;
+                    lhs.double();
+                    let mut rhs = t.0;
+                    rhs.square();
+                    let tmp = rhs;
+                    rhs.double();
+                    rhs.add_assign(&tmp);
+                    (lhs, rhs)
+                }
+                // lambda * (q.x - t.x) = q.y - t.y
+                Some(q) => {
+                    let mut lhs = q.x;
+                    lhs.sub_assign(&t.0);
+                    let mut rhs = q.y;
+                    rhs.sub_assign(&t.1);
+                    (lhs, rhs)
+                }
+            };
+            lhs.mul_assign(&lambda);
+            if lhs != rhs {
+                return false;
+            }
+
+            rhs = lambda;
+            rhs.mul_assign(&t.0);
+            rhs.sub_assign(&t.1);
+            if rhs != c.1 {
+                return false;
+            }
+
+            // x' = lambda^2 - t.x - (t.x or q.x)
+            lhs = lambda;
+            lhs.square();
+            lhs.sub_assign(&t.0);
+            lhs.sub_assign(match q {
+                None => &t.0,
+                Some(q) => &q.x,
+            });
+
+            // y' = lambda * (t.x - x') - t.y
+            rhs = t.0;
+            rhs.sub_assign(&lhs);
+            rhs.mul_assign(&lambda);
+            rhs.sub_assign(&t.1);
+
+            *t = (lhs, rhs);
+
+            true
+        }
+
+        let mut t = (q.x, q.y);
+        let mut coeffs = coeffs.iter();
+
+        let mut found_one = false;
+        for i in BitIterator::new([BLS_X >> 1]) {
+            if !found_one {
+                found_one = i;
+                continue;
+            }
+
+            if !step(&mut t, coeffs.next().unwrap(), None) {
+                return false;
+            }
+
+            if i && !step(&mut t, coeffs.next().unwrap(), Some(q)) {
+                return false;
+            }
         }
+
+        step(&mut t, coeffs.next().unwrap(), None)
     }
 }
 
 #[test]
 fn bls12_engine_tests() {
     ::tests::engine::engine_tests::<Bls12>();
+    ::tests::engine::multi_miller_loop_tests::<Bls12>();
 }
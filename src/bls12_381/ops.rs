@@ -0,0 +1,226 @@
+//! Implementations of the `std::ops` operators, and of `Sum` and `Product`,
+//! in terms of the `Field`, `CurveProjective` and `CurveAffine` methods.
+
+/// Implements a binary operator and its assigning variant, taking either
+/// operand by value or by reference, given a function computing
+/// `lhs op= &rhs`.
+macro_rules! impl_binop {
+    ($lhs:ty, $rhs:ty, $op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident, $f:expr) => {
+        impl<'b> ::std::ops::$assign<&'b $rhs> for $lhs {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: &'b $rhs) {
+                ($f)(self, rhs)
+            }
+        }
+
+        impl ::std::ops::$assign<$rhs> for $lhs {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: $rhs) {
+                ::std::ops::$assign::$assign_fn(self, &rhs)
+            }
+        }
+
+        impl<'a, 'b> ::std::ops::$op<&'b $rhs> for &'a $lhs {
+            type Output = $lhs;
+
+            #[inline]
+            fn $op_fn(self, rhs: &'b $rhs) -> $lhs {
+                let mut res = *self;
+                ::std::ops::$assign::$assign_fn(&mut res, rhs);
+                res
+            }
+        }
+
+        impl<'a> ::std::ops::$op<$rhs> for &'a $lhs {
+            type Output = $lhs;
+
+            #[inline]
+            fn $op_fn(self, rhs: $rhs) -> $lhs {
+                ::std::ops::$op::$op_fn(self, &rhs)
+            }
+        }
+
+        impl<'b> ::std::ops::$op<&'b $rhs> for $lhs {
+            type Output = $lhs;
+
+            #[inline]
+            fn $op_fn(self, rhs: &'b $rhs) -> $lhs {
+                ::std::ops::$op::$op_fn(&self, rhs)
+            }
+        }
+
+        impl ::std::ops::$op<$rhs> for $lhs {
+            type Output = $lhs;
+
+            #[inline]
+            fn $op_fn(self, rhs: $rhs) -> $lhs {
+                ::std::ops::$op::$op_fn(&self, &rhs)
+            }
+        }
+    };
+}
+
+/// Implements `Neg` by value and by reference, given a function negating
+/// an element in place.
+macro_rules! impl_neg {
+    ($t:ty, $f:expr) => {
+        impl ::std::ops::Neg for $t {
+            type Output = $t;
+
+            #[inline]
+            fn neg(mut self) -> $t {
+                ($f)(&mut self);
+                self
+            }
+        }
+
+        impl<'a> ::std::ops::Neg for &'a $t {
+            type Output = $t;
+
+            #[inline]
+            fn neg(self) -> $t {
+                -*self
+            }
+        }
+    };
+}
+
+/// Implements `Sum` or `Product` of an iterator of `$item`s, by value and
+/// by reference, given the identity of the operator.
+macro_rules! impl_fold {
+    ($trait:ident, $trait_fn:ident, $op:ident, $op_fn:ident, $t:ty, $item:ty, $identity:expr) => {
+        impl ::std::iter::$trait<$item> for $t {
+            fn $trait_fn<I: Iterator<Item = $item>>(iter: I) -> $t {
+                iter.fold($identity, |acc, e| ::std::ops::$op::$op_fn(acc, e))
+            }
+        }
+
+        impl<'a> ::std::iter::$trait<&'a $item> for $t {
+            fn $trait_fn<I: Iterator<Item = &'a $item>>(iter: I) -> $t {
+                iter.fold($identity, |acc, e| ::std::ops::$op::$op_fn(acc, e))
+            }
+        }
+    };
+}
+
+/// Implements the arithmetic operators of a field, along with `Sum` and
+/// `Product`.
+macro_rules! impl_field_ops {
+    ($field:ident) => {
+        impl_binop!($field, $field, Add, add, AddAssign, add_assign, ::Field::add_assign);
+        impl_binop!($field, $field, Sub, sub, SubAssign, sub_assign, ::Field::sub_assign);
+        impl_binop!($field, $field, Mul, mul, MulAssign, mul_assign, ::Field::mul_assign);
+        impl_neg!($field, ::Field::negate);
+        impl_fold!(Sum, sum, Add, add, $field, $field, <$field as ::Field>::zero());
+        impl_fold!(Product, product, Mul, mul, $field, $field, <$field as ::Field>::one());
+    };
+}
+
+/// Implements addition and subtraction of projective and affine points,
+/// multiplication by a scalar and negation, along with `Sum` of points.
+macro_rules! impl_curve_ops {
+    ($projective:ident, $affine:ident, $scalarfield:ident) => {
+        impl_binop!(
+            $projective,
+            $projective,
+            Add,
+            add,
+            AddAssign,
+            add_assign,
+            ::CurveProjective::add_assign
+        );
+        impl_binop!(
+            $projective,
+            $projective,
+            Sub,
+            sub,
+            SubAssign,
+            sub_assign,
+            ::CurveProjective::sub_assign
+        );
+        impl_binop!(
+            $projective,
+            $affine,
+            Add,
+            add,
+            AddAssign,
+            add_assign,
+            ::CurveProjective::add_assign_mixed
+        );
+        impl_binop!(
+            $projective,
+            $affine,
+            Sub,
+            sub,
+            SubAssign,
+            sub_assign,
+            |a: &mut $projective, b: &$affine| ::CurveProjective::add_assign_mixed(a, &-b)
+        );
+        impl_binop!(
+            $projective,
+            $scalarfield,
+            Mul,
+            mul,
+            MulAssign,
+            mul_assign,
+            |a: &mut $projective, b: &$scalarfield| ::CurveProjective::mul_assign(a, *b)
+        );
+        impl_neg!($projective, ::CurveProjective::negate);
+        impl_neg!($affine, ::CurveAffine::negate);
+
+        impl<'a, 'b> ::std::ops::Mul<&'b $scalarfield> for &'a $affine {
+            type Output = $projective;
+
+            #[inline]
+            fn mul(self, rhs: &'b $scalarfield) -> $projective {
+                ::CurveAffine::mul(self, *rhs)
+            }
+        }
+
+        impl<'a> ::std::ops::Mul<$scalarfield> for &'a $affine {
+            type Output = $projective;
+
+            #[inline]
+            fn mul(self, rhs: $scalarfield) -> $projective {
+                ::CurveAffine::mul(self, rhs)
+            }
+        }
+
+        impl<'b> ::std::ops::Mul<&'b $scalarfield> for $affine {
+            type Output = $projective;
+
+            #[inline]
+            fn mul(self, rhs: &'b $scalarfield) -> $projective {
+                ::CurveAffine::mul(&self, *rhs)
+            }
+        }
+
+        impl ::std::ops::Mul<$scalarfield> for $affine {
+            type Output = $projective;
+
+            #[inline]
+            fn mul(self, rhs: $scalarfield) -> $projective {
+                ::CurveAffine::mul(&self, rhs)
+            }
+        }
+
+        impl_fold!(
+            Sum,
+            sum,
+            Add,
+            add,
+            $projective,
+            $projective,
+            <$projective as ::CurveProjective>::zero()
+        );
+        impl_fold!(
+            Sum,
+            sum,
+            Add,
+            add,
+            $projective,
+            $affine,
+            <$projective as ::CurveProjective>::zero()
+        );
+    };
+}
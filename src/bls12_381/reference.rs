@@ -0,0 +1,441 @@
+//! A deliberately simple implementation of the Tate, ate and Weil pairings
+//! on BLS12-381, used to test the optimized pairing against.
+//!
+//! G2 is untwisted into E(Fq12), the Miller loops are textbook double-and-add
+//! loops in affine coordinates (vertical lines included) and the final
+//! exponentiation raises to (q^12 - 1) / r directly. Nothing here is fast or
+//! constant time.
+
+use super::{Fq, Fq12, Fq2, Fq6, Fr, G1Affine, G2Affine, BLS_X, BLS_X_IS_NEGATIVE};
+use {BitIterator, CurveAffine, Field, PrimeField};
+
+/// A point of E(Fq12): y^2 = x^3 + 4, in affine coordinates.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Point {
+    x: Fq12,
+    y: Fq12,
+    infinity: bool,
+}
+
+impl Point {
+    pub fn zero() -> Self {
+        Point {
+            x: Fq12::zero(),
+            y: Fq12::one(),
+            infinity: true,
+        }
+    }
+
+    fn new(x: Fq12, y: Fq12) -> Self {
+        Point {
+            x,
+            y,
+            infinity: false,
+        }
+    }
+
+    /// Embeds a point of G1 into E(Fq12).
+    pub fn from_g1(p: &G1Affine) -> Self {
+        if p.is_zero() {
+            Point::zero()
+        } else {
+            Point::new(embed_fq(p.x), embed_fq(p.y))
+        }
+    }
+
+    /// Maps a point of G2, on the twist E': y^2 = x^3 + 4(u + 1), to
+    /// E(Fq12) through the untwisting isomorphism (x, y) -> (x / w^2, y / w^3).
+    pub fn from_g2(q: &G2Affine) -> Self {
+        if q.is_zero() {
+            return Point::zero();
+        }
+
+        let w = Fq12 {
+            c0: Fq6::zero(),
+            c1: Fq6::one(),
+        };
+        let mut w2 = w;
+        w2.square();
+        let mut w3 = w2;
+        w3.mul_assign(&w);
+
+        let mut x = embed_fq2(q.x);
+        x.mul_assign(&w2.inverse().unwrap());
+        let mut y = embed_fq2(q.y);
+        y.mul_assign(&w3.inverse().unwrap());
+
+        Point::new(x, y)
+    }
+
+    /// Determines if the point lies on E.
+    pub fn is_on_curve(&self) -> bool {
+        if self.infinity {
+            return true;
+        }
+
+        let mut lhs = self.y;
+        lhs.square();
+
+        let mut rhs = self.x;
+        rhs.square();
+        rhs.mul_assign(&self.x);
+        rhs.add_assign(&embed_fq(Fq::from_repr(4.into()).unwrap()));
+
+        lhs == rhs
+    }
+
+    pub fn negate(&self) -> Self {
+        let mut res = *self;
+        if !res.infinity {
+            res.y.negate();
+        }
+        res
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.infinity {
+            return *other;
+        }
+        if other.infinity {
+            return *self;
+        }
+
+        match self.slope(other) {
+            // The points are each other's negation.
+            None => Point::zero(),
+            Some(lambda) => {
+                let mut x3 = lambda;
+                x3.square();
+                x3.sub_assign(&self.x);
+                x3.sub_assign(&other.x);
+
+                let mut y3 = self.x;
+                y3.sub_assign(&x3);
+                y3.mul_assign(&lambda);
+                y3.sub_assign(&self.y);
+
+                Point::new(x3, y3)
+            }
+        }
+    }
+
+    pub fn mul<S: AsRef<[u64]>>(&self, scalar: S) -> Self {
+        let mut res = Point::zero();
+        for i in BitIterator::new(scalar) {
+            res = res.add(&res);
+            if i {
+                res = res.add(self);
+            }
+        }
+        res
+    }
+
+    // The slope of the line through two finite points, or of the tangent if
+    // they are equal, or None if that line is vertical.
+    fn slope(&self, other: &Self) -> Option<Fq12> {
+        let (mut num, mut den);
+        if self.x == other.x {
+            if self.y != other.y || self.y.is_zero() {
+                return None;
+            }
+
+            // 3 x^2 / 2 y
+            num = self.x;
+            num.square();
+            let tmp = num;
+            num.double();
+            num.add_assign(&tmp);
+
+            den = self.y;
+            den.double();
+        } else {
+            num = other.y;
+            num.sub_assign(&self.y);
+            den = other.x;
+            den.sub_assign(&self.x);
+        }
+
+        num.mul_assign(&den.inverse().unwrap());
+        Some(num)
+    }
+
+    // Evaluates at `at` the line through two finite points (the tangent if
+    // they are equal), divided by the vertical line through their sum.
+    fn line(&self, other: &Self, at: &Self) -> Fq12 {
+        assert!(!self.infinity && !other.infinity && !at.infinity);
+
+        match self.slope(other) {
+            // The line is the vertical x - x1 and the sum is the point at
+            // infinity, whose vertical line is 1.
+            None => {
+                let mut l = at.x;
+                l.sub_assign(&self.x);
+                l
+            }
+            // (y - y1 - lambda (x - x1)) / (x - x3)
+            Some(lambda) => {
+                let mut l = at.x;
+                l.sub_assign(&self.x);
+                l.mul_assign(&lambda);
+                l.negate();
+                l.add_assign(&at.y);
+                l.sub_assign(&self.y);
+
+                let mut v = at.x;
+                v.sub_assign(&self.add(other).x);
+
+                l.mul_assign(&v.inverse().unwrap());
+                l
+            }
+        }
+    }
+}
+
+fn embed_fq(a: Fq) -> Fq12 {
+    embed_fq2(Fq2 {
+        c0: a,
+        c1: Fq::zero(),
+    })
+}
+
+fn embed_fq2(a: Fq2) -> Fq12 {
+    Fq12 {
+        c0: Fq6 {
+            c0: a,
+            c1: Fq2::zero(),
+            c2: Fq2::zero(),
+        },
+        c1: Fq6::zero(),
+    }
+}
+
+/// Computes the Miller function f_{n, p} evaluated at `q`, with the
+/// normalization f_{n, p} = prod (line / vertical) over the steps of
+/// double-and-add. Both points must be finite and `q` must not be a
+/// multiple of `p`.
+pub fn miller_loop<S: AsRef<[u64]>>(n: S, p: &Point, q: &Point) -> Fq12 {
+    let mut f = Fq12::one();
+    let mut t = *p;
+
+    let mut found_one = false;
+    for i in BitIterator::new(n) {
+        if !found_one {
+            found_one = i;
+            continue;
+        }
+
+        f.square();
+        f.mul_assign(&t.line(&t, q));
+        t = t.add(&t);
+
+        if i {
+            f.mul_assign(&t.line(p, q));
+            t = t.add(p);
+        }
+    }
+
+    f
+}
+
+/// Raises `f` to (q^12 - 1) / r with a single square-and-multiply
+/// exponentiation.
+pub fn final_exponentiation(f: &Fq12) -> Fq12 {
+    let q = Fq::char();
+    let mut q12 = vec![1];
+    for _ in 0..12 {
+        q12 = bigint::mul(&q12, q.as_ref());
+    }
+    bigint::sub_one(&mut q12);
+
+    let (e, rem) = bigint::div_rem(&q12, Fr::char().as_ref());
+    assert!(bigint::is_zero(&rem));
+
+    f.pow(&e)
+}
+
+/// The reduced Tate pairing f_{r, P}(Q)^((q^12 - 1) / r).
+pub fn tate(p: &G1Affine, q: &G2Affine) -> Fq12 {
+    if p.is_zero() || q.is_zero() {
+        return Fq12::one();
+    }
+
+    final_exponentiation(&miller_loop(
+        Fr::char(),
+        &Point::from_g1(p),
+        &Point::from_g2(q),
+    ))
+}
+
+/// The ate pairing f_{T, Q}(P)^((q^12 - 1) / r) with T = t - 1 = x, the
+/// BLS parameter.
+///
+/// As x is negative, this uses f_{x, Q} = 1 / (f_{-x, Q} v_{-xQ}). The
+/// vertical line v_{-xQ} evaluated at P lies in Fq and is eliminated by the
+/// final exponentiation, so it is omitted.
+pub fn ate(p: &G1Affine, q: &G2Affine) -> Fq12 {
+    if p.is_zero() || q.is_zero() {
+        return Fq12::one();
+    }
+
+    let mut f = miller_loop([BLS_X], &Point::from_g2(q), &Point::from_g1(p));
+    if BLS_X_IS_NEGATIVE {
+        f = f.inverse().unwrap();
+    }
+
+    final_exponentiation(&f)
+}
+
+/// The Weil pairing (-1)^r f_{r, P}(Q) / f_{r, Q}(P), which needs no final
+/// exponentiation.
+pub fn weil(p: &G1Affine, q: &G2Affine) -> Fq12 {
+    if p.is_zero() || q.is_zero() {
+        return Fq12::one();
+    }
+
+    let p = Point::from_g1(p);
+    let q = Point::from_g2(q);
+
+    let mut f = miller_loop(Fr::char(), &p, &q);
+    f.mul_assign(&miller_loop(Fr::char(), &q, &p).inverse().unwrap());
+
+    // r is odd.
+    f.negate();
+
+    f
+}
+
+/// Minimal arithmetic on little-endian multiprecision integers, enough to
+/// compute the exponents used by the reference pairings.
+pub mod bigint {
+    use std::cmp::Ordering;
+
+    pub fn is_zero(a: &[u64]) -> bool {
+        a.iter().all(|&l| l == 0)
+    }
+
+    pub fn mul(a: &[u64], b: &[u64]) -> Vec<u64> {
+        let mut res = vec![0u64; a.len() + b.len()];
+        for (i, &a) in a.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, &b) in b.iter().enumerate() {
+                let t = u128::from(res[i + j]) + u128::from(a) * u128::from(b) + carry;
+                res[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            res[i + b.len()] = carry as u64;
+        }
+        res
+    }
+
+    pub fn sub_one(a: &mut [u64]) {
+        for l in a.iter_mut() {
+            let (t, borrow) = l.overflowing_sub(1);
+            *l = t;
+            if !borrow {
+                return;
+            }
+        }
+        panic!("subtraction underflowed");
+    }
+
+    fn cmp(a: &[u64], b: &[u64]) -> Ordering {
+        let len = a.len().max(b.len());
+        for i in (0..len).rev() {
+            let a = a.get(i).cloned().unwrap_or(0);
+            let b = b.get(i).cloned().unwrap_or(0);
+            match a.cmp(&b) {
+                Ordering::Equal => {}
+                ord => return ord,
+            }
+        }
+        Ordering::Equal
+    }
+
+    // a -= b, where a >= b.
+    fn sub_assign(a: &mut [u64], b: &[u64]) {
+        let mut borrow = false;
+        for (i, l) in a.iter_mut().enumerate() {
+            let b = b.get(i).cloned().unwrap_or(0);
+            let (t, b1) = l.overflowing_sub(b);
+            let (t, b2) = t.overflowing_sub(borrow as u64);
+            *l = t;
+            borrow = b1 || b2;
+        }
+        assert!(!borrow, "subtraction underflowed");
+    }
+
+    /// Returns the quotient and remainder of `a` divided by `d`, by binary
+    /// long division.
+    pub fn div_rem(a: &[u64], d: &[u64]) -> (Vec<u64>, Vec<u64>) {
+        assert!(!is_zero(d), "division by zero");
+
+        let mut q = vec![0u64; a.len()];
+        let mut r = vec![0u64; d.len() + 1];
+
+        for i in (0..a.len() * 64).rev() {
+            // r = 2r + bit i of a
+            let mut carry = (a[i / 64] >> (i % 64)) & 1;
+            for l in r.iter_mut() {
+                let next = *l >> 63;
+                *l = (*l << 1) | carry;
+                carry = next;
+            }
+
+            if cmp(&r, d) != Ordering::Less {
+                sub_assign(&mut r, d);
+                q[i / 64] |= 1 << (i % 64);
+            }
+        }
+
+        (q, r)
+    }
+}
+
+#[test]
+fn test_reference_pairings() {
+    use super::{G1, G2};
+    use rand::{Rand, SeedableRng, XorShiftRng};
+    use CurveProjective;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert!(Point::from_g1(&G1Affine::one()).is_on_curve());
+    assert!(Point::from_g2(&G2Affine::one()).is_on_curve());
+    assert_eq!(
+        Point::from_g2(&G2Affine::one()).mul(Fr::char()),
+        Point::zero()
+    );
+
+    {
+        let a = Fr::rand(&mut rng);
+        let b = Fr::rand(&mut rng);
+        let mut ab = a;
+        ab.mul_assign(&b);
+
+        let p = G1::rand(&mut rng);
+        let q = G2::rand(&mut rng);
+
+        let mut pa = p;
+        pa.mul_assign(a);
+        let mut qb = q;
+        qb.mul_assign(b);
+
+        let pa = pa.into_affine();
+        let qb = qb.into_affine();
+        let p = p.into_affine();
+        let q = q.into_affine();
+
+        let sample = Point::from_g2(&qb);
+        assert!(sample.is_on_curve());
+        assert_eq!(sample, Point::from_g2(&q).mul(b.into_repr()));
+
+        // e(aP, bQ) = e(P, Q)^(ab) and e(P, Q) is a nontrivial r-th root
+        // of unity, for each pairing.
+        for pairing in &[tate as fn(&G1Affine, &G2Affine) -> Fq12, ate, weil] {
+            let e = pairing(&p, &q);
+            assert!(e != Fq12::one());
+            assert_eq!(e.pow(Fr::char()), Fq12::one());
+            assert_eq!(pairing(&pa, &qb), e.pow(ab.into_repr()));
+        }
+    }
+}
@@ -609,3 +609,486 @@ fn test_g2_compressed_invalid_vectors() {
         }
     }
 }
+
+#[test]
+fn test_final_exponentiation_against_reference() {
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..100 {
+        let a = Fq12::rand(&mut rng);
+
+        assert_eq!(
+            Bls12::final_exponentiation(&a),
+            Bls12::final_exponentiation_reference(&a)
+        );
+    }
+
+    for _ in 0..10 {
+        let p = G1Affine::from(G1::rand(&mut rng)).prepare();
+        let q = G2Affine::from(G2::rand(&mut rng)).prepare();
+        let f = Bls12::miller_loop(&[(&p, &q)]);
+
+        assert_eq!(
+            Bls12::final_exponentiation(&f),
+            Bls12::final_exponentiation_reference(&f)
+        );
+    }
+
+    assert_eq!(Bls12::final_exponentiation(&Fq12::zero()), None);
+    assert_eq!(Bls12::final_exponentiation_reference(&Fq12::zero()), None);
+}
+
+#[test]
+fn test_field_encodings_match_point_encodings() {
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..10 {
+        let p = G1::rand(&mut rng).into_affine();
+        let encoded = p.into_uncompressed();
+        assert_eq!(&encoded.as_ref()[0..48], &p.x.to_bytes_be()[..]);
+        assert_eq!(&encoded.as_ref()[48..96], &p.y.to_bytes_be()[..]);
+
+        let q = G2::rand(&mut rng).into_affine();
+        let encoded = q.into_uncompressed();
+        assert_eq!(&encoded.as_ref()[0..96], &q.x.to_bytes_be()[..]);
+        assert_eq!(&encoded.as_ref()[96..192], &q.y.to_bytes_be()[..]);
+
+        let gt = Gt::rand(&mut rng);
+        assert_eq!(gt.to_uncompressed().as_ref(), &gt.0.to_bytes_be()[..]);
+        assert_eq!(
+            Fq12::from_bytes_be(gt.to_uncompressed().as_ref()).unwrap(),
+            gt.0
+        );
+    }
+}
+
+#[test]
+fn test_gt_uncompressed_vectors() {
+    let g = Gt::generator();
+    let encoded = g.to_uncompressed();
+
+    // The generator is encoded with c1 before c0, and with the highest
+    // degree coefficients first.
+    {
+        let mut expected = vec![];
+        g.0.c1.c2.c1.into_repr().write_be(&mut expected).unwrap();
+        g.0.c1.c2.c0.into_repr().write_be(&mut expected).unwrap();
+        assert_eq!(&encoded.as_ref()[0..96], &expected[..]);
+
+        let mut expected = vec![];
+        g.0.c0.c0.c1.into_repr().write_be(&mut expected).unwrap();
+        g.0.c0.c0.c0.into_repr().write_be(&mut expected).unwrap();
+        assert_eq!(&encoded.as_ref()[480..], &expected[..]);
+    }
+
+    // The identity is encoded as the integer one in its last coefficient.
+    {
+        let encoded = Gt::one().to_uncompressed();
+        assert!(encoded.as_ref()[0..575].iter().all(|b| *b == 0));
+        assert_eq!(encoded.as_ref()[575], 1);
+        assert!(encoded.to_target().unwrap().is_one());
+    }
+
+    // The all-zero encoding is not an element of the group.
+    if let Err(GroupDecodingError::NotInSubgroup) = GtUncompressed::empty().to_target() {
+        // :)
+    } else {
+        panic!("should have rejected zero");
+    }
+
+    // Coefficients must be canonical.
+    for i in 0..12 {
+        let mut e = encoded;
+        for b in &mut e.as_mut()[i * 48..(i + 1) * 48] {
+            *b = 0xff;
+        }
+        if let Err(GroupDecodingError::CoordinateDecodingError(..)) = e.to_target() {
+            // :)
+        } else {
+            panic!("should have rejected a non-canonical coefficient");
+        }
+        if let Err(GroupDecodingError::CoordinateDecodingError(..)) = e.to_target_unchecked() {
+            // :)
+        } else {
+            panic!("should have rejected a non-canonical coefficient");
+        }
+    }
+
+    // Elements of Fq12 outside of the subgroup are rejected.
+    {
+        let mut e = encoded;
+        e.as_mut()[575] ^= 1;
+        if let Err(GroupDecodingError::NotInSubgroup) = e.to_target() {
+            // :)
+        } else {
+            panic!("should have rejected an element outside of the subgroup");
+        }
+        assert!(e.to_target_unchecked().is_ok());
+    }
+
+    // Elements of the cyclotomic subgroup which are not of order r are
+    // rejected.
+    {
+        use rand::{Rand, SeedableRng, XorShiftRng};
+
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let f = Fq12::rand(&mut rng);
+        let mut g = f;
+        g.conjugate();
+        g.mul_assign(&f.inverse().unwrap());
+        let mut f = g;
+        f.frobenius_map(2);
+        f.mul_assign(&g);
+        assert!(f.is_cyclotomic());
+
+        let e = Gt::from_fqk_unchecked(f).to_uncompressed();
+        if let Err(GroupDecodingError::NotInSubgroup) = e.to_target() {
+            // :)
+        } else {
+            panic!("should have rejected an element outside of the subgroup");
+        }
+    }
+}
+
+#[test]
+fn test_gt_compressed_vectors() {
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    fn test_identity<E: EncodedTarget<Target = Gt>>(flags: &[u8]) {
+        let encoded = E::from_target(Gt::one());
+        assert_eq!(encoded.as_ref()[0], 0b0100_0000);
+        assert!(encoded.as_ref()[1..].iter().all(|b| *b == 0));
+        assert!(encoded.to_target().unwrap().is_one());
+
+        for flag in flags {
+            let mut e = encoded;
+            e.as_mut()[0] |= flag;
+            if let Err(GroupDecodingError::UnexpectedInformation) = e.to_target() {
+                // :)
+            } else {
+                panic!("should have rejected the identity with unexpected flags");
+            }
+        }
+
+        for i in 1..E::size() {
+            let mut e = encoded;
+            e.as_mut()[i] |= 0b0000_0001;
+            if let Err(GroupDecodingError::UnexpectedInformation) = e.to_target() {
+                // :)
+            } else {
+                panic!("should have rejected the identity with nonzero coefficients");
+            }
+        }
+    }
+
+    test_identity::<GtCompressed>(&[0b1000_0000, 0b0010_0000]);
+    test_identity::<GtCompressedT6>(&[0b1000_0000, 0b0010_0000]);
+
+    for _ in 0..10 {
+        let g = Gt::rand(&mut rng);
+
+        let encoded = g.to_compressed();
+        assert_eq!(encoded.to_target().unwrap(), g);
+
+        let mut e = encoded;
+        e.as_mut()[0] |= 0b1000_0000;
+        if let Err(GroupDecodingError::UnexpectedInformation) = e.to_target() {
+            // :)
+        } else {
+            panic!("should have rejected the encoding with the unused bit set");
+        }
+
+        let mut e = encoded;
+        e.as_mut()[287] ^= 1;
+        if let Err(GroupDecodingError::NotInSubgroup) = e.to_target() {
+            // :)
+        } else {
+            panic!("should have rejected an element outside of the subgroup");
+        }
+        assert!(e.to_target_unchecked().is_ok());
+
+        let encoded = GtCompressedT6::from_target(g);
+        assert_eq!(encoded.to_target().unwrap(), g);
+        assert_eq!(encoded.as_ref()[0] & 0b1110_0000, 0);
+
+        let mut e = encoded;
+        e.as_mut()[191] ^= 1;
+        if let Err(GroupDecodingError::NotInSubgroup) = e.to_target() {
+            // :)
+        } else {
+            panic!("should have rejected an element outside of the subgroup");
+        }
+        assert!(e.to_target_unchecked().unwrap().as_fqk().is_cyclotomic());
+
+        // A zero g1 must be encoded in the exceptional form.
+        let mut e = encoded;
+        for b in &mut e.as_mut()[96..] {
+            *b = 0;
+        }
+        if let Err(GroupDecodingError::UnexpectedInformation) = e.to_target_unchecked() {
+            // :)
+        } else {
+            panic!("should have rejected a zero g1");
+        }
+
+        // Coefficients must be canonical.
+        for i in 0..4 {
+            let mut e = encoded;
+            for b in &mut e.as_mut()[i * 48..(i + 1) * 48] {
+                *b = 0xff;
+            }
+            e.as_mut()[0] &= 0x1f;
+            if let Err(GroupDecodingError::CoordinateDecodingError(..)) = e.to_target() {
+                // :)
+            } else {
+                panic!("should have rejected a non-canonical coefficient");
+            }
+        }
+    }
+
+    // Elements of Gt with a zero g1 are vanishingly rare, so exercise the
+    // exceptional form of the T6 encoding with other elements of the torus.
+    for _ in 0..10 {
+        let mut g = Fq6::rand(&mut rng);
+        g.c1 = Fq2::zero();
+        let f = Fq12::decompress_t2(&g);
+
+        let encoded = GtCompressedT6::from_target(Gt::from_fqk_unchecked(f));
+        assert_eq!(encoded.as_ref()[0] & 0b1110_0000, 0b0010_0000);
+        assert_eq!(*encoded.to_target_unchecked().unwrap().as_fqk(), f);
+        if let Err(GroupDecodingError::NotInSubgroup) = encoded.to_target() {
+            // :)
+        } else {
+            panic!("should have rejected an element outside of the subgroup");
+        }
+    }
+}
+
+#[test]
+fn test_prepared_encoding() {
+    use rand::{Rand, SeedableRng, XorShiftRng};
+    use std::io::ErrorKind;
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..5 {
+        let p = G1Affine::from(G1::rand(&mut rng));
+        let q = G2Affine::from(G2::rand(&mut rng));
+        let other = G2Affine::from(G2::rand(&mut rng));
+
+        let p_prepared = p.prepare();
+        let mut encoded = vec![];
+        p_prepared.write(&mut encoded).unwrap();
+        assert_eq!(&encoded[..], p.into_uncompressed().as_ref());
+        assert_eq!(G1Prepared::read(&encoded[..]).unwrap().0, p);
+        assert_eq!(G1Prepared::read_unchecked(&encoded[..]).unwrap().0, p);
+
+        let projective = q.prepare();
+        let compact = G2Prepared::from_affine_compact(q);
+        assert!(!projective.is_compact());
+        assert!(compact.is_compact());
+        assert!(projective.compact().is_compact());
+
+        // The miller loops only agree after final exponentiation.
+        assert_eq!(
+            Bls12::final_exponentiation(&Bls12::miller_loop(&[(&p_prepared, &compact)])),
+            Bls12::final_exponentiation(&Bls12::miller_loop(&[(&p_prepared, &projective)]))
+        );
+
+        // Only the compact form can be checked against its point.
+        let mut encoded = vec![];
+        projective.write(&mut encoded).unwrap();
+        assert_eq!(encoded.len(), 1 + 68 * 3 * 96);
+        let decoded = G2Prepared::read_unchecked(&encoded[..]).unwrap();
+        assert!(!decoded.is_compact());
+        assert_eq!(
+            Bls12::miller_loop(&[(&p_prepared, &decoded)]),
+            Bls12::miller_loop(&[(&p_prepared, &projective)])
+        );
+        let err = G2Prepared::read(&encoded[..], &q).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        if let Some(&GroupDecodingError::UnexpectedCompressionMode) =
+            err.get_ref().and_then(|e| e.downcast_ref())
+        {
+        } else {
+            panic!("projective line functions should be rejected");
+        }
+
+        let len = 1 + 68 * 2 * 96;
+        let mut encoded = vec![];
+        compact.write(&mut encoded).unwrap();
+        assert_eq!(encoded.len(), len);
+
+        for decoded in &[
+            G2Prepared::read(&encoded[..], &q).unwrap(),
+            G2Prepared::read_unchecked(&encoded[..]).unwrap(),
+        ] {
+            assert!(decoded.is_compact());
+            assert_eq!(
+                Bls12::miller_loop(&[(&p_prepared, decoded)]),
+                Bls12::miller_loop(&[(&p_prepared, &compact)])
+            );
+        }
+
+        // The line functions of a different point are rejected.
+        assert_eq!(
+            G2Prepared::read(&encoded[..], &other).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert_eq!(
+            G2Prepared::read(&encoded[..], &G2Affine::zero())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData
+        );
+
+        // So are corrupted line functions.
+        let mut corrupted = encoded.clone();
+        corrupted[len - 1] ^= 1;
+        assert_eq!(
+            G2Prepared::read(&corrupted[..], &q).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        // A truncated encoding is an unexpected end of file.
+        assert_eq!(
+            G2Prepared::read_unchecked(&encoded[..len - 1])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::UnexpectedEof
+        );
+    }
+
+    let mut encoded = vec![];
+    G2Affine::zero().prepare().write(&mut encoded).unwrap();
+    assert_eq!(encoded, vec![0]);
+    assert!(G2Prepared::read(&encoded[..], &G2Affine::zero())
+        .unwrap()
+        .is_zero());
+    assert_eq!(
+        G2Prepared::read(&encoded[..], &G2Affine::one())
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+    assert!(G2Prepared::read_unchecked(&[3][..]).is_err());
+
+    // Non-canonical coefficients are rejected.
+    let mut encoded = vec![];
+    G2Affine::one().prepare().write(&mut encoded).unwrap();
+    for b in &mut encoded[1..49] {
+        *b = 0xff;
+    }
+    assert_eq!(
+        G2Prepared::read_unchecked(&encoded[..]).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_g2_prepared_batch_from_affine() {
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    fn encode(prepared: &G2Prepared) -> Vec<u8> {
+        let mut encoded = vec![];
+        prepared.write(&mut encoded).unwrap();
+        encoded
+    }
+
+    assert!(G2Prepared::batch_from_affine(&[]).is_empty());
+
+    let qs: Vec<G2Affine> = (0..10)
+        .map(|i| {
+            if i == 4 {
+                G2Affine::zero()
+            } else {
+                G2Affine::from(G2::rand(&mut rng))
+            }
+        })
+        .collect();
+    let batch = G2Prepared::batch_from_affine(&qs);
+    assert_eq!(batch.len(), qs.len());
+
+    for (q, prepared) in qs.iter().zip(&batch) {
+        assert!(prepared.is_compact());
+        assert_eq!(prepared.is_zero(), q.is_zero());
+        assert_eq!(
+            encode(prepared),
+            encode(&G2Prepared::from_affine_compact(*q))
+        );
+        assert!(G2Prepared::read(&encode(prepared)[..], q).is_ok());
+
+        let p = G1Affine::from(G1::rand(&mut rng));
+        assert_eq!(
+            Bls12::final_exponentiation(&Bls12::miller_loop(&[(&p.prepare(), prepared)])).unwrap(),
+            Bls12::pairing(p, *q)
+        );
+    }
+}
+
+#[test]
+fn test_pairing_against_reference() {
+    use super::reference::{self, bigint, Point};
+    use super::BLS_X;
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    // With T = t - 1 = x, the ate and Tate pairings satisfy
+    // t(Q, P)^L = a(Q, P)^c for L = (T^12 - 1) / r and
+    // c = sum T^(11 - i) q^i (Hess, Smart and Vercauteren).
+    let mut l = vec![1];
+    for _ in 0..12 {
+        l = bigint::mul(&l, &[BLS_X]);
+    }
+    bigint::sub_one(&mut l);
+    let (l, rem) = bigint::div_rem(&l, Fr::char().as_ref());
+    assert!(bigint::is_zero(&rem));
+
+    let mut t = Fr::from_repr(FrRepr::from(BLS_X)).unwrap();
+    t.negate();
+    let (_, q) = bigint::div_rem(Fq::char().as_ref(), Fr::char().as_ref());
+    let q = Fr::from_repr(FrRepr([q[0], q[1], q[2], q[3]])).unwrap();
+    let mut c = Fr::zero();
+    for i in 0..12 {
+        let mut term = t.pow([11 - i]);
+        term.mul_assign(&q.pow([i]));
+        c.add_assign(&term);
+    }
+
+    for _ in 0..2 {
+        let p = G1Affine::from(G1::rand(&mut rng));
+        let q = G2Affine::from(G2::rand(&mut rng));
+
+        let ate = reference::ate(&p, &q);
+
+        // The final exponentiation of the optimized pairing raises to
+        // 3 (q^12 - 1) / r, so it is the cube of the ate pairing.
+        let mut cube = ate;
+        cube.square();
+        cube.mul_assign(&ate);
+        assert_eq!(Bls12::pairing(p, q), cube);
+
+        let reverse_tate = reference::final_exponentiation(&reference::miller_loop(
+            Fr::char(),
+            &Point::from_g2(&q),
+            &Point::from_g1(&p),
+        ));
+        assert_eq!(reverse_tate.pow(&l), ate.pow(c.into_repr()));
+
+        // The Tate pairing with the arguments exchanged differs by the Weil
+        // pairing raised to (q^12 - 1) / r.
+        let mut expected = reverse_tate;
+        expected.mul_assign(&reference::final_exponentiation(&reference::weil(&p, &q)));
+        assert_eq!(reference::tate(&p, &q), expected);
+    }
+}
@@ -0,0 +1,435 @@
+//! Radix-2 fast Fourier transforms over the multiplicative subgroups of a
+//! prime field whose order is a power of two.
+//!
+//! A field `F` has such subgroups of every order up to `2^F::S`, generated by
+//! powers of `F::root_of_unity()`. The FFT over a subgroup of order `n`
+//! evaluates a polynomial of degree less than `n`, given by its
+//! coefficients, at every element of the subgroup, and the inverse FFT
+//! interpolates a polynomial from its evaluations. The same transforms apply
+//! to vectors of group elements on which `F` acts, such as commitments to
+//! the powers of a secret in `G1`.
+
+use std::cmp;
+use std::thread;
+
+use super::{Field, PrimeField, WnafGroup};
+
+/// The arithmetic the transforms need on the elements of a vector, which
+/// are either elements of the field itself or of a group of which it is the
+/// scalar field. The two cases are told apart by marker types, as a field
+/// could in principle also be a `WnafGroup`.
+trait Arith<T> {
+    type Scalar: PrimeField;
+
+    fn add(a: &mut T, b: &T);
+
+    fn sub(a: &mut T, b: &T);
+
+    fn mul(a: &mut T, by: &Self::Scalar);
+}
+
+/// Arithmetic on vectors of field elements.
+enum FieldArith {}
+
+impl<F: PrimeField> Arith<F> for FieldArith {
+    type Scalar = F;
+
+    fn add(a: &mut F, b: &F) {
+        a.add_assign(b);
+    }
+
+    fn sub(a: &mut F, b: &F) {
+        a.sub_assign(b);
+    }
+
+    fn mul(a: &mut F, by: &F) {
+        a.mul_assign(by);
+    }
+}
+
+/// Arithmetic on vectors of group elements.
+enum GroupArith {}
+
+impl<G: WnafGroup> Arith<G> for GroupArith {
+    type Scalar = G::Scalar;
+
+    fn add(a: &mut G, b: &G) {
+        a.group_add(b);
+    }
+
+    fn sub(a: &mut G, b: &G) {
+        a.group_sub(b);
+    }
+
+    fn mul(a: &mut G, by: &G::Scalar) {
+        a.group_mul(by);
+    }
+}
+
+/// The multiplicative subgroup of `F` of some order `n`, a power of two,
+/// over which FFTs of vectors of length `n` are performed.
+///
+/// Cosets of the subgroup are taken by multiplying it by
+/// `F::multiplicative_generator()`, which lies outside of every such
+/// subgroup.
+#[derive(Clone, Debug)]
+pub struct EvaluationDomain<F: PrimeField> {
+    size: usize,
+    log_size: u32,
+    // A generator of the subgroup, a primitive `size`-th root of unity.
+    omega: F,
+    omega_inv: F,
+    size_inv: F,
+    coset_gen: F,
+    coset_gen_inv: F,
+    log_threads: u32,
+}
+
+impl<F: PrimeField> EvaluationDomain<F> {
+    /// Returns the smallest domain with at least `min_size` elements, or
+    /// `None` if it would have more than `2^F::S` elements.
+    pub fn new(min_size: usize) -> Option<Self> {
+        let size = min_size.checked_next_power_of_two()?;
+        let log_size = size.trailing_zeros();
+        if log_size > F::S {
+            return None;
+        }
+
+        // omega = root_of_unity^(2^(S - log_size))
+        let mut omega = F::root_of_unity();
+        for _ in log_size..F::S {
+            omega.square();
+        }
+
+        let size_inv = F::from_repr(F::Repr::from(size as u64)).ok()?.inverse()?;
+        let coset_gen = F::multiplicative_generator();
+
+        Some(EvaluationDomain {
+            size,
+            log_size,
+            omega,
+            omega_inv: omega.inverse()?,
+            size_inv,
+            coset_gen,
+            coset_gen_inv: coset_gen.inverse()?,
+            log_threads: 0,
+        })
+    }
+
+    /// Spreads the transforms of this domain across `threads` threads,
+    /// rounded down to a power of two. Each thread first transforms its own
+    /// share of the vector, and the butterflies of the remaining stages are
+    /// then split evenly between the threads. The results do not depend on
+    /// the number of threads.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.log_threads = usize::BITS - 1 - cmp::max(threads, 1).leading_zeros();
+        self
+    }
+
+    /// Returns the number of elements of the domain.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the base two logarithm of the number of elements of the domain.
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    /// Returns the generator of the domain, whose powers are its elements.
+    pub fn generator(&self) -> F {
+        self.omega
+    }
+
+    /// Returns the elements of the domain, the powers of its generator in
+    /// increasing order.
+    pub fn elements(&self) -> Vec<F> {
+        let mut res = Vec::with_capacity(self.size);
+        let mut e = F::one();
+        for _ in 0..self.size {
+            res.push(e);
+            e.mul_assign(&self.omega);
+        }
+
+        res
+    }
+
+    /// Evaluates the polynomial with coefficients `a`, lowest degree first,
+    /// at every element of the domain, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn fft(&self, a: &mut [F]) {
+        self.transform::<FieldArith, _>(a);
+    }
+
+    /// Interpolates the polynomial of degree less than the size of the
+    /// domain taking the values `a` at the elements of the domain, replacing
+    /// them with its coefficients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn ifft(&self, a: &mut [F]) {
+        self.inverse_transform::<FieldArith, _>(a);
+    }
+
+    /// Evaluates the polynomial with coefficients `a` at every element of
+    /// the coset of the domain, in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn coset_fft(&self, a: &mut [F]) {
+        assert_eq!(a.len(), self.size, "vector length must match the domain");
+        distribute_powers::<FieldArith, _>(a, &self.coset_gen, self.log_threads);
+        self.fft(a);
+    }
+
+    /// Interpolates the polynomial taking the values `a` at the elements of
+    /// the coset of the domain, replacing them with its coefficients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn coset_ifft(&self, a: &mut [F]) {
+        self.ifft(a);
+        distribute_powers::<FieldArith, _>(a, &self.coset_gen_inv, self.log_threads);
+    }
+
+    /// Performs the FFT on a vector of group elements, each coefficient
+    /// being a group element rather than a field element. The result is the
+    /// same as applying the coefficients to a common base after `fft`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn group_fft<G: WnafGroup<Scalar = F>>(&self, a: &mut [G]) {
+        self.transform::<GroupArith, _>(a);
+    }
+
+    /// Performs the inverse FFT on a vector of group elements, undoing
+    /// `group_fft`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `a` is not the size of the domain.
+    pub fn group_ifft<G: WnafGroup<Scalar = F>>(&self, a: &mut [G]) {
+        self.inverse_transform::<GroupArith, _>(a);
+    }
+
+    fn transform<A: Arith<T, Scalar = F>, T: Copy + Send>(&self, a: &mut [T]) {
+        assert_eq!(a.len(), self.size, "vector length must match the domain");
+        fft::<A, _>(a, &self.omega, self.log_size, self.log_threads);
+    }
+
+    fn inverse_transform<A: Arith<T, Scalar = F>, T: Copy + Send>(&self, a: &mut [T]) {
+        assert_eq!(a.len(), self.size, "vector length must match the domain");
+        fft::<A, _>(a, &self.omega_inv, self.log_size, self.log_threads);
+        scale::<A, _>(a, &self.size_inv, self.log_threads);
+    }
+
+    /// Evaluates the vanishing polynomial of the domain, `X^n - 1`, which is
+    /// zero at exactly the elements of the domain, at `tau`.
+    pub fn evaluate_vanishing_polynomial(&self, tau: &F) -> F {
+        let mut res = tau.pow([self.size as u64]);
+        res.sub_assign(&F::one());
+        res
+    }
+
+    /// Evaluates every Lagrange basis polynomial of the domain at `tau`. The
+    /// `i`-th of them is the polynomial of degree less than the size of the
+    /// domain which is one at the `i`-th element of the domain and zero at
+    /// every other element.
+    pub fn evaluate_all_lagrange_coefficients(&self, tau: &F) -> Vec<F> {
+        let z = self.evaluate_vanishing_polynomial(tau);
+        let elements = self.elements();
+
+        if z.is_zero() {
+            // tau is an element of the domain.
+            return elements
+                .iter()
+                .map(|e| if e == tau { F::one() } else { F::zero() })
+                .collect();
+        }
+
+        // L_i(tau) = z(tau) * omega^i / (n * (tau - omega^i))
+        let mut res: Vec<F> = elements
+            .iter()
+            .map(|e| {
+                let mut d = *tau;
+                d.sub_assign(e);
+                d
+            })
+            .collect();
+        F::batch_invert(&mut res);
+
+        let mut l = z;
+        l.mul_assign(&self.size_inv);
+        for (r, e) in res.iter_mut().zip(elements.iter()) {
+            r.mul_assign(e);
+            r.mul_assign(&l);
+        }
+
+        res
+    }
+
+    /// Converts the powers of a secret `tau` applied to some group element,
+    /// `tau^i * G` for every `i` below the size of the domain, into the
+    /// Lagrange basis polynomials of the domain evaluated at `tau` and
+    /// applied to the same element. This is an inverse FFT, as the matrix
+    /// of the transform is symmetric.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of powers is not the size of the domain.
+    pub fn lagrange_basis<G: WnafGroup<Scalar = F>>(&self, powers: &[G]) -> Vec<G> {
+        let mut res = powers.to_vec();
+        self.group_ifft(&mut res);
+        res
+    }
+}
+
+/// Performs the FFT of `a`, of length `2^log_n`, in place, given a primitive
+/// `2^log_n`-th root of unity.
+fn fft<A: Arith<T>, T: Copy + Send>(a: &mut [T], omega: &A::Scalar, log_n: u32, log_threads: u32) {
+    if log_n == 0 {
+        return;
+    }
+
+    let shift = usize::BITS - log_n;
+    for k in 0..a.len() {
+        let rk = k.reverse_bits() >> shift;
+        if k < rk {
+            a.swap(k, rk);
+        }
+    }
+
+    // Every thread needs at least one butterfly in each stage.
+    let log_threads = cmp::min(log_threads, log_n - 1);
+    if log_threads == 0 {
+        serial_fft::<A, _>(a, omega, log_n);
+    } else {
+        parallel_fft::<A, _>(a, omega, log_n, log_threads);
+    }
+}
+
+/// Performs every stage of the FFT on `a`, of length `2^log_n`, which has
+/// already been permuted into bit-reversed order.
+fn serial_fft<A: Arith<T>, T: Copy + Send>(a: &mut [T], omega: &A::Scalar, log_n: u32) {
+    let n = a.len();
+    let mut m = 1;
+    for _ in 0..log_n {
+        let w_m = omega.pow([(n / (2 * m)) as u64]);
+        for block in a.chunks_mut(2 * m) {
+            let (lo, hi) = block.split_at_mut(m);
+            butterflies::<A, _>(lo, hi, A::Scalar::one(), &w_m);
+        }
+
+        m *= 2;
+    }
+}
+
+/// Performs the stages of the FFT as `serial_fft` does, across
+/// `2^log_threads` threads.
+fn parallel_fft<A: Arith<T>, T: Copy + Send>(
+    a: &mut [T],
+    omega: &A::Scalar,
+    log_n: u32,
+    log_threads: u32,
+) {
+    let n = a.len();
+    let log_region = log_n - log_threads;
+    let region_len = 1 << log_region;
+
+    // The first stages only combine elements within each thread's region,
+    // which is an FFT of its own.
+    let omega_region = omega.pow([1u64 << log_threads]);
+    thread::scope(|scope| {
+        for region in a.chunks_mut(region_len) {
+            scope.spawn(move || serial_fft::<A, _>(region, &omega_region, log_region));
+        }
+    });
+
+    // The butterflies of each remaining stage are split into one run of
+    // consecutive butterflies per thread.
+    let run_len = region_len / 2;
+    let mut m = region_len;
+    while m < n {
+        let w_m = omega.pow([(n / (2 * m)) as u64]);
+        thread::scope(|scope| {
+            for block in a.chunks_mut(2 * m) {
+                let (lo, hi) = block.split_at_mut(m);
+                let runs = lo.chunks_mut(run_len).zip(hi.chunks_mut(run_len));
+                for (i, (lo, hi)) in runs.enumerate() {
+                    let w = w_m.pow([(i * run_len) as u64]);
+                    scope.spawn(move || butterflies::<A, _>(lo, hi, w, &w_m));
+                }
+            }
+        });
+
+        m *= 2;
+    }
+}
+
+/// Performs the butterflies between the two halves of a block of a stage
+/// of the FFT, with the twiddle factors being successive powers of `w_m`
+/// starting from `w`.
+fn butterflies<A: Arith<T>, T: Copy>(
+    lo: &mut [T],
+    hi: &mut [T],
+    mut w: A::Scalar,
+    w_m: &A::Scalar,
+) {
+    for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+        let mut t = *b;
+        A::mul(&mut t, &w);
+        *b = *a;
+        A::sub(b, &t);
+        A::add(a, &t);
+        w.mul_assign(w_m);
+    }
+}
+
+/// Runs `f` on consecutive chunks of `a`, one per thread, along with the
+/// index of the first element of each chunk.
+fn for_each_chunk<T, C>(a: &mut [T], log_threads: u32, f: C)
+where
+    T: Send,
+    C: Fn(usize, &mut [T]) + Sync,
+{
+    let chunk_len = cmp::max(a.len() >> log_threads, 1);
+    if log_threads == 0 || chunk_len == a.len() {
+        f(0, a);
+        return;
+    }
+
+    let f = &f;
+    thread::scope(|scope| {
+        for (i, chunk) in a.chunks_mut(chunk_len).enumerate() {
+            scope.spawn(move || f(i * chunk_len, chunk));
+        }
+    });
+}
+
+/// Multiplies every element of `a` by `by`.
+fn scale<A: Arith<T>, T: Send>(a: &mut [T], by: &A::Scalar, log_threads: u32) {
+    for_each_chunk(a, log_threads, |_, chunk| {
+        for e in chunk {
+            A::mul(e, by);
+        }
+    });
+}
+
+/// Multiplies the `i`-th element of `a` by `g^i`, which turns the
+/// coefficients of a polynomial `p(X)` into those of `p(gX)`.
+fn distribute_powers<A: Arith<T>, T: Send>(a: &mut [T], g: &A::Scalar, log_threads: u32) {
+    for_each_chunk(a, log_threads, |start, chunk| {
+        let mut u = g.pow([start as u64]);
+        for e in chunk {
+            A::mul(e, &u);
+            u.mul_assign(g);
+        }
+    });
+}
@@ -15,19 +15,30 @@
 
 extern crate byteorder;
 extern crate rand;
+#[cfg(feature = "bigint")]
+extern crate num_bigint;
+#[cfg(feature = "constant-time")]
+extern crate subtle;
 
 #[cfg(test)]
 pub mod tests;
 
 pub mod bls12_381;
+pub mod fft;
+pub mod poly;
 
 mod wnaf;
-pub use self::wnaf::Wnaf;
+pub use self::wnaf::{multiexp, FixedBaseTable, Wnaf, WnafGroup};
 
 use std::error::Error;
 use std::fmt;
 use std::io::{self, Read, Write};
 
+#[cfg(feature = "bigint")]
+use num_bigint::{BigInt, BigUint, Sign};
+#[cfg(feature = "constant-time")]
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
 /// An "engine" is a collection of types (fields, elliptic curve groups, etc.)
 /// with well-defined relationships. In particular, the G1/G2 curve groups are
 /// of prime order `r`, and are equipped with a bilinear pairing function.
@@ -84,7 +95,15 @@ pub trait Engine: Sized + 'static + Clone {
     /// The extension field that hosts the target group of the pairing.
     type Fqk: Field;
 
+    /// The target group of the pairing, the subgroup of `Fqk` of order `r`.
+    type Gt: TargetGroup<Engine = Self, Scalar = Self::Fr, Fqk = Self::Fqk>
+        + WnafGroup<Scalar = Self::Fr>;
+
     /// Perform a miller loop with some number of (G1, G2) pairs.
+    ///
+    /// The result is not an element of the target group until it goes
+    /// through `final_exponentiation`. `MultiMillerLoop::multi_miller_loop`
+    /// enforces this distinction and should be preferred.
     fn miller_loop<'a, I>(i: I) -> Self::Fqk
     where
         I: IntoIterator<
@@ -107,6 +126,98 @@ pub trait Engine: Sized + 'static + Clone {
             [(&(p.into().prepare()), &(q.into().prepare()))].into_iter(),
         )).unwrap()
     }
+
+    /// Determines if the product of the pairings of some number of (G1, G2)
+    /// pairs is the identity, using a single miller loop and final
+    /// exponentiation. Pairs involving the point at infinity are skipped.
+    fn pairing_product_is_one(pairs: &[(Self::G1Affine, Self::G2Affine)]) -> bool {
+        let prepared: Vec<_> = pairs
+            .iter()
+            .filter(|&&(p, q)| !p.is_zero() && !q.is_zero())
+            .map(|&(p, q)| (p.prepare(), q.prepare()))
+            .collect();
+        let prepared: Vec<_> = prepared.iter().map(|(p, q)| (p, q)).collect();
+
+        match Self::final_exponentiation(&Self::miller_loop(&prepared)) {
+            Some(f) => f == Self::Fqk::one(),
+            None => false,
+        }
+    }
+
+    /// Determines if `e(lhs.0, lhs.1) = e(rhs.0, rhs.1)`, by checking that
+    /// `e(lhs.0, lhs.1) * e(-rhs.0, rhs.1)` is the identity.
+    fn pairings_equal(
+        lhs: (Self::G1Affine, Self::G2Affine),
+        rhs: (Self::G1Affine, Self::G2Affine),
+    ) -> bool {
+        let mut neg = rhs.0;
+        neg.negate();
+
+        Self::pairing_product_is_one(&[lhs, (neg, rhs.1)])
+    }
+
+    /// Performs a complete pairing operation `(p, q)`, yielding an element
+    /// of the target group.
+    fn pairing_gt<G1, G2>(p: G1, q: G2) -> Self::Gt
+    where
+        G1: Into<Self::G1Affine>,
+        G2: Into<Self::G2Affine>,
+    {
+        Self::Gt::from_fqk_unchecked(Self::pairing(p, q))
+    }
+}
+
+/// An engine which performs miller loops over any number of prepared
+/// (G1, G2) pairs, yielding a `MillerLoopResult` rather than an element of
+/// `Fqk`, so that unreduced and reduced values cannot be confused.
+///
+/// This supersedes `Engine::miller_loop` and `Engine::final_exponentiation`:
+/// `E::final_exponentiation(&E::miller_loop(&[(&p, &q)])).unwrap()` becomes
+/// `E::multi_miller_loop(vec![(&p, &q)]).final_exponentiation()`.
+pub trait MultiMillerLoop: Engine {
+    /// The result of a miller loop.
+    type Result: MillerLoopResult<Gt = Self::Gt, Fqk = Self::Fqk>;
+
+    /// Performs a miller loop over some number of (G1, G2) pairs.
+    fn multi_miller_loop<'a, I>(terms: I) -> Self::Result
+    where
+        I: IntoIterator<
+            Item = (
+                &'a <Self::G1Affine as CurveAffine>::Prepared,
+                &'a <Self::G2Affine as CurveAffine>::Prepared,
+            ),
+        >;
+}
+
+/// The result of a miller loop, which is not an element of the target group
+/// until it is reduced by final exponentiation.
+pub trait MillerLoopResult:
+    PartialEq + Eq + Sized + Copy + Clone + Send + Sync + fmt::Debug + 'static
+{
+    /// The target group of the pairing.
+    type Gt: TargetGroup<Fqk = Self::Fqk>;
+
+    /// The extension field hosting the result.
+    type Fqk: Field;
+
+    /// Returns the result of a miller loop over no pairs.
+    fn one() -> Self;
+
+    /// Multiplies another result by this result, yielding the result of a
+    /// miller loop over the pairs of both.
+    fn mul_assign(&mut self, other: &Self);
+
+    /// Performs final exponentiation, yielding an element of the target
+    /// group.
+    fn final_exponentiation(&self) -> Self::Gt;
+
+    /// Returns the underlying element of `Fqk`.
+    fn as_fqk(&self) -> &Self::Fqk;
+
+    /// Interprets an element of `Fqk`, such as the result of
+    /// `Engine::miller_loop`, as the result of a miller loop. Returns `None`
+    /// for zero, which cannot be the result of a miller loop.
+    fn from_fqk(f: Self::Fqk) -> Option<Self>;
 }
 
 /// Projective representation of an elliptic curve point guaranteed to be
@@ -263,6 +374,105 @@ pub trait EncodedPoint:
     fn from_affine(affine: Self::Affine) -> Self;
 }
 
+/// An element of the target group of a pairing, the multiplicative subgroup
+/// of order `r` in `Fqk`. The group operation is written multiplicatively.
+pub trait TargetGroup:
+    PartialEq
+    + Eq
+    + Sized
+    + Copy
+    + Clone
+    + Send
+    + Sync
+    + fmt::Debug
+    + fmt::Display
+    + rand::Rand
+    + 'static
+{
+    type Engine: Engine<Fr = Self::Scalar>;
+    type Scalar: PrimeField + SqrtField;
+    type Fqk: Field;
+    type Uncompressed: EncodedTarget<Target = Self>;
+    type Compressed: EncodedTarget<Target = Self>;
+
+    /// Returns the multiplicative identity.
+    fn one() -> Self;
+
+    /// Returns the fixed generator `e(G1::one(), G2::one())`.
+    fn generator() -> Self;
+
+    /// Determines if this element is the identity.
+    fn is_one(&self) -> bool;
+
+    /// Squares this element.
+    fn square(&mut self);
+
+    /// Multiplies another element by this element.
+    fn mul_assign(&mut self, other: &Self);
+
+    /// Inverts this element.
+    fn invert(&mut self);
+
+    /// Exponentiates this element by a scalar.
+    fn pow<S: Into<<Self::Scalar as PrimeField>::Repr>>(&self, exp: S) -> Self;
+
+    /// Returns the underlying element of `Fqk`.
+    fn as_fqk(&self) -> &Self::Fqk;
+
+    /// Interprets an element of `Fqk` as an element of the target group, if
+    /// it lies in the subgroup of order `r`.
+    fn from_fqk(f: Self::Fqk) -> Option<Self>;
+
+    /// Interprets an element of `Fqk` as an element of the target group,
+    /// without checking that it lies in the subgroup of order `r`. This is
+    /// useful when the caller knows the element is valid already, such as
+    /// the result of a final exponentiation.
+    ///
+    /// If the element is invalid, this can break API invariants,
+    /// so caution is strongly encouraged.
+    fn from_fqk_unchecked(f: Self::Fqk) -> Self;
+
+    /// Converts this element into its uncompressed encoding.
+    fn to_uncompressed(&self) -> Self::Uncompressed {
+        <Self::Uncompressed as EncodedTarget>::from_target(*self)
+    }
+
+    /// Converts this element into its compressed encoding.
+    fn to_compressed(&self) -> Self::Compressed {
+        <Self::Compressed as EncodedTarget>::from_target(*self)
+    }
+}
+
+/// An encoded element of the target group, which should essentially wrap a
+/// `[u8; N]`.
+pub trait EncodedTarget:
+    Sized + Send + Sync + AsRef<[u8]> + AsMut<[u8]> + Clone + Copy + 'static
+{
+    type Target: TargetGroup;
+
+    /// Creates an empty representation.
+    fn empty() -> Self;
+
+    /// Returns the number of bytes consumed by this representation.
+    fn size() -> usize;
+
+    /// Converts an `EncodedTarget` into a `TargetGroup` element,
+    /// if the encoding represents a valid element.
+    fn to_target(&self) -> Result<Self::Target, GroupDecodingError>;
+
+    /// Converts an `EncodedTarget` into a `TargetGroup` element,
+    /// without guaranteeing that the encoding represents an element
+    /// of the target group. This is useful when the caller knows the
+    /// encoding is valid already.
+    ///
+    /// If the encoding is invalid, this can break API invariants,
+    /// so caution is strongly encouraged.
+    fn to_target_unchecked(&self) -> Result<Self::Target, GroupDecodingError>;
+
+    /// Creates an `EncodedTarget` from an element of the target group.
+    fn from_target(target: Self::Target) -> Self;
+}
+
 /// This trait represents an element of a field.
 pub trait Field:
     Sized + Eq + Copy + Clone + Send + Sync + fmt::Debug + fmt::Display + 'static + rand::Rand
@@ -297,6 +507,77 @@ pub trait Field:
     /// Computes the multiplicative inverse of this element, if nonzero.
     fn inverse(&self) -> Option<Self>;
 
+    /// Inverts every element of a slice in place with a single inversion,
+    /// using Montgomery's trick.
+    ///
+    /// Panics if any element is zero, leaving the slice unchanged; use
+    /// `batch_inverse` if the slice may contain zeros.
+    fn batch_invert(v: &mut [Self]) {
+        Self::batch_invert_with_scratch(v, &mut Vec::with_capacity(v.len()));
+    }
+
+    /// Inverts every element of a slice in place as `batch_invert` does,
+    /// using `scratch` to hold the intermediate products so that it can be
+    /// reused between calls.
+    ///
+    /// Extension fields override this to apply Montgomery's trick to norms
+    /// in a subfield instead, holding up to two entries per element in
+    /// `scratch`. Prime fields have no subfield to move the work into, so
+    /// this takes three multiplications per element and one inversion there.
+    fn batch_invert_with_scratch(v: &mut [Self], scratch: &mut Vec<Self>) {
+        // Montgomery’s Trick and Fast Implementation of Masked AES
+        // Genelle, Prouff and Quisquater
+        // Section 3.2
+
+        // First pass: compute [1, a, ab, abc, ...]
+        scratch.clear();
+        let mut acc = Self::one();
+        for e in v.iter() {
+            scratch.push(acc);
+            acc.mul_assign(e);
+        }
+
+        let mut acc = acc.inverse().expect("cannot invert zero");
+
+        // Second pass: iterate backwards to compute inverses
+        for (e, prod) in v.iter_mut().zip(scratch.iter()).rev() {
+            let mut inv = acc;
+            inv.mul_assign(prod);
+            acc.mul_assign(e);
+            *e = inv;
+        }
+    }
+
+    /// Inverts every element of a slice in place, `chunk_size` elements at
+    /// a time, reusing `scratch` for every chunk. This bounds the memory
+    /// needed for very large slices at the cost of one inversion per chunk.
+    ///
+    /// Panics if any element is zero or `chunk_size` is zero. Chunks before
+    /// the one containing a zero will have been inverted.
+    fn batch_invert_chunked(v: &mut [Self], chunk_size: usize, scratch: &mut Vec<Self>) {
+        for chunk in v.chunks_mut(chunk_size) {
+            Self::batch_invert_with_scratch(chunk, scratch);
+        }
+    }
+
+    /// Returns the inverse of every element of a slice, or `None` for
+    /// zeros, with a single inversion.
+    fn batch_inverse(v: &[Self]) -> Vec<Option<Self>> {
+        let mut nonzero: Vec<Self> = v.iter().filter(|e| !e.is_zero()).cloned().collect();
+        Self::batch_invert(&mut nonzero);
+
+        let mut nonzero = nonzero.into_iter();
+        v.iter()
+            .map(|e| {
+                if e.is_zero() {
+                    None
+                } else {
+                    nonzero.next()
+                }
+            })
+            .collect()
+    }
+
     /// Exponentiates this element by a power of the base prime modulus via
     /// the Frobenius automorphism.
     fn frobenius_map(&mut self, power: usize);
@@ -334,6 +615,103 @@ pub trait SqrtField: Field {
     fn sqrt(&self) -> Option<Self>;
 }
 
+/// A field whose elements can be compared, selected and inverted in time
+/// independent of their values, for code operating on secrets.
+///
+/// Implementations are expected to perform the rest of their `Field`
+/// arithmetic in fixed time as well, so that only `is_zero()`, `inverse()`
+/// and equality need replacing.
+#[cfg(feature = "constant-time")]
+pub trait CtField: Field + ConditionallySelectable + ConstantTimeEq {
+    /// Returns whether the element is zero.
+    fn ct_is_zero(&self) -> Choice {
+        self.ct_eq(&Self::zero())
+    }
+
+    /// Computes the multiplicative inverse of the element, which is none if
+    /// and only if the element is zero.
+    fn ct_inverse(&self) -> CtOption<Self>;
+}
+
+/// A field with a square root operation running in time independent of
+/// the value of its input.
+#[cfg(feature = "constant-time")]
+pub trait CtSqrtField: CtField + SqrtField {
+    /// Returns a square root of the element, which is none if and only if
+    /// the element is a quadratic nonresidue. This is the same square root
+    /// as returned by `sqrt()`, up to sign.
+    fn ct_sqrt(&self) -> CtOption<Self>;
+}
+
+/// A canonical encoding of the elements of a field as bytes.
+///
+/// Elements of prime fields are encoded as integers of the size of their
+/// `PrimeFieldRepr`. Elements of extension fields are encoded as the
+/// concatenation of their coefficients, highest degree first in big endian
+/// encodings, consistent with the encodings of curve points and `Gt`; so
+/// `c1 || c0` for `c0 + c1 * u`. Little endian encodings are exactly the
+/// reverse of big endian encodings.
+pub trait FieldEncoding: Field {
+    /// Returns the length of the encoding in bytes.
+    fn encoded_len() -> usize;
+
+    /// Encodes this element as big endian bytes.
+    fn to_bytes_be(&self) -> Vec<u8>;
+
+    /// Decodes an element from big endian bytes, rejecting encodings of the
+    /// wrong length and non-canonical encodings of any coefficient.
+    fn from_bytes_be(bytes: &[u8]) -> Result<Self, PrimeFieldDecodingError>;
+
+    /// Encodes this element as little endian bytes.
+    fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_be();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Decodes an element from little endian bytes, rejecting encodings of
+    /// the wrong length and non-canonical encodings of any coefficient.
+    fn from_bytes_le(bytes: &[u8]) -> Result<Self, PrimeFieldDecodingError> {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        Self::from_bytes_be(&bytes)
+    }
+}
+
+/// Checks that an encoding has the length of the encodings of `F`.
+pub(crate) fn check_encoded_len<F: FieldEncoding>(
+    bytes: &[u8],
+) -> Result<(), PrimeFieldDecodingError> {
+    if bytes.len() == F::encoded_len() {
+        Ok(())
+    } else {
+        Err(PrimeFieldDecodingError::InvalidLength(
+            F::encoded_len(),
+            bytes.len(),
+        ))
+    }
+}
+
+impl<F: PrimeField> FieldEncoding for F {
+    fn encoded_len() -> usize {
+        F::Repr::default().as_ref().len() * 8
+    }
+
+    fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::encoded_len());
+        self.into_repr().write_be(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn from_bytes_be(bytes: &[u8]) -> Result<Self, PrimeFieldDecodingError> {
+        check_encoded_len::<Self>(bytes)?;
+
+        let mut repr = F::Repr::default();
+        repr.read_be(bytes).unwrap();
+        F::from_repr(repr)
+    }
+}
+
 /// This trait represents a wrapper around a biginteger which can encode any element of a particular
 /// prime field. It is a smart wrapper around a sequence of `u64` limbs, least-significant digit
 /// first.
@@ -360,6 +738,30 @@ pub trait PrimeFieldRepr:
     /// Add another representation to this one.
     fn add_nocarry(&mut self, other: &Self);
 
+    /// Adds another representation to this one, returning true iff the sum
+    /// overflowed and was wrapped around.
+    #[inline(always)]
+    fn add_with_carry(&mut self, other: &Self) -> bool {
+        let mut carry = 0;
+        for (a, b) in self.as_mut().iter_mut().zip(other.as_ref()) {
+            *a = adc(*a, *b, &mut carry);
+        }
+
+        carry != 0
+    }
+
+    /// Subtracts another representation from this one, returning true iff
+    /// the difference underflowed and was wrapped around.
+    #[inline(always)]
+    fn sub_with_borrow(&mut self, other: &Self) -> bool {
+        let mut borrow = 0;
+        for (a, b) in self.as_mut().iter_mut().zip(other.as_ref()) {
+            *a = sbb(*a, *b, &mut borrow);
+        }
+
+        borrow != 0
+    }
+
     /// Compute the number of bits needed to encode this number. Always a
     /// multiple of 64.
     fn num_bits(&self) -> u32;
@@ -387,6 +789,50 @@ pub trait PrimeFieldRepr:
     /// Performs a leftwise bitshift of this number by some amount.
     fn shl(&mut self, amt: u32);
 
+    /// Returns bit `i` of this number, counting from the least significant
+    /// bit. Bits beyond the width of the representation are zero.
+    fn bit(&self, i: u32) -> bool {
+        let limbs = self.as_ref();
+        let i = i as usize;
+
+        i < 64 * limbs.len() && (limbs[i / 64] >> (i % 64)) & 1 == 1
+    }
+
+    /// Divides this number by a nonzero `u64` in place, returning the
+    /// remainder.
+    fn div_rem_u64(&mut self, divisor: u64) -> u64 {
+        let num = *self;
+        let mut rem = [0];
+        div_rem_limbs(num.as_ref(), &[divisor], self.as_mut(), &mut rem);
+
+        rem[0]
+    }
+
+    /// Returns the quotient and remainder of this number divided by
+    /// another, or `None` if the divisor is zero.
+    fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        if divisor.is_zero() {
+            return None;
+        }
+
+        let mut quo = Self::default();
+        let mut rem = Self::default();
+        div_rem_limbs(self.as_ref(), divisor.as_ref(), quo.as_mut(), rem.as_mut());
+
+        Some((quo, rem))
+    }
+
+    /// Returns the Jacobi symbol of this number over an odd `modulus` as 0,
+    /// 1 or -1, which for a prime modulus is the Legendre symbol. The running
+    /// time does not depend on the values.
+    fn jacobi(&self, modulus: &Self) -> i32 {
+        assert!(modulus.is_odd(), "the modulus must be odd");
+
+        let (mut a, mut b) = (*self, *modulus);
+        let (mut ta, mut tb) = (Self::default(), Self::default());
+        jacobi_limbs(a.as_mut(), b.as_mut(), ta.as_mut(), tb.as_mut())
+    }
+
     /// Writes this `PrimeFieldRepr` as a big endian integer.
     fn write_be<W: Write>(&self, mut writer: W) -> io::Result<()> {
         use byteorder::{BigEndian, WriteBytesExt};
@@ -430,6 +876,82 @@ pub trait PrimeFieldRepr:
 
         Ok(())
     }
+
+    /// Converts this representation into a `BigUint`.
+    #[cfg(feature = "bigint")]
+    fn to_biguint(&self) -> BigUint {
+        let mut bytes = Vec::with_capacity(self.as_ref().len() * 8);
+        self.write_le(&mut bytes).unwrap();
+
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    /// Converts a `BigUint` into a representation, if it fits in the limbs.
+    #[cfg(feature = "bigint")]
+    fn from_biguint(n: &BigUint) -> Option<Self> {
+        let digits = n.to_u64_digits();
+        let mut res = Self::default();
+        if digits.len() > res.as_ref().len() {
+            return None;
+        }
+        res.as_mut()[..digits.len()].copy_from_slice(&digits);
+
+        Some(res)
+    }
+}
+
+/// Double-width arithmetic on a `PrimeFieldRepr`, such as the full product
+/// of two representations and its reduction modulo the characteristic of a
+/// field.
+pub trait WidePrimeFieldRepr: PrimeFieldRepr {
+    /// A number twice as wide as this representation, such as the full
+    /// product of two representations, least-significant limb first.
+    type Wide: Sized
+        + Copy
+        + Clone
+        + Eq
+        + Send
+        + Sync
+        + Default
+        + fmt::Debug
+        + 'static
+        + AsRef<[u64]>
+        + AsMut<[u64]>;
+
+    /// Computes the full product of this number and another.
+    fn mul_wide(&self, other: &Self) -> Self::Wide {
+        let mut res = Self::Wide::default();
+        {
+            let (a, b, res) = (self.as_ref(), other.as_ref(), res.as_mut());
+            assert_eq!(res.len(), a.len() + b.len());
+
+            for (i, a) in a.iter().enumerate() {
+                let mut carry = 0;
+                for (j, b) in b.iter().enumerate() {
+                    res[i + j] = mac_with_carry(res[i + j], *a, *b, &mut carry);
+                }
+                res[i + b.len()] = carry;
+            }
+        }
+
+        res
+    }
+
+    /// Reduces a double-width number modulo a nonzero `modulus`.
+    fn rem_wide(wide: &Self::Wide, modulus: &Self) -> Self {
+        let mut quo = Self::Wide::default();
+        let mut rem = Self::default();
+        div_rem_limbs(wide.as_ref(), modulus.as_ref(), quo.as_mut(), rem.as_mut());
+
+        rem
+    }
+
+    /// Interprets a double-width representation, such as the full product
+    /// of two representations, as an element of the field `F` by reducing
+    /// it modulo the characteristic.
+    fn from_wide_repr<F: PrimeField<Repr = Self>>(wide: &Self::Wide) -> F {
+        F::from_repr(Self::rem_wide(wide, &F::char())).unwrap()
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -445,12 +967,15 @@ pub enum LegendreSymbol {
 pub enum PrimeFieldDecodingError {
     /// The encoded value is not in the field
     NotInField(String),
+    /// The encoding has the wrong length; the expected and actual lengths
+    InvalidLength(usize, usize),
 }
 
 impl Error for PrimeFieldDecodingError {
     fn description(&self) -> &str {
         match *self {
             PrimeFieldDecodingError::NotInField(..) => "not an element of the field",
+            PrimeFieldDecodingError::InvalidLength(..) => "encoding has the wrong length",
         }
     }
 }
@@ -461,6 +986,11 @@ impl fmt::Display for PrimeFieldDecodingError {
             PrimeFieldDecodingError::NotInField(ref repr) => {
                 write!(f, "{} is not an element of the field", repr)
             }
+            PrimeFieldDecodingError::InvalidLength(expected, actual) => write!(
+                f,
+                "expected an encoding of {} bytes, found {}",
+                expected, actual
+            ),
         }
     }
 }
@@ -478,6 +1008,8 @@ pub enum GroupDecodingError {
     UnexpectedCompressionMode,
     /// The encoding contained bits that should not have been set
     UnexpectedInformation,
+    /// The precomputed data does not belong to the expected element
+    InvalidPrecomputation,
 }
 
 impl Error for GroupDecodingError {
@@ -490,6 +1022,9 @@ impl Error for GroupDecodingError {
                 "encoding has unexpected compression mode"
             }
             GroupDecodingError::UnexpectedInformation => "encoding has unexpected information",
+            GroupDecodingError::InvalidPrecomputation => {
+                "precomputed data does not belong to the element"
+            }
         }
     }
 }
@@ -511,6 +1046,32 @@ pub trait PrimeField: Field {
     /// representation.
     type Repr: PrimeFieldRepr + From<Self>;
 
+    /// Interprets a big endian integer of any length, such as a 32, 48 or
+    /// 64 byte hash output, as an element of the field by reducing it modulo
+    /// the characteristic. Unlike `from_bytes_be`, this never fails.
+    fn from_bytes_reduce(bytes: &[u8]) -> Self {
+        // 2^64
+        let mut shift = Self::one();
+        for _ in 0..64 {
+            shift.double();
+        }
+
+        // Horner's rule over 64-bit digits, the most significant of which
+        // may be shorter.
+        let mut res = Self::zero();
+        let (head, tail) = bytes.split_at(bytes.len() % 8);
+        for digit in Some(head).into_iter().chain(tail.chunks(8)) {
+            let digit = digit.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+
+            res.mul_assign(&shift);
+            res.add_assign(
+                &Self::from_repr(Self::Repr::from(digit)).expect("fields are larger than 64 bits"),
+            );
+        }
+
+        res
+    }
+
     /// Interpret a string of numbers as a (congruent) prime field element.
     /// Does not accept unnecessary leading zeroes or a blank string.
     fn from_str(s: &str) -> Option<Self> {
@@ -551,6 +1112,77 @@ pub trait PrimeField: Field {
         Some(res)
     }
 
+    /// Interpret a string of digits in the given radix as a (congruent)
+    /// prime field element. Leading zeroes are accepted, a leading `-`
+    /// negates the result and in radix 16 the digits may be prefixed with
+    /// `0x`. Does not accept a blank string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range from 2 to 36.
+    fn from_str_radix(s: &str, radix: u32) -> Option<Self> {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in the range from 2 to 36"
+        );
+
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let s = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(digits) if radix == 16 => digits,
+            _ => s,
+        };
+
+        if s.is_empty() {
+            return None;
+        }
+
+        let mut res = Self::zero();
+
+        let base = Self::from_repr(Self::Repr::from(u64::from(radix))).unwrap();
+
+        for c in s.chars() {
+            let digit = c.to_digit(radix)?;
+
+            res.mul_assign(&base);
+            res.add_assign(&Self::from_repr(Self::Repr::from(u64::from(digit))).unwrap());
+        }
+
+        if negative {
+            res.negate();
+        }
+
+        Some(res)
+    }
+
+    /// Formats the canonical representative of this element in the given
+    /// radix, with lowercase digits and without a prefix or leading zeroes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range from 2 to 36.
+    fn to_str_radix(&self, radix: u32) -> String {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in the range from 2 to 36"
+        );
+
+        let mut repr = self.into_repr();
+        let mut digits = vec![];
+        loop {
+            let digit = repr.div_rem_u64(u64::from(radix));
+            digits.push(std::char::from_digit(digit as u32, radix).unwrap());
+
+            if repr.is_zero() {
+                break;
+            }
+        }
+
+        digits.iter().rev().collect()
+    }
+
     /// Convert this prime field element into a biginteger representation.
     fn from_repr(Self::Repr) -> Result<Self, PrimeFieldDecodingError>;
 
@@ -561,6 +1193,39 @@ pub trait PrimeField: Field {
     /// Returns the field characteristic; the modulus.
     fn char() -> Self::Repr;
 
+    /// Returns the field characteristic as a `BigUint`.
+    #[cfg(feature = "bigint")]
+    fn modulus_biguint() -> BigUint {
+        Self::char().to_biguint()
+    }
+
+    /// Converts the canonical representative of this element into a
+    /// `BigUint`.
+    #[cfg(feature = "bigint")]
+    fn to_biguint(&self) -> BigUint {
+        self.into_repr().to_biguint()
+    }
+
+    /// Reduces an integer of any size into the field.
+    #[cfg(feature = "bigint")]
+    fn from_biguint(n: &BigUint) -> Self {
+        let n = n % Self::modulus_biguint();
+
+        Self::from_repr(Self::Repr::from_biguint(&n).unwrap()).unwrap()
+    }
+
+    /// Reduces a signed integer of any size into the field, so that -1
+    /// becomes the modulus minus one.
+    #[cfg(feature = "bigint")]
+    fn from_bigint(n: &BigInt) -> Self {
+        let mut res = Self::from_biguint(n.magnitude());
+        if n.sign() == Sign::Minus {
+            res.negate();
+        }
+
+        res
+    }
+
     /// How many bits are needed to represent an element of this field.
     const NUM_BITS: u32;
 
@@ -579,6 +1244,135 @@ pub trait PrimeField: Field {
     fn root_of_unity() -> Self;
 }
 
+/// Divides the little-endian limbs `num` by the nonzero `den`, writing the
+/// quotient and remainder to `quo` and `rem`, which must be wide enough to
+/// hold them.
+///
+/// This is Algorithm D of Knuth, TAOCP volume 2, section 4.3.1, on 32-bit
+/// digits so that intermediate values fit in 64 bits.
+fn div_rem_limbs(num: &[u64], den: &[u64], quo: &mut [u64], rem: &mut [u64]) {
+    const BASE: u64 = 1 << 32;
+    const MASK: u64 = BASE - 1;
+
+    // A divisor of a single digit takes one division per digit of `num`,
+    // as the remainder of each division fits in a digit.
+    if den[0] < BASE && den[1..].iter().all(|&d| d == 0) {
+        let d = den[0];
+        assert!(d != 0, "division by zero");
+
+        let mut r = 0;
+        for (i, &n) in num.iter().enumerate().rev() {
+            let hi = (r << 32) | (n >> 32);
+            let lo = ((hi % d) << 32) | (n & MASK);
+            let q = ((hi / d) << 32) | (lo / d);
+            match quo.get_mut(i) {
+                Some(l) => *l = q,
+                None => debug_assert_eq!(q, 0),
+            }
+            r = lo % d;
+        }
+        for l in quo.iter_mut().skip(num.len()) {
+            *l = 0;
+        }
+        for (i, l) in rem.iter_mut().enumerate() {
+            *l = if i == 0 { r } else { 0 };
+        }
+        return;
+    }
+
+    fn to_digits(limbs: &[u64]) -> Vec<u64> {
+        let mut digits = Vec::with_capacity(2 * limbs.len());
+        for &l in limbs {
+            digits.push(l & MASK);
+            digits.push(l >> 32);
+        }
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        digits
+    }
+
+    fn from_digits(digits: &[u64], limbs: &mut [u64]) {
+        for l in limbs.iter_mut() {
+            *l = 0;
+        }
+        for (i, &d) in digits.iter().enumerate().filter(|&(_, &d)| d != 0) {
+            limbs[i / 2] |= d << (32 * (i % 2));
+        }
+    }
+
+    let u = to_digits(num);
+    let v = to_digits(den);
+    let n = v.len();
+    assert!(n > 0, "division by zero");
+
+    if u.len() < n {
+        from_digits(&[], quo);
+        from_digits(&u, rem);
+        return;
+    }
+
+    // Normalize so that the top digit of the divisor has its high bit set,
+    // which keeps each estimate of a quotient digit off by at most two.
+    let s = (v[n - 1] as u32).leading_zeros();
+    let normalize = |d: &[u64], i: usize| {
+        let lo = if i > 0 { d[i - 1] >> (32 - s) } else { 0 };
+        ((d[i] << s) & MASK) | lo
+    };
+    let vn: Vec<u64> = (0..n).map(|i| normalize(&v, i)).collect();
+    let mut un: Vec<u64> = (0..u.len()).map(|i| normalize(&u, i)).collect();
+    un.push(u[u.len() - 1] >> (32 - s));
+
+    let mut q = vec![0; u.len() - n + 1];
+    for j in (0..q.len()).rev() {
+        let top = (un[j + n] << 32) | un[j + n - 1];
+        let mut qhat = top / vn[n - 1];
+        let mut rhat = top % vn[n - 1];
+        while qhat >= BASE || (n > 1 && qhat * vn[n - 2] > ((rhat << 32) | un[j + n - 2])) {
+            qhat -= 1;
+            rhat += vn[n - 1];
+            if rhat >= BASE {
+                break;
+            }
+        }
+
+        // un[j..j + n + 1] -= qhat * vn
+        let mut carry = 0;
+        let mut borrow = 0;
+        for i in 0..n {
+            let p = qhat * vn[i] + carry;
+            carry = p >> 32;
+            let t = un[i + j].wrapping_sub(p & MASK).wrapping_sub(borrow);
+            un[i + j] = t & MASK;
+            borrow = t >> 63;
+        }
+        let t = un[j + n].wrapping_sub(carry).wrapping_sub(borrow);
+        un[j + n] = t & MASK;
+
+        // The estimate was one too large, so add the divisor back.
+        if t >> 63 == 1 {
+            qhat -= 1;
+            let mut carry = 0;
+            for i in 0..n {
+                let t = un[i + j] + vn[i] + carry;
+                un[i + j] = t & MASK;
+                carry = t >> 32;
+            }
+            un[j + n] = (un[j + n] + carry) & MASK;
+        }
+
+        q[j] = qhat;
+    }
+
+    let r: Vec<u64> = (0..n)
+        .map(|i| (un[i] >> s) | ((un[i + 1] << (32 - s)) & MASK))
+        .collect();
+
+    from_digits(&q, quo);
+    from_digits(&r, rem);
+}
+
 #[derive(Debug)]
 pub struct BitIterator<E> {
     t: E,
@@ -609,6 +1403,135 @@ impl<E: AsRef<[u64]>> Iterator for BitIterator<E> {
     }
 }
 
+/// Computes the Jacobi symbol `(a / b)` of the little-endian limbs `a` over
+/// the odd `b`, which have the same length, as 0, 1 or -1. Both are
+/// overwritten, and `ta` and `tb` of the same length are used as scratch.
+///
+/// This is the binary GCD of Pornin, "Optimized Binary GCD for Modular
+/// Inversion" <https://eprint.iacr.org/2020/972>, which runs steps on 64-bit
+/// approximations of `a` and `b` and then applies them to the full numbers,
+/// flipping the symbol by quadratic reciprocity on each swap and by `(2 / b)`
+/// on each halving. Its running time only depends on the number of limbs.
+fn jacobi_limbs(a: &mut [u64], b: &mut [u64], ta: &mut [u64], tb: &mut [u64]) -> i32 {
+    // The approximations keep the low 31 bits exact, but the symbol needs
+    // the low 3 bits of b, so two steps fewer than 31 are taken at a time.
+    const STEPS: usize = 29;
+    const LOW: u64 = (1 << 31) - 1;
+
+    // Approximates a and b by their low 31 bits and the 33 bits from the top
+    // bit of the larger one down, which is exact when both fit in 64 bits.
+    fn approximate(a: &[u64], b: &[u64]) -> (u64, u64) {
+        let n = a.len();
+        if n == 1 {
+            return (a[0], b[0]);
+        }
+
+        let (mut a_hi, mut a_lo) = (a[n - 1], a[n - 2]);
+        let (mut b_hi, mut b_lo) = (b[n - 1], b[n - 2]);
+        for i in (0..n - 2).rev() {
+            // Shift down by a limb while the top limbs are both zero.
+            let x = a_hi | b_hi;
+            let mask = ((x | x.wrapping_neg()) >> 63).wrapping_sub(1);
+            a_hi ^= mask & (a_hi ^ a_lo);
+            a_lo ^= mask & (a_lo ^ a[i]);
+            b_hi ^= mask & (b_hi ^ b_lo);
+            b_lo ^= mask & (b_lo ^ b[i]);
+        }
+
+        // Shifts by up to 64 bits are split in two, as a single shift has to
+        // be by less than 64.
+        let s = (a_hi | b_hi).leading_zeros();
+        let top = |hi: u64, lo: u64| {
+            let r = 64 - s;
+            ((hi << (s / 2)) << (s - s / 2)) | ((lo >> (r / 2)) >> (r - r / 2))
+        };
+
+        (
+            (top(a_hi, a_lo) & !LOW) | (a[0] & LOW),
+            (top(b_hi, b_lo) & !LOW) | (b[0] & LOW),
+        )
+    }
+
+    // Writes |a * f + b * g| / 2^STEPS, which is exact, to `out`, returning
+    // all ones if it is negative and zero otherwise.
+    fn combine(a: &[u64], b: &[u64], f: u64, g: u64, out: &mut [u64]) -> u64 {
+        let (fs, gs) = (((f as i64) >> 63) as u64, ((g as i64) >> 63) as u64);
+        let (f, g) = ((f ^ fs).wrapping_sub(fs), (g ^ gs).wrapping_sub(gs));
+
+        // The sum in two's complement with one more limb, shifted into `out`
+        // as it is computed.
+        let (mut carry_a, mut carry_b) = (0, 0);
+        let (mut neg_a, mut neg_b) = (fs & 1, gs & 1);
+        let mut carry = 0;
+        let mut prev = 0;
+        for i in 0..a.len() + 1 {
+            let (x, y) = if i < a.len() { (a[i], b[i]) } else { (0, 0) };
+            let x = adc(mac_with_carry(0, x, f, &mut carry_a) ^ fs, 0, &mut neg_a);
+            let y = adc(mac_with_carry(0, y, g, &mut carry_b) ^ gs, 0, &mut neg_b);
+            let t = adc(x, y, &mut carry);
+            if i > 0 {
+                out[i - 1] = (prev >> STEPS) | (t << (64 - STEPS));
+            }
+            prev = t;
+        }
+
+        let neg = ((prev as i64) >> 63) as u64;
+        let mut carry = neg & 1;
+        for l in out.iter_mut() {
+            *l = adc(*l ^ neg, 0, &mut carry);
+        }
+
+        neg
+    }
+
+    // The parity of the number of sign flips, in the low bit.
+    let mut flips = 0u64;
+    for _ in 0..(128 * a.len() + STEPS - 2) / STEPS {
+        let (mut xa, mut xb) = approximate(a, b);
+        let (mut f0, mut g0, mut f1, mut g1) = (1u64, 0u64, 0u64, 1u64);
+        for _ in 0..STEPS {
+            let odd = (xa & 1).wrapping_neg();
+            let swap = odd & (xa.overflowing_sub(xb).1 as u64).wrapping_neg();
+
+            // (a / b) = -(b / a) when both are 3 mod 4.
+            flips ^= swap & ((xa & xb) >> 1);
+            let t = swap & (xa ^ xb);
+            xa ^= t;
+            xb ^= t;
+            let t = swap & (f0 ^ f1);
+            f0 ^= t;
+            f1 ^= t;
+            let t = swap & (g0 ^ g1);
+            g0 ^= t;
+            g1 ^= t;
+
+            xa = xa.wrapping_sub(odd & xb);
+            f0 = f0.wrapping_sub(odd & f1);
+            g0 = g0.wrapping_sub(odd & g1);
+            xa >>= 1;
+            f1 <<= 1;
+            g1 <<= 1;
+
+            // (2 / b) = -1 when b is 3 or 5 mod 8.
+            flips ^= xb.wrapping_add(2) >> 2;
+        }
+
+        let neg_a = combine(a, b, f0, g0, ta);
+        combine(a, b, f1, g1, tb);
+        a.copy_from_slice(ta);
+        b.copy_from_slice(tb);
+
+        // (-a / b) = -(a / b) when b is 3 mod 4.
+        flips ^= neg_a & (b[0] >> 1);
+    }
+
+    // a is now zero and b the greatest common divisor, which must be 1.
+    let x = b[1..].iter().fold(b[0] ^ 1, |acc, &l| acc | l);
+    let coprime = ((x | x.wrapping_neg()) >> 63) ^ 1;
+
+    (coprime as i32) * (1 - 2 * (flips & 1) as i32)
+}
+
 #[test]
 fn test_bit_iterator() {
     let mut a = BitIterator::new([0xa953d79b83f6ab59, 0x6dea2059e200bd39]);
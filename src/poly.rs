@@ -0,0 +1,549 @@
+//! Univariate polynomials over a prime field.
+//!
+//! Dense polynomials hold every coefficient and are multiplied using the
+//! FFT whenever the field has a large enough subgroup of order a power of
+//! two, and by schoolbook multiplication otherwise. Sparse polynomials only
+//! hold their nonzero terms, as is natural for vanishing polynomials such as
+//! `X^n - 1`.
+
+use std::cmp;
+
+use super::fft::EvaluationDomain;
+use super::PrimeField;
+
+/// The length below which operands are multiplied by schoolbook
+/// multiplication, and divided by long division.
+const SCHOOLBOOK_THRESHOLD: usize = 32;
+
+/// The number of points below which a polynomial is evaluated at each point
+/// separately rather than through a subproduct tree.
+const SUBPRODUCT_LEAF_SIZE: usize = 16;
+
+/// A polynomial given by its coefficients, lowest degree first.
+///
+/// The coefficients never end with a zero, so the zero polynomial has no
+/// coefficients at all and equal polynomials have equal representations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DensePolynomial<F: PrimeField> {
+    coeffs: Vec<F>,
+}
+
+impl<F: PrimeField> DensePolynomial<F> {
+    /// Returns the zero polynomial.
+    pub fn zero() -> Self {
+        DensePolynomial { coeffs: vec![] }
+    }
+
+    /// Returns the polynomial with the given coefficients, lowest degree
+    /// first.
+    pub fn from_coefficients(coeffs: Vec<F>) -> Self {
+        let mut res = DensePolynomial { coeffs };
+        res.trim();
+        res
+    }
+
+    /// Interpolates the polynomial of degree less than the size of the
+    /// domain which takes the values `evals` at the elements of the domain,
+    /// padding `evals` with zeros.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more values than elements in the domain.
+    pub fn from_evaluations(domain: &EvaluationDomain<F>, evals: &[F]) -> Self {
+        assert!(
+            evals.len() <= domain.size(),
+            "too many evaluations for the domain"
+        );
+
+        let mut coeffs = evals.to_vec();
+        coeffs.resize(domain.size(), F::zero());
+        domain.ifft(&mut coeffs);
+
+        Self::from_coefficients(coeffs)
+    }
+
+    /// Returns the coefficients of the polynomial, lowest degree first.
+    pub fn coefficients(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    /// Returns whether this is the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// Returns the degree of the polynomial, or `None` for the zero
+    /// polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    /// Evaluates the polynomial at `x` by Horner's rule.
+    pub fn evaluate(&self, x: &F) -> F {
+        let mut res = F::zero();
+        for c in self.coeffs.iter().rev() {
+            res.mul_assign(x);
+            res.add_assign(c);
+        }
+
+        res
+    }
+
+    /// Evaluates the polynomial at every element of the domain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the degree of the polynomial is not below the size of the
+    /// domain.
+    pub fn evaluate_over_domain(&self, domain: &EvaluationDomain<F>) -> Vec<F> {
+        assert!(
+            self.coeffs.len() <= domain.size(),
+            "polynomial too large for the domain"
+        );
+
+        let mut evals = self.coeffs.clone();
+        evals.resize(domain.size(), F::zero());
+        domain.fft(&mut evals);
+
+        evals
+    }
+
+    /// Evaluates the polynomial at every one of `points`. Beyond a few
+    /// points, this reduces the polynomial modulo the products of ever
+    /// smaller halves of the points, taking `O(n log^2 n)` operations for
+    /// `n` points and a polynomial of degree below `n`.
+    pub fn evaluate_many(&self, points: &[F]) -> Vec<F> {
+        if points.len() <= SUBPRODUCT_LEAF_SIZE {
+            return points.iter().map(|x| self.evaluate(x)).collect();
+        }
+
+        let tree = SubproductTree::new(points);
+        let mut res = Vec::with_capacity(points.len());
+        tree.evaluate(&self.rem(&tree.product), &mut res);
+
+        res
+    }
+
+    /// Adds another polynomial to this polynomial.
+    pub fn add_assign(&mut self, other: &Self) {
+        if self.coeffs.len() < other.coeffs.len() {
+            self.coeffs.resize(other.coeffs.len(), F::zero());
+        }
+        for (a, b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
+            a.add_assign(b);
+        }
+
+        self.trim();
+    }
+
+    /// Subtracts another polynomial from this polynomial.
+    pub fn sub_assign(&mut self, other: &Self) {
+        if self.coeffs.len() < other.coeffs.len() {
+            self.coeffs.resize(other.coeffs.len(), F::zero());
+        }
+        for (a, b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
+            a.sub_assign(b);
+        }
+
+        self.trim();
+    }
+
+    /// Negates this polynomial.
+    pub fn negate(&mut self) {
+        for c in &mut self.coeffs {
+            c.negate();
+        }
+    }
+
+    /// Multiplies this polynomial by a scalar.
+    pub fn scale(&mut self, by: &F) {
+        if by.is_zero() {
+            self.coeffs.clear();
+        }
+        for c in &mut self.coeffs {
+            c.mul_assign(by);
+        }
+    }
+
+    /// Multiplies this polynomial by another polynomial.
+    pub fn mul_assign(&mut self, other: &Self) {
+        self.coeffs = mul(&self.coeffs, &other.coeffs);
+        self.trim();
+    }
+
+    /// Divides this polynomial by another, returning the quotient and the
+    /// remainder, whose degree is below that of the divisor, or `None` if
+    /// the divisor is zero.
+    ///
+    /// Large divisions are performed by multiplying by the inverse of the
+    /// reversed divisor as a power series, computed by Newton iteration, so
+    /// they take as many operations as a few multiplications.
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        if self.coeffs.len() < divisor.coeffs.len() {
+            return Some((Self::zero(), self.clone()));
+        }
+
+        let quotient_len = self.coeffs.len() - divisor.coeffs.len() + 1;
+        if cmp::min(quotient_len, divisor.coeffs.len()) < SCHOOLBOOK_THRESHOLD {
+            let terms = divisor.coeffs.iter().cloned().enumerate();
+            let (q, r) = long_division(&self.coeffs, terms);
+
+            return Some((Self::from_coefficients(q), Self::from_coefficients(r)));
+        }
+
+        // With rev(p) the coefficients of p in reverse order,
+        // rev(q) = rev(self) / rev(divisor) mod X^quotient_len.
+        let mut rev_divisor = divisor.coeffs.clone();
+        rev_divisor.reverse();
+        let inv = inverse_series(&rev_divisor, quotient_len);
+
+        let mut rev_self: Vec<F> = self
+            .coeffs
+            .iter()
+            .rev()
+            .take(quotient_len)
+            .cloned()
+            .collect();
+        rev_self = mul(&rev_self, &inv);
+        rev_self.truncate(quotient_len);
+        rev_self.resize(quotient_len, F::zero());
+        rev_self.reverse();
+
+        let q = Self::from_coefficients(rev_self);
+        let mut r = q.clone();
+        r.mul_assign(divisor);
+        r.negate();
+        r.add_assign(self);
+
+        Some((q, r))
+    }
+
+    /// Divides this polynomial by `X - z`, returning the quotient and the
+    /// remainder, which is the value of the polynomial at `z`.
+    pub fn divide_by_linear(&self, z: &F) -> (Self, F) {
+        if self.is_zero() {
+            return (Self::zero(), F::zero());
+        }
+
+        // Synthetic division, from the leading coefficient down.
+        let mut q = vec![F::zero(); self.coeffs.len() - 1];
+        let mut acc = F::zero();
+        for (i, c) in self.coeffs.iter().enumerate().rev() {
+            acc.mul_assign(z);
+            acc.add_assign(c);
+            if i > 0 {
+                q[i - 1] = acc;
+            }
+        }
+
+        (Self::from_coefficients(q), acc)
+    }
+
+    /// Divides this polynomial by a sparse polynomial, taking a number of
+    /// operations proportional to the number of terms of the divisor. This
+    /// returns `None` if the divisor is zero.
+    pub fn div_rem_sparse(&self, divisor: &SparsePolynomial<F>) -> Option<(Self, Self)> {
+        if divisor.is_zero() {
+            return None;
+        }
+
+        let (q, r) = long_division(&self.coeffs, divisor.terms.iter().cloned());
+
+        Some((Self::from_coefficients(q), Self::from_coefficients(r)))
+    }
+
+    /// Interpolates the polynomial of degree less than the number of points
+    /// which takes the value `values[i]` at `points[i]`, or returns `None`
+    /// if two of the points are equal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not as many values as points.
+    pub fn interpolate(points: &[F], values: &[F]) -> Option<Self> {
+        assert_eq!(points.len(), values.len(), "one value is needed per point");
+
+        // z = (X - x_0) * ... * (X - x_{n-1})
+        let mut z = vec![F::one()];
+        for x in points {
+            let mut neg_x = *x;
+            neg_x.negate();
+            z.insert(0, F::zero());
+            for i in 0..(z.len() - 1) {
+                let mut t = z[i + 1];
+                t.mul_assign(&neg_x);
+                z[i].add_assign(&t);
+            }
+        }
+        let z = Self::from_coefficients(z);
+
+        // The i-th Lagrange polynomial is z / (X - x_i), divided by its
+        // value at x_i, which is only zero if x_i is repeated.
+        let basis: Vec<Self> = points.iter().map(|x| z.divide_by_linear(x).0).collect();
+        let mut denominators: Vec<F> = basis
+            .iter()
+            .zip(points.iter())
+            .map(|(l, x)| l.evaluate(x))
+            .collect();
+        if denominators.iter().any(|d| d.is_zero()) {
+            return None;
+        }
+        F::batch_invert(&mut denominators);
+
+        let mut res = Self::zero();
+        for ((mut l, d), y) in basis.into_iter().zip(denominators).zip(values.iter()) {
+            let mut w = *y;
+            w.mul_assign(&d);
+            l.scale(&w);
+            res.add_assign(&l);
+        }
+
+        Some(res)
+    }
+
+    /// Returns the remainder of the division of this polynomial by a
+    /// nonzero divisor.
+    fn rem(&self, divisor: &Self) -> Self {
+        self.div_rem(divisor).expect("divisor is nonzero").1
+    }
+
+    fn trim(&mut self) {
+        while let Some(c) = self.coeffs.last() {
+            if !c.is_zero() {
+                break;
+            }
+            self.coeffs.pop();
+        }
+    }
+}
+
+impl<F: PrimeField> From<SparsePolynomial<F>> for DensePolynomial<F> {
+    fn from(p: SparsePolynomial<F>) -> DensePolynomial<F> {
+        let mut coeffs = vec![F::zero(); p.degree().map_or(0, |d| d + 1)];
+        for (i, c) in p.terms {
+            coeffs[i] = c;
+        }
+
+        DensePolynomial { coeffs }
+    }
+}
+
+/// A polynomial given by its nonzero terms, for polynomials with few terms
+/// compared to their degree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparsePolynomial<F: PrimeField> {
+    // The degree and coefficient of every nonzero term, by increasing degree.
+    terms: Vec<(usize, F)>,
+}
+
+impl<F: PrimeField> SparsePolynomial<F> {
+    /// Returns the polynomial with the given terms, as pairs of a degree and
+    /// a coefficient. Terms may be given in any order, and the coefficients
+    /// of terms of the same degree are added.
+    pub fn from_terms(mut terms: Vec<(usize, F)>) -> Self {
+        terms.sort_by_key(|&(i, _)| i);
+
+        let mut merged: Vec<(usize, F)> = Vec::with_capacity(terms.len());
+        for (i, c) in terms {
+            match merged.last_mut() {
+                Some(&mut (j, ref mut acc)) if j == i => acc.add_assign(&c),
+                _ => merged.push((i, c)),
+            }
+        }
+        merged.retain(|&(_, c)| !c.is_zero());
+
+        SparsePolynomial { terms: merged }
+    }
+
+    /// Returns the vanishing polynomial of the domain, `X^n - 1` for a
+    /// domain of size `n`, which is zero at exactly the elements of the
+    /// domain.
+    pub fn vanishing(domain: &EvaluationDomain<F>) -> Self {
+        let mut neg_one = F::one();
+        neg_one.negate();
+
+        SparsePolynomial {
+            terms: vec![(0, neg_one), (domain.size(), F::one())],
+        }
+    }
+
+    /// Returns the nonzero terms of the polynomial, as pairs of a degree and
+    /// a coefficient, by increasing degree.
+    pub fn terms(&self) -> &[(usize, F)] {
+        &self.terms
+    }
+
+    /// Returns whether this is the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Returns the degree of the polynomial, or `None` for the zero
+    /// polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.terms.last().map(|&(i, _)| i)
+    }
+
+    /// Evaluates the polynomial at `x`.
+    pub fn evaluate(&self, x: &F) -> F {
+        let mut res = F::zero();
+        for &(i, ref c) in &self.terms {
+            let mut t = x.pow([i as u64]);
+            t.mul_assign(c);
+            res.add_assign(&t);
+        }
+
+        res
+    }
+}
+
+/// The product of `X - x` over some points, split in halves down to a few
+/// points at a time.
+struct SubproductTree<F: PrimeField> {
+    product: DensePolynomial<F>,
+    node: SubproductNode<F>,
+}
+
+enum SubproductNode<F: PrimeField> {
+    Leaf(Vec<F>),
+    Branch(Box<SubproductTree<F>>, Box<SubproductTree<F>>),
+}
+
+impl<F: PrimeField> SubproductTree<F> {
+    fn new(points: &[F]) -> Self {
+        if points.len() <= SUBPRODUCT_LEAF_SIZE {
+            let mut product = DensePolynomial::from_coefficients(vec![F::one()]);
+            for x in points {
+                let mut neg_x = *x;
+                neg_x.negate();
+                product.mul_assign(&DensePolynomial::from_coefficients(vec![neg_x, F::one()]));
+            }
+
+            return SubproductTree {
+                product,
+                node: SubproductNode::Leaf(points.to_vec()),
+            };
+        }
+
+        let (left, right) = points.split_at(points.len() / 2);
+        let left = SubproductTree::new(left);
+        let right = SubproductTree::new(right);
+        let mut product = left.product.clone();
+        product.mul_assign(&right.product);
+
+        SubproductTree {
+            product,
+            node: SubproductNode::Branch(Box::new(left), Box::new(right)),
+        }
+    }
+
+    /// Evaluates `p`, already reduced modulo the product of this subtree, at
+    /// every point of the subtree, in order.
+    fn evaluate(&self, p: &DensePolynomial<F>, res: &mut Vec<F>) {
+        match self.node {
+            SubproductNode::Leaf(ref points) => res.extend(points.iter().map(|x| p.evaluate(x))),
+            SubproductNode::Branch(ref left, ref right) => {
+                left.evaluate(&p.rem(&left.product), res);
+                right.evaluate(&p.rem(&right.product), res);
+            }
+        }
+    }
+}
+
+/// Multiplies two polynomials given by their coefficients, using the FFT
+/// unless either is short or the field has no large enough domain.
+fn mul<F: PrimeField>(a: &[F], b: &[F]) -> Vec<F> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let len = a.len() + b.len() - 1;
+    if cmp::min(a.len(), b.len()) >= SCHOOLBOOK_THRESHOLD {
+        if let Some(domain) = EvaluationDomain::new(len) {
+            let mut a = a.to_vec();
+            let mut b = b.to_vec();
+            a.resize(domain.size(), F::zero());
+            b.resize(domain.size(), F::zero());
+            domain.fft(&mut a);
+            domain.fft(&mut b);
+            for (a, b) in a.iter_mut().zip(b.iter()) {
+                a.mul_assign(b);
+            }
+            domain.ifft(&mut a);
+            a.truncate(len);
+
+            return a;
+        }
+    }
+
+    let mut res = vec![F::zero(); len];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            let mut t = *x;
+            t.mul_assign(y);
+            res[i + j].add_assign(&t);
+        }
+    }
+
+    res
+}
+
+/// Returns the first `n` coefficients of the inverse of `f` as a power
+/// series, whose constant coefficient must be nonzero.
+fn inverse_series<F: PrimeField>(f: &[F], n: usize) -> Vec<F> {
+    let mut g = vec![f[0].inverse().expect("constant coefficient is nonzero")];
+
+    // Each step doubles the precision: g' = g * (2 - f * g) mod X^k.
+    let mut two = F::one();
+    two.double();
+    while g.len() < n {
+        let k = cmp::min(2 * g.len(), n);
+
+        let mut t = mul(&f[..cmp::min(k, f.len())], &g);
+        t.truncate(k);
+        for c in &mut t {
+            c.negate();
+        }
+        t[0].add_assign(&two);
+
+        g = mul(&g, &t);
+        g.truncate(k);
+    }
+
+    g
+}
+
+/// Divides the polynomial with coefficients `a` by the polynomial with the
+/// given terms, by increasing degree and ending with a nonzero leading term,
+/// returning the coefficients of the quotient and the remainder.
+fn long_division<F, I>(a: &[F], terms: I) -> (Vec<F>, Vec<F>)
+where
+    F: PrimeField,
+    I: Iterator<Item = (usize, F)> + Clone,
+{
+    let (degree, lead) = terms.clone().last().expect("divisor is nonzero");
+    let lead_inv = lead.inverse().expect("leading coefficient is nonzero");
+
+    let mut r = a.to_vec();
+    if r.len() <= degree {
+        return (vec![], r);
+    }
+
+    let mut q = vec![F::zero(); r.len() - degree];
+    for i in (0..q.len()).rev() {
+        let mut c = r[i + degree];
+        c.mul_assign(&lead_inv);
+        q[i] = c;
+
+        for (j, t) in terms.clone() {
+            let mut t = t;
+            t.mul_assign(&c);
+            r[i + j].sub_assign(&t);
+        }
+    }
+    r.truncate(degree);
+
+    (q, r)
+}
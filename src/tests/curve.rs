@@ -1,7 +1,12 @@
 use rand::{Rand, Rng, SeedableRng, XorShiftRng};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use {CurveAffine, CurveProjective, EncodedPoint, Field};
 
+#[cfg(feature = "constant-time")]
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
 pub fn curve_tests<G: CurveProjective>() {
     let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
 
@@ -67,7 +72,7 @@ pub fn curve_tests<G: CurveProjective>() {
 
 fn random_wnaf_tests<G: CurveProjective>() {
     use wnaf::*;
-    use PrimeField;
+    use {PrimeField, PrimeFieldRepr};
 
     let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
 
@@ -176,6 +181,188 @@ fn random_wnaf_tests<G: CurveProjective>() {
             assert_eq!(g1, g9);
         }
     }
+
+    {
+        for w in 1..10 {
+            let g = G::rand(&mut rng);
+            let table = FixedBaseTable::new(g, w);
+            assert_eq!(table.window(), w);
+            assert!(table.exp(G::Scalar::zero().into_repr()).is_zero());
+
+            for _ in 0..10 {
+                let s = G::Scalar::rand(&mut rng).into_repr();
+                let mut g1 = g;
+                g1.mul_assign(s);
+
+                assert_eq!(g1, table.exp(s));
+            }
+
+            // Scalars are not required to be reduced.
+            let mut s = G::Scalar::char();
+            s.add_nocarry(&G::Scalar::char());
+            let mut g1 = g;
+            g1.mul_assign(s);
+            assert_eq!(g1, table.exp(s));
+        }
+    }
+
+    {
+        assert!(multiexp::<G>(&[], &[]).is_zero());
+
+        for n in 1..10 {
+            let bases: Vec<G> = (0..n).map(|_| G::rand(&mut rng)).collect();
+            let mut scalars: Vec<_> = (0..n)
+                .map(|_| G::Scalar::rand(&mut rng).into_repr())
+                .collect();
+            // Scalars of different lengths.
+            scalars[0].shr(128);
+            if n > 1 {
+                scalars[1] = G::Scalar::zero().into_repr();
+            }
+
+            let mut expected = G::zero();
+            for (base, scalar) in bases.iter().zip(scalars.iter()) {
+                let mut tmp = *base;
+                tmp.mul_assign(*scalar);
+                expected.add_assign(&tmp);
+            }
+
+            assert_eq!(expected, multiexp(&bases, &scalars));
+        }
+    }
+}
+
+#[cfg(feature = "constant-time")]
+pub fn random_ct_tests<G: CurveAffine + ConditionallySelectable + ConstantTimeEq>()
+where
+    for<'a> &'a G: Neg<Output = G>,
+{
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    // Negating the point at infinity, conditionally or not, leaves it
+    // unchanged.
+    let mut z = G::zero();
+    z.conditional_negate(Choice::from(1));
+    assert_eq!(z, G::zero());
+    z.negate();
+    assert_eq!(z, G::zero());
+
+    for _ in 0..100 {
+        let a = G::Projective::rand(&mut rng).into_affine();
+        let b = G::Projective::rand(&mut rng).into_affine();
+
+        for &(p, q) in &[(a, b), (a, G::zero()), (G::zero(), b)] {
+            assert!(bool::from(p.ct_eq(&p)));
+            assert_eq!(bool::from(p.ct_eq(&q)), p == q);
+
+            assert_eq!(G::conditional_select(&p, &q, Choice::from(0)), p);
+            assert_eq!(G::conditional_select(&p, &q, Choice::from(1)), q);
+
+            let (mut c, mut d) = (p, q);
+            G::conditional_swap(&mut c, &mut d, Choice::from(1));
+            assert_eq!((c, d), (q, p));
+        }
+
+        let mut c = a;
+        c.conditional_negate(Choice::from(0));
+        assert_eq!(c, a);
+        c.conditional_negate(Choice::from(1));
+        assert_eq!(c, -&a);
+        assert!(c != a);
+
+        let mut sum = a.into_projective();
+        sum.add_assign_mixed(&c);
+        assert!(sum.is_zero());
+    }
+}
+
+// Every combination of operands by value and by reference is exercised.
+#[allow(clippy::op_ref)]
+pub fn random_operator_tests<G>()
+where
+    G: CurveProjective
+        + Add<Output = G>
+        + Sub<Output = G>
+        + Add<<G as CurveProjective>::Affine, Output = G>
+        + Sub<<G as CurveProjective>::Affine, Output = G>
+        + Mul<<G as CurveProjective>::Scalar, Output = G>
+        + Neg<Output = G>
+        + for<'a> Add<&'a G, Output = G>
+        + for<'a> Sub<&'a G, Output = G>
+        + for<'a> Add<&'a <G as CurveProjective>::Affine, Output = G>
+        + for<'a> Mul<&'a <G as CurveProjective>::Scalar, Output = G>
+        + AddAssign
+        + SubAssign
+        + AddAssign<<G as CurveProjective>::Affine>
+        + SubAssign<<G as CurveProjective>::Affine>
+        + MulAssign<<G as CurveProjective>::Scalar>
+        + Sum
+        + Sum<<G as CurveProjective>::Affine>
+        + for<'a> Sum<&'a G>,
+    <G as CurveProjective>::Affine: Neg<Output = <G as CurveProjective>::Affine>
+        + Mul<<G as CurveProjective>::Scalar, Output = G>,
+    for<'a> &'a G: Add<&'a G, Output = G> + Sub<G, Output = G> + Neg<Output = G>,
+{
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..10 {
+        let a = G::rand(&mut rng);
+        let b = G::rand(&mut rng);
+        let b_affine = b.into_affine();
+        let s = G::Scalar::rand(&mut rng);
+
+        let mut sum = a;
+        CurveProjective::add_assign(&mut sum, &b);
+        let mut difference = a;
+        CurveProjective::sub_assign(&mut difference, &b);
+        let mut product = a;
+        CurveProjective::mul_assign(&mut product, s);
+        let mut negation = a;
+        CurveProjective::negate(&mut negation);
+
+        assert_eq!(a + b, sum);
+        assert_eq!(a + &b, sum);
+        assert_eq!(&a + &b, sum);
+        assert_eq!(a + b_affine, sum);
+        assert_eq!(a + &b_affine, sum);
+        assert_eq!(a - b, difference);
+        assert_eq!(a - &b, difference);
+        assert_eq!(&a - b, difference);
+        assert_eq!(a - b_affine, difference);
+        assert_eq!(a * s, product);
+        assert_eq!(a * &s, product);
+        assert_eq!(a.into_affine() * s, product);
+        assert_eq!(-a, negation);
+        assert_eq!(-&a, negation);
+        assert_eq!((-a.into_affine()).into_projective(), negation);
+
+        let mut tmp = a;
+        tmp += b;
+        assert_eq!(tmp, sum);
+        let mut tmp = a;
+        tmp += b_affine;
+        assert_eq!(tmp, sum);
+        let mut tmp = a;
+        tmp -= b;
+        assert_eq!(tmp, difference);
+        let mut tmp = a;
+        tmp -= b_affine;
+        assert_eq!(tmp, difference);
+        let mut tmp = a;
+        tmp *= s;
+        assert_eq!(tmp, product);
+
+        let v: Vec<G> = (0..10).map(|_| G::rand(&mut rng)).collect();
+        let mut sum = G::zero();
+        for e in &v {
+            CurveProjective::add_assign(&mut sum, e);
+        }
+        assert_eq!(v.iter().sum::<G>(), sum);
+        assert_eq!(v.iter().cloned().sum::<G>(), sum);
+        assert_eq!(v.iter().map(|e| e.into_affine()).sum::<G>(), sum);
+    }
+
+    assert!(Vec::<G>::new().into_iter().sum::<G>().is_zero());
 }
 
 fn random_negation_tests<G: CurveProjective>() {
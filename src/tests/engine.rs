@@ -1,6 +1,9 @@
 use rand::{Rand, SeedableRng, XorShiftRng};
 
-use {CurveAffine, CurveProjective, Engine, Field, PrimeField};
+use {
+    multiexp, CurveAffine, CurveProjective, EncodedTarget, Engine, Field, FixedBaseTable,
+    MillerLoopResult, MultiMillerLoop, PrimeField, PrimeFieldRepr, TargetGroup, Wnaf,
+};
 
 pub fn engine_tests<E: Engine>() {
     let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
@@ -45,6 +48,187 @@ pub fn engine_tests<E: Engine>() {
 
     random_bilinearity_tests::<E>();
     random_miller_loop_tests::<E>();
+    random_target_group_tests::<E>();
+    random_pairing_product_tests::<E>();
+}
+
+pub fn multi_miller_loop_tests<E: MultiMillerLoop>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert!(E::multi_miller_loop(vec![])
+        .final_exponentiation()
+        .is_one());
+    assert!(E::Result::one().final_exponentiation().is_one());
+    assert!(E::Result::from_fqk(E::Fqk::zero()).is_none());
+
+    for _ in 0..10 {
+        let z1 = E::G1Affine::zero().prepare();
+        let z2 = E::G2Affine::zero().prepare();
+
+        let a = E::G1::rand(&mut rng).into_affine();
+        let b = E::G2::rand(&mut rng).into_affine();
+        let c = E::G1::rand(&mut rng).into_affine();
+        let d = E::G2::rand(&mut rng).into_affine();
+
+        let ab = E::pairing_gt(a, b);
+        let cd = E::pairing_gt(c, d);
+        let mut abcd = ab;
+        abcd.mul_assign(&cd);
+
+        let a = a.prepare();
+        let b = b.prepare();
+        let c = c.prepare();
+        let d = d.prepare();
+
+        assert_eq!(E::multi_miller_loop(Some((&a, &b))).final_exponentiation(), ab);
+        assert_eq!(
+            E::multi_miller_loop(vec![(&a, &b), (&z1, &d), (&c, &z2)]).final_exponentiation(),
+            ab
+        );
+
+        // The result agrees with the untyped API it supersedes.
+        let f = E::multi_miller_loop(vec![(&a, &b), (&c, &d)]);
+        assert_eq!(*f.as_fqk(), E::miller_loop(&[(&a, &b), (&c, &d)]));
+        assert_eq!(
+            E::Result::from_fqk(E::miller_loop(&[(&a, &b), (&c, &d)])),
+            Some(f)
+        );
+        assert_eq!(f.final_exponentiation(), abcd);
+
+        // Results of disjoint sets of pairs multiply.
+        let mut g = E::multi_miller_loop(vec![(&a, &b)]);
+        g.mul_assign(&E::multi_miller_loop(vec![(&c, &d)]));
+        assert_eq!(g.final_exponentiation(), abcd);
+    }
+}
+
+fn random_pairing_product_tests<E: Engine>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert!(E::pairing_product_is_one(&[]));
+    assert!(E::pairing_product_is_one(&[
+        (E::G1Affine::zero(), E::G2Affine::one()),
+        (E::G1Affine::one(), E::G2Affine::zero()),
+    ]));
+    assert!(!E::pairing_product_is_one(&[(
+        E::G1Affine::one(),
+        E::G2Affine::one()
+    )]));
+
+    for _ in 0..100 {
+        let a = E::G1::rand(&mut rng);
+        let b = E::G2::rand(&mut rng);
+        let c = E::Fr::rand(&mut rng);
+
+        let mut ac = a;
+        ac.mul_assign(c);
+        let mut bc = b;
+        bc.mul_assign(c);
+
+        // e(a * c, b) = e(a, b * c)
+        assert!(E::pairings_equal(
+            (ac.into_affine(), b.into_affine()),
+            (a.into_affine(), bc.into_affine())
+        ));
+        assert!(!E::pairings_equal(
+            (a.into_affine(), b.into_affine()),
+            (a.into_affine(), bc.into_affine())
+        ));
+
+        // e(a * c, b) * e(-a, b * c) * e(0, b) = 1
+        let mut neg_a = a;
+        neg_a.negate();
+        assert!(E::pairing_product_is_one(&[
+            (ac.into_affine(), b.into_affine()),
+            (neg_a.into_affine(), bc.into_affine()),
+            (E::G1Affine::zero(), b.into_affine()),
+        ]));
+        assert!(!E::pairing_product_is_one(&[
+            (ac.into_affine(), b.into_affine()),
+            (a.into_affine(), bc.into_affine()),
+        ]));
+    }
+}
+
+fn random_target_group_tests<E: Engine>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert!(E::Gt::one().is_one());
+    assert!(E::Gt::one().to_uncompressed().to_target().unwrap().is_one());
+    assert!(E::Gt::one().to_compressed().to_target().unwrap().is_one());
+    assert_eq!(
+        E::Gt::generator(),
+        E::pairing_gt(E::G1Affine::one(), E::G2Affine::one())
+    );
+    assert!(E::Gt::from_fqk(*E::Gt::generator().as_fqk()).is_some());
+    assert!(E::Gt::from_fqk(E::Fqk::zero()).is_none());
+
+    for _ in 0..100 {
+        let a = E::G1::rand(&mut rng);
+        let b = E::G2::rand(&mut rng);
+        let c = E::Fr::rand(&mut rng);
+        let d = E::Fr::rand(&mut rng);
+
+        let ab = E::pairing_gt(a, b);
+        assert_eq!(*ab.as_fqk(), E::pairing(a, b));
+        assert_eq!(ab.pow(c), E::Gt::from_fqk_unchecked(ab.as_fqk().pow(c.into_repr())));
+
+        // e(a, b)^c * e(a, b)^d = e(a, b)^(c + d)
+        let mut tmp1 = ab.pow(c);
+        tmp1.mul_assign(&ab.pow(d));
+        let mut cd = c;
+        cd.add_assign(&d);
+        assert_eq!(tmp1, ab.pow(cd));
+
+        // e(a, b)^2
+        let mut tmp1 = ab;
+        tmp1.square();
+        let mut tmp2 = ab;
+        tmp2.mul_assign(&ab);
+        assert_eq!(tmp1, tmp2);
+
+        // e(a, b) * e(a, b)^-1 = 1
+        let mut tmp1 = ab;
+        tmp1.invert();
+        assert_eq!(*tmp1.as_fqk(), ab.as_fqk().inverse().unwrap());
+        tmp1.mul_assign(&ab);
+        assert!(tmp1.is_one());
+
+        // e(a, b)^r = 1
+        let mut r = E::Fr::char();
+        r.sub_noborrow(&E::Fr::one().into_repr());
+        let mut tmp1 = ab.pow(r);
+        tmp1.mul_assign(&ab);
+        assert!(tmp1.is_one());
+
+        // e(a, b)^c by w-NAF, by a fixed base table and by multi-exponentiation
+        let abc = ab.pow(c);
+        assert_eq!(abc, Wnaf::new().base(ab, 1).scalar(c.into_repr()));
+        assert_eq!(abc, FixedBaseTable::new(ab, 5).exp(c.into_repr()));
+        assert_eq!(abc, multiexp(&[ab], &[c.into_repr()]));
+
+        // e(a, b)^c * g^d
+        let mut tmp1 = E::Gt::generator().pow(d);
+        tmp1.mul_assign(&abc);
+        assert_eq!(
+            tmp1,
+            multiexp(&[ab, E::Gt::generator()], &[c.into_repr(), d.into_repr()])
+        );
+
+        let random = E::Gt::rand(&mut rng);
+        assert!(E::Gt::from_fqk(*random.as_fqk()).is_some());
+        assert!(E::Gt::from_fqk(E::Fqk::rand(&mut rng)).is_none());
+
+        let encoded = ab.to_uncompressed();
+        assert_eq!(encoded.as_ref().len(), <E::Gt as TargetGroup>::Uncompressed::size());
+        assert_eq!(encoded.to_target().unwrap(), ab);
+        assert_eq!(encoded.to_target_unchecked().unwrap(), ab);
+
+        let encoded = ab.to_compressed();
+        assert_eq!(encoded.as_ref().len(), <E::Gt as TargetGroup>::Compressed::size());
+        assert_eq!(encoded.to_target().unwrap(), ab);
+        assert_eq!(encoded.to_target_unchecked().unwrap(), ab);
+    }
 }
 
 fn random_miller_loop_tests<E: Engine>() {
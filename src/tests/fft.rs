@@ -0,0 +1,169 @@
+use rand::{Rand, SeedableRng, XorShiftRng};
+
+use fft::EvaluationDomain;
+use {CurveProjective, Field, PrimeField};
+
+/// Evaluates the polynomial with coefficients `a` at `x`.
+fn evaluate<F: PrimeField>(a: &[F], x: &F) -> F {
+    let mut res = F::zero();
+    for c in a.iter().rev() {
+        res.mul_assign(x);
+        res.add_assign(c);
+    }
+
+    res
+}
+
+pub fn random_fft_tests<F: PrimeField>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert_eq!(EvaluationDomain::<F>::new(0).unwrap().size(), 1);
+    assert_eq!(EvaluationDomain::<F>::new(1).unwrap().size(), 1);
+    assert_eq!(EvaluationDomain::<F>::new(2).unwrap().size(), 2);
+    if F::S >= 2 {
+        assert_eq!(EvaluationDomain::<F>::new(3).unwrap().size(), 4);
+    }
+    assert_eq!(
+        EvaluationDomain::<F>::new(1 << F::S).unwrap().log_size(),
+        F::S
+    );
+    assert!(EvaluationDomain::<F>::new((1 << F::S) + 1).is_none());
+
+    for log_n in 0..(F::S.min(7) + 1) {
+        let domain = EvaluationDomain::<F>::new(1 << log_n).unwrap();
+        let elements = domain.elements();
+        assert_eq!(elements.len(), 1 << log_n);
+        assert_eq!(domain.generator().pow([1 << log_n]), F::one());
+        if log_n > 0 {
+            assert!(domain.generator().pow([1 << (log_n - 1)]) != F::one());
+        }
+
+        let mut coset = elements.clone();
+        for e in &mut coset {
+            e.mul_assign(&F::multiplicative_generator());
+        }
+
+        for _ in 0..10 {
+            let a: Vec<F> = (0..(1 << log_n)).map(|_| F::rand(&mut rng)).collect();
+
+            let mut evals = a.clone();
+            domain.fft(&mut evals);
+            for (e, x) in evals.iter().zip(elements.iter()) {
+                assert_eq!(*e, evaluate(&a, x));
+            }
+
+            domain.ifft(&mut evals);
+            assert_eq!(evals, a);
+
+            let mut coset_evals = a.clone();
+            domain.coset_fft(&mut coset_evals);
+            for (e, x) in coset_evals.iter().zip(coset.iter()) {
+                assert_eq!(*e, evaluate(&a, x));
+            }
+
+            domain.coset_ifft(&mut coset_evals);
+            assert_eq!(coset_evals, a);
+
+            // The parallel schedule yields the same results for any number
+            // of threads, including more threads than butterflies.
+            for &threads in &[2, 3, 4, 16, 1 << 10] {
+                let parallel = domain.clone().with_threads(threads);
+
+                let mut b = a.clone();
+                parallel.fft(&mut b);
+                assert_eq!(b, evals_of(&domain, &a));
+
+                parallel.ifft(&mut b);
+                assert_eq!(b, a);
+
+                parallel.coset_fft(&mut b);
+                assert_eq!(b, coset_evals_of(&domain, &a));
+
+                parallel.coset_ifft(&mut b);
+                assert_eq!(b, a);
+            }
+
+            let tau = F::rand(&mut rng);
+            let mut z = F::one();
+            for x in &elements {
+                let mut t = tau;
+                t.sub_assign(x);
+                z.mul_assign(&t);
+
+                assert!(domain.evaluate_vanishing_polynomial(x).is_zero());
+            }
+            assert_eq!(domain.evaluate_vanishing_polynomial(&tau), z);
+
+            // p(tau) is the sum of the evaluations of p over the domain,
+            // weighted by the Lagrange coefficients at tau.
+            let lagrange = domain.evaluate_all_lagrange_coefficients(&tau);
+            let mut sum = F::zero();
+            for (l, x) in lagrange.iter().zip(elements.iter()) {
+                let mut t = evaluate(&a, x);
+                t.mul_assign(l);
+                sum.add_assign(&t);
+            }
+            assert_eq!(sum, evaluate(&a, &tau));
+        }
+
+        for (i, x) in elements.iter().enumerate() {
+            let lagrange = domain.evaluate_all_lagrange_coefficients(x);
+            for (j, l) in lagrange.iter().enumerate() {
+                assert_eq!(*l, if i == j { F::one() } else { F::zero() });
+            }
+        }
+    }
+}
+
+fn evals_of<F: PrimeField>(domain: &EvaluationDomain<F>, a: &[F]) -> Vec<F> {
+    let mut res = a.to_vec();
+    domain.fft(&mut res);
+    res
+}
+
+fn coset_evals_of<F: PrimeField>(domain: &EvaluationDomain<F>, a: &[F]) -> Vec<F> {
+    let mut res = a.to_vec();
+    domain.coset_fft(&mut res);
+    res
+}
+
+pub fn random_group_fft_tests<G: CurveProjective>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let g = G::rand(&mut rng);
+    let apply = |a: &[G::Scalar]| -> Vec<G> {
+        a.iter()
+            .map(|s| {
+                let mut p = g;
+                p.mul_assign(*s);
+                p
+            })
+            .collect()
+    };
+
+    for log_n in 0..4 {
+        let domain = EvaluationDomain::<G::Scalar>::new(1 << log_n).unwrap();
+
+        let a: Vec<G::Scalar> = (0..(1 << log_n))
+            .map(|_| G::Scalar::rand(&mut rng))
+            .collect();
+        let points = apply(&a);
+
+        // The transforms commute with multiplication by g.
+        let mut expected = a.clone();
+        domain.fft(&mut expected);
+        let mut b = points.clone();
+        domain.group_fft(&mut b);
+        assert_eq!(b, apply(&expected));
+
+        domain.clone().with_threads(2).group_ifft(&mut b);
+        assert_eq!(b, points);
+
+        // Commitments to the powers of tau become commitments to the
+        // Lagrange basis at tau.
+        let tau = G::Scalar::rand(&mut rng);
+        let powers: Vec<G::Scalar> = (0..(1 << log_n)).map(|i| tau.pow([i])).collect();
+        let lagrange = domain.evaluate_all_lagrange_coefficients(&tau);
+        assert_eq!(domain.lagrange_basis(&apply(&powers)), apply(&lagrange));
+    }
+}
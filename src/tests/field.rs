@@ -1,5 +1,14 @@
 use rand::{Rng, SeedableRng, XorShiftRng};
-use {Field, LegendreSymbol, PrimeField, SqrtField};
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use {Field, FieldEncoding, LegendreSymbol, PrimeField, PrimeFieldRepr, SqrtField};
+
+#[cfg(feature = "bigint")]
+use num_bigint::{BigInt, BigUint, Sign};
+#[cfg(feature = "constant-time")]
+use subtle::{Choice, ConditionallyNegatable};
+#[cfg(feature = "constant-time")]
+use {CtField, CtSqrtField};
 
 pub fn random_frobenius_tests<F: Field, C: AsRef<[u64]>>(characteristic: C, maxpower: usize) {
     let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
@@ -63,6 +72,7 @@ pub fn random_field_tests<F: Field>() {
     random_doubling_tests::<F, _>(&mut rng);
     random_squaring_tests::<F, _>(&mut rng);
     random_inversion_tests::<F, _>(&mut rng);
+    random_batch_inversion_tests::<F, _>(&mut rng);
     random_expansion_tests::<F, _>(&mut rng);
 
     assert!(F::zero().is_zero());
@@ -124,6 +134,345 @@ pub fn from_str_tests<F: PrimeField>() {
     assert!(F::from_str("00000000000").is_none());
 }
 
+pub fn from_str_radix_tests<F: PrimeField>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..1000 {
+        let n: u64 = rng.gen();
+        let a = F::from_repr(n.into()).unwrap();
+
+        assert_eq!(a.to_str_radix(2), format!("{:b}", n));
+        assert_eq!(a.to_str_radix(10), format!("{}", n));
+        assert_eq!(a.to_str_radix(16), format!("{:x}", n));
+
+        assert_eq!(F::from_str_radix(&format!("{:b}", n), 2), Some(a));
+        assert_eq!(F::from_str_radix(&format!("{}", n), 10), Some(a));
+        assert_eq!(F::from_str_radix(&format!("{:#x}", n), 16), Some(a));
+        assert_eq!(F::from_str_radix(&format!("{:#X}", n), 16), Some(a));
+        assert_eq!(F::from_str_radix(&format!("{:020}", n), 10), Some(a));
+
+        let mut neg = a;
+        neg.negate();
+        assert_eq!(F::from_str_radix(&format!("-{}", n), 10), Some(neg));
+        assert_eq!(F::from_str_radix(&format!("-{:#x}", n), 16), Some(neg));
+    }
+
+    for _ in 0..100 {
+        let a = F::rand(&mut rng);
+
+        for &radix in &[2, 3, 10, 16, 36] {
+            let s = a.to_str_radix(radix);
+            assert!(!s.starts_with('0') || a.is_zero());
+            assert_eq!(F::from_str_radix(&s, radix), Some(a));
+        }
+
+        // Decimal parsing agrees with `from_str`, which also reduces.
+        let s = a.to_str_radix(10);
+        let mut big = s.clone();
+        big.push_str(&s);
+        assert_eq!(F::from_str_radix(&s, 10), F::from_str(&s));
+        assert_eq!(F::from_str_radix(&big, 10), F::from_str(&big));
+    }
+
+    assert_eq!(F::zero().to_str_radix(10), "0");
+    assert_eq!(F::from_str_radix("0", 10), Some(F::zero()));
+    assert_eq!(F::from_str_radix("-0", 10), Some(F::zero()));
+    assert_eq!(F::from_str_radix("0000", 16), Some(F::zero()));
+    assert_eq!(F::from_str_radix("0x0", 16), Some(F::zero()));
+    assert!(F::from_str_radix("", 10).is_none());
+    assert!(F::from_str_radix("-", 10).is_none());
+    assert!(F::from_str_radix("0x", 16).is_none());
+    assert!(F::from_str_radix("0x1", 10).is_none());
+    assert!(F::from_str_radix("12a", 10).is_none());
+    assert!(F::from_str_radix("2", 2).is_none());
+    assert!(F::from_str_radix("--1", 10).is_none());
+    assert!(F::from_str_radix(" 1", 10).is_none());
+}
+
+#[cfg(feature = "bigint")]
+pub fn random_bigint_tests<F: PrimeField>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let modulus = F::modulus_biguint();
+    assert_eq!(modulus.bits(), u64::from(F::NUM_BITS));
+    assert_eq!(F::Repr::from_biguint(&modulus), Some(F::char()));
+    assert!(F::from_biguint(&modulus).is_zero());
+    assert_eq!(F::zero().to_biguint(), BigUint::from(0u32));
+    assert_eq!(F::one().to_biguint(), BigUint::from(1u32));
+
+    let limbs = F::char().as_ref().len();
+    let max = (BigUint::from(1u32) << (64 * limbs)) - 1u32;
+    assert!(F::Repr::from_biguint(&max).is_some());
+    assert!(F::Repr::from_biguint(&(max + 1u32)).is_none());
+
+    for _ in 0..1000 {
+        let a = F::rand(&mut rng);
+        let b = F::rand(&mut rng);
+        let (n, m) = (a.to_biguint(), b.to_biguint());
+
+        assert!(n < modulus);
+        assert_eq!(n.to_str_radix(10), a.to_str_radix(10));
+        assert_eq!(F::Repr::from_biguint(&n), Some(a.into_repr()));
+        assert_eq!(F::from_biguint(&n), a);
+
+        // Integers of any size are reduced.
+        let k: u64 = rng.gen();
+        let wide = &n + &modulus * k * &modulus;
+        assert_eq!(F::from_biguint(&wide), a);
+
+        let mut neg = a;
+        neg.negate();
+        assert_eq!(F::from_bigint(&BigInt::from_biguint(Sign::Minus, n.clone())), neg);
+        assert_eq!(F::from_bigint(&BigInt::from_biguint(Sign::Plus, wide)), a);
+
+        // Field arithmetic agrees with integer arithmetic modulo the modulus.
+        let mut c = a;
+        c.add_assign(&b);
+        assert_eq!(c.to_biguint(), (&n + &m) % &modulus);
+
+        let mut c = a;
+        c.mul_assign(&b);
+        assert_eq!(c.to_biguint(), (&n * &m) % &modulus);
+    }
+}
+
+pub fn random_encoding_tests<F: FieldEncoding>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert_eq!(F::zero().to_bytes_be(), vec![0; F::encoded_len()]);
+
+    for _ in 0..1000 {
+        let a = F::rand(&mut rng);
+
+        let be = a.to_bytes_be();
+        let mut le = a.to_bytes_le();
+        assert_eq!(be.len(), F::encoded_len());
+        assert_eq!(F::from_bytes_be(&be).unwrap(), a);
+        assert_eq!(F::from_bytes_le(&le).unwrap(), a);
+
+        le.reverse();
+        assert_eq!(le, be);
+
+        assert!(F::from_bytes_be(&be[1..]).is_err());
+        let mut longer = be.clone();
+        longer.push(0);
+        assert!(F::from_bytes_be(&longer).is_err());
+
+        // The most significant bits of the encoding exceed the modulus.
+        let mut invalid = be.clone();
+        invalid[0] = 0xff;
+        assert!(F::from_bytes_be(&invalid).is_err());
+        invalid.reverse();
+        assert!(F::from_bytes_le(&invalid).is_err());
+    }
+}
+
+pub fn from_bytes_reduce_tests<F: PrimeField>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert!(F::from_bytes_reduce(&[]).is_zero());
+    assert_eq!(F::from_bytes_reduce(&[1]), F::one());
+
+    let mut char_bytes = vec![];
+    F::char().write_be(&mut char_bytes).unwrap();
+    assert!(F::from_bytes_reduce(&char_bytes).is_zero());
+
+    // 2^64
+    let mut shift = F::one();
+    for _ in 0..64 {
+        shift.double();
+    }
+    assert_eq!(F::from_bytes_reduce(&[1, 0, 0, 0, 0, 0, 0, 0, 0]), shift);
+
+    for _ in 0..1000 {
+        let a = F::rand(&mut rng);
+        assert_eq!(F::from_bytes_reduce(&a.to_bytes_be()), a);
+
+        // x || y is x * 2^(8 * len(y)) + y
+        for &(len_x, len_y) in &[(32, 32), (16, 48), (5, 27), (0, 64), (1, 7)] {
+            let bytes: Vec<u8> = (0..(len_x + len_y)).map(|_| rng.gen()).collect();
+
+            let mut expected = F::from_bytes_reduce(&bytes[..len_x]);
+            for _ in 0..(8 * len_y) {
+                expected.double();
+            }
+            expected.add_assign(&F::from_bytes_reduce(&bytes[len_x..]));
+
+            assert_eq!(F::from_bytes_reduce(&bytes), expected);
+        }
+    }
+}
+
+#[cfg(feature = "constant-time")]
+pub fn random_ct_tests<F: CtField>()
+where
+    for<'a> &'a F: Neg<Output = F>,
+{
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert!(bool::from(F::zero().ct_is_zero()));
+    assert!(!bool::from(F::one().ct_is_zero()));
+    assert!(bool::from(F::zero().ct_inverse().is_none()));
+    assert_eq!(F::one().ct_inverse().unwrap(), F::one());
+
+    for _ in 0..100 {
+        let a = F::rand(&mut rng);
+        let b = F::rand(&mut rng);
+
+        assert!(bool::from(a.ct_eq(&a)));
+        assert_eq!(bool::from(a.ct_eq(&b)), a == b);
+        assert_eq!(bool::from(a.ct_is_zero()), a.is_zero());
+
+        assert_eq!(F::conditional_select(&a, &b, Choice::from(0)), a);
+        assert_eq!(F::conditional_select(&a, &b, Choice::from(1)), b);
+
+        let (mut c, mut d) = (a, b);
+        F::conditional_swap(&mut c, &mut d, Choice::from(0));
+        assert_eq!((c, d), (a, b));
+        F::conditional_swap(&mut c, &mut d, Choice::from(1));
+        assert_eq!((c, d), (b, a));
+
+        let mut c = a;
+        c.conditional_negate(Choice::from(0));
+        assert_eq!(c, a);
+        c.conditional_negate(Choice::from(1));
+        let mut neg = a;
+        neg.negate();
+        assert_eq!(c, neg);
+
+        let inv = a.ct_inverse();
+        assert!(bool::from(inv.is_some()));
+        assert_eq!(inv.unwrap(), a.inverse().unwrap());
+    }
+}
+
+#[cfg(feature = "constant-time")]
+pub fn random_ct_sqrt_tests<F: CtSqrtField>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    assert_eq!(F::zero().ct_sqrt().unwrap(), F::zero());
+
+    for _ in 0..100 {
+        let a = F::rand(&mut rng);
+        let mut b = a;
+        b.square();
+
+        let root = b.ct_sqrt().unwrap();
+        let mut neg = root;
+        neg.negate();
+        assert!(a == root || a == neg);
+
+        // Nonresidues have no square root, and the roots that do exist
+        // agree with `sqrt()` up to sign.
+        let root = a.ct_sqrt();
+        match a.sqrt() {
+            Some(expected) => {
+                let root = root.unwrap();
+                let mut neg = root;
+                neg.negate();
+                assert!(expected == root || expected == neg);
+            }
+            None => assert!(bool::from(root.is_none())),
+        }
+    }
+}
+
+// Every combination of operands by value and by reference is exercised.
+#[allow(clippy::op_ref)]
+pub fn random_operator_tests<F>()
+where
+    F: Field
+        + Add<Output = F>
+        + Sub<Output = F>
+        + Mul<Output = F>
+        + Neg<Output = F>
+        + for<'a> Add<&'a F, Output = F>
+        + for<'a> Sub<&'a F, Output = F>
+        + for<'a> Mul<&'a F, Output = F>
+        + AddAssign
+        + SubAssign
+        + MulAssign
+        + for<'a> AddAssign<&'a F>
+        + for<'a> SubAssign<&'a F>
+        + for<'a> MulAssign<&'a F>
+        + Sum
+        + Product
+        + for<'a> Sum<&'a F>
+        + for<'a> Product<&'a F>,
+    for<'a> &'a F: Add<F, Output = F>
+        + Sub<F, Output = F>
+        + Mul<F, Output = F>
+        + Add<&'a F, Output = F>
+        + Sub<&'a F, Output = F>
+        + Mul<&'a F, Output = F>
+        + Neg<Output = F>,
+{
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..100 {
+        let a = F::rand(&mut rng);
+        let b = F::rand(&mut rng);
+
+        let mut sum = a;
+        Field::add_assign(&mut sum, &b);
+        let mut difference = a;
+        Field::sub_assign(&mut difference, &b);
+        let mut product = a;
+        Field::mul_assign(&mut product, &b);
+        let mut negation = a;
+        Field::negate(&mut negation);
+
+        assert_eq!(a + b, sum);
+        assert_eq!(a + &b, sum);
+        assert_eq!(&a + b, sum);
+        assert_eq!(&a + &b, sum);
+        assert_eq!(a - b, difference);
+        assert_eq!(a - &b, difference);
+        assert_eq!(&a - b, difference);
+        assert_eq!(&a - &b, difference);
+        assert_eq!(a * b, product);
+        assert_eq!(a * &b, product);
+        assert_eq!(&a * b, product);
+        assert_eq!(&a * &b, product);
+        assert_eq!(-a, negation);
+        assert_eq!(-&a, negation);
+
+        let mut tmp = a;
+        tmp += b;
+        assert_eq!(tmp, sum);
+        let mut tmp = a;
+        tmp += &b;
+        assert_eq!(tmp, sum);
+        let mut tmp = a;
+        tmp -= b;
+        assert_eq!(tmp, difference);
+        let mut tmp = a;
+        tmp -= &b;
+        assert_eq!(tmp, difference);
+        let mut tmp = a;
+        tmp *= b;
+        assert_eq!(tmp, product);
+        let mut tmp = a;
+        tmp *= &b;
+        assert_eq!(tmp, product);
+
+        let v: Vec<F> = (0..10).map(|_| F::rand(&mut rng)).collect();
+        let mut sum = F::zero();
+        let mut product = F::one();
+        for e in &v {
+            Field::add_assign(&mut sum, e);
+            Field::mul_assign(&mut product, e);
+        }
+        assert_eq!(v.iter().sum::<F>(), sum);
+        assert_eq!(v.iter().cloned().sum::<F>(), sum);
+        assert_eq!(v.iter().product::<F>(), product);
+        assert_eq!(v.iter().cloned().product::<F>(), product);
+    }
+
+    assert!(Vec::<F>::new().into_iter().sum::<F>().is_zero());
+    assert_eq!(Vec::<F>::new().into_iter().product::<F>(), F::one());
+}
+
 fn random_multiplication_tests<F: Field, R: Rng>(rng: &mut R) {
     for _ in 0..10000 {
         let a = F::rand(rng);
@@ -233,6 +582,56 @@ fn random_inversion_tests<F: Field, R: Rng>(rng: &mut R) {
     }
 }
 
+fn random_batch_inversion_tests<F: Field, R: Rng>(rng: &mut R) {
+    use std::panic;
+
+    let mut empty: Vec<F> = vec![];
+    F::batch_invert(&mut empty);
+    assert!(F::batch_inverse(&empty).is_empty());
+
+    let mut scratch = vec![];
+
+    for n in 1..40 {
+        let v: Vec<F> = (0..n).map(|_| F::rand(rng)).collect();
+        let expected: Vec<F> = v.iter().map(|e| e.inverse().unwrap()).collect();
+
+        let mut inverted = v.clone();
+        F::batch_invert(&mut inverted);
+        assert_eq!(inverted, expected);
+
+        let mut inverted = v.clone();
+        F::batch_invert_with_scratch(&mut inverted, &mut scratch);
+        assert_eq!(inverted, expected);
+
+        for chunk_size in &[1, 3, 7, 64] {
+            let mut inverted = v.clone();
+            F::batch_invert_chunked(&mut inverted, *chunk_size, &mut scratch);
+            assert_eq!(inverted, expected);
+            assert!(scratch.len() <= 2 * chunk_size);
+        }
+
+        // Replace some elements with zero.
+        let mut v = v;
+        for i in (0..n).filter(|i| i % 3 == 1) {
+            v[i] = F::zero();
+        }
+        let inverses = F::batch_inverse(&v);
+        assert_eq!(inverses.len(), n);
+        for (e, inv) in v.iter().zip(inverses) {
+            assert_eq!(inv, e.inverse());
+        }
+
+        // Zeros cannot be inverted in place, and leave the slice unchanged.
+        if n > 1 {
+            let mut inverted = v.clone();
+            assert!(panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                F::batch_invert(&mut inverted)
+            })).is_err());
+            assert_eq!(inverted, v);
+        }
+    }
+}
+
 fn random_expansion_tests<F: Field, R: Rng>(rng: &mut R) {
     for _ in 0..10000 {
         // Compare (a + b)(c + d) and (a*c + b*c + a*d + b*d)
@@ -1,4 +1,6 @@
 pub mod curve;
 pub mod engine;
+pub mod fft;
 pub mod field;
+pub mod poly;
 pub mod repr;
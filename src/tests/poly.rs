@@ -0,0 +1,169 @@
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+use fft::EvaluationDomain;
+use poly::{DensePolynomial, SparsePolynomial};
+use PrimeField;
+
+fn random_poly<F: PrimeField, R: Rng>(rng: &mut R, len: usize) -> DensePolynomial<F> {
+    DensePolynomial::from_coefficients((0..len).map(|_| F::rand(rng)).collect())
+}
+
+pub fn random_poly_tests<F: PrimeField>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    // Representations are trimmed, so equal polynomials compare equal.
+    let zero = DensePolynomial::<F>::zero();
+    assert!(zero.is_zero());
+    assert_eq!(zero.degree(), None);
+    assert_eq!(DensePolynomial::from_coefficients(vec![F::zero(); 3]), zero);
+    let one = DensePolynomial::from_coefficients(vec![F::one(), F::zero()]);
+    assert_eq!(one.degree(), Some(0));
+    assert_eq!(one.coefficients(), &[F::one()]);
+
+    let lens = [0, 1, 2, 5, 31, 32, 33, 70, 150];
+    for &len_a in &lens {
+        for &len_b in &lens {
+            let a = random_poly::<F, _>(&mut rng, len_a);
+            let b = random_poly::<F, _>(&mut rng, len_b);
+            let x = F::rand(&mut rng);
+            let (ax, bx) = (a.evaluate(&x), b.evaluate(&x));
+
+            let mut c = a.clone();
+            c.add_assign(&b);
+            let mut expected = ax;
+            expected.add_assign(&bx);
+            assert_eq!(c.evaluate(&x), expected);
+
+            c.sub_assign(&b);
+            assert_eq!(c, a);
+            c.sub_assign(&a);
+            assert!(c.is_zero());
+
+            let mut c = a.clone();
+            c.mul_assign(&b);
+            let mut expected = ax;
+            expected.mul_assign(&bx);
+            assert_eq!(c.evaluate(&x), expected);
+            assert_eq!(
+                c.degree(),
+                a.degree().and_then(|da| b.degree().map(|db| da + db))
+            );
+
+            // a = q * b + r, with r of lower degree than b.
+            match a.div_rem(&b) {
+                None => assert!(b.is_zero()),
+                Some((q, r)) => {
+                    assert!(r.degree() < b.degree());
+                    let mut c = q;
+                    c.mul_assign(&b);
+                    c.add_assign(&r);
+                    assert_eq!(c, a);
+                }
+            }
+        }
+    }
+
+    for &len in &lens {
+        let a = random_poly::<F, _>(&mut rng, len);
+        let x = F::rand(&mut rng);
+
+        let mut c = a.clone();
+        c.negate();
+        c.add_assign(&a);
+        assert!(c.is_zero());
+
+        let s = F::rand(&mut rng);
+        let mut c = a.clone();
+        c.scale(&s);
+        let mut expected = a.evaluate(&x);
+        expected.mul_assign(&s);
+        assert_eq!(c.evaluate(&x), expected);
+        c.scale(&F::zero());
+        assert!(c.is_zero());
+
+        // a = q * (X - z) + a(z)
+        let z = F::rand(&mut rng);
+        let (q, r) = a.divide_by_linear(&z);
+        assert_eq!(r, a.evaluate(&z));
+        let mut neg_z = z;
+        neg_z.negate();
+        let mut c = q;
+        c.mul_assign(&DensePolynomial::from_coefficients(vec![neg_z, F::one()]));
+        c.add_assign(&DensePolynomial::from_coefficients(vec![r]));
+        assert_eq!(c, a);
+
+        let sparse = SparsePolynomial::from_terms(vec![
+            (7, F::rand(&mut rng)),
+            (0, F::rand(&mut rng)),
+            (3, F::one()),
+            (3, F::one()),
+            (5, F::zero()),
+        ]);
+        assert_eq!(sparse.terms().len(), 3);
+        assert_eq!(sparse.degree(), Some(7));
+        let dense = DensePolynomial::from(sparse.clone());
+        assert_eq!(sparse.evaluate(&x), dense.evaluate(&x));
+        assert_eq!(a.div_rem_sparse(&sparse), a.div_rem(&dense));
+        assert!(a
+            .div_rem_sparse(&SparsePolynomial::from_terms(vec![]))
+            .is_none());
+
+        // Multipoint evaluation agrees with evaluating at each point, for
+        // polynomials of any degree relative to the number of points.
+        for &n in &[0, 3, 17, 40, 100] {
+            let points: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+            let expected: Vec<F> = points.iter().map(|p| a.evaluate(p)).collect();
+            assert_eq!(a.evaluate_many(&points), expected);
+        }
+    }
+
+    for &n in &[0, 1, 2, 7, 20] {
+        let points: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let values: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+
+        let p = DensePolynomial::interpolate(&points, &values).unwrap();
+        if let Some(d) = p.degree() {
+            assert!(d < n);
+        }
+        for (x, y) in points.iter().zip(values.iter()) {
+            assert_eq!(p.evaluate(x), *y);
+        }
+
+        if n > 1 {
+            let mut repeated = points.clone();
+            repeated[1] = repeated[0];
+            assert!(DensePolynomial::interpolate(&repeated, &values).is_none());
+        }
+    }
+
+    let domain = EvaluationDomain::<F>::new(1 << F::S.min(4)).unwrap();
+    let vanishing = SparsePolynomial::vanishing(&domain);
+    assert_eq!(vanishing.degree(), Some(domain.size()));
+    for x in domain.elements() {
+        assert!(vanishing.evaluate(&x).is_zero());
+    }
+    let x = F::rand(&mut rng);
+    assert_eq!(
+        vanishing.evaluate(&x),
+        domain.evaluate_vanishing_polynomial(&x)
+    );
+
+    // A multiple of the vanishing polynomial vanishes over the domain, and
+    // is divided by it exactly.
+    let a = random_poly::<F, _>(&mut rng, 5);
+    let mut c = DensePolynomial::from(vanishing.clone());
+    c.mul_assign(&a);
+    assert!(c
+        .evaluate_many(&domain.elements())
+        .iter()
+        .all(|e| e.is_zero()));
+    assert_eq!(
+        c.div_rem_sparse(&vanishing),
+        Some((a, DensePolynomial::zero()))
+    );
+
+    let a = random_poly::<F, _>(&mut rng, domain.size());
+    let evals = a.evaluate_over_domain(&domain);
+    assert_eq!(evals, a.evaluate_many(&domain.elements()));
+    assert_eq!(DensePolynomial::from_evaluations(&domain, &evals), a);
+}
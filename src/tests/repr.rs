@@ -1,10 +1,258 @@
-use rand::{SeedableRng, XorShiftRng};
-use PrimeFieldRepr;
+use rand::{Rng, SeedableRng, XorShiftRng};
+use {PrimeField, PrimeFieldRepr, WidePrimeFieldRepr};
 
-pub fn random_repr_tests<R: PrimeFieldRepr>() {
+pub fn random_repr_tests<R: WidePrimeFieldRepr>() {
     random_encoding_tests::<R>();
     random_shl_tests::<R>();
     random_shr_tests::<R>();
+    random_carry_tests::<R>();
+    random_bit_tests::<R>();
+    random_mul_wide_tests::<R>();
+    random_div_rem_tests::<R>();
+    random_jacobi_tests::<R>();
+}
+
+/// Samples a representation whose limbs are often zero, all ones or have a
+/// single bit set, and whose leading limbs are often zero, to reach the
+/// edge cases of carries and long division.
+fn sample<R: PrimeFieldRepr, G: Rng>(rng: &mut G) -> R {
+    let mut r = R::rand(rng);
+    let len = r.as_ref().len();
+    let top = rng.gen_range(0, len + 1);
+    for (i, l) in r.as_mut().iter_mut().enumerate() {
+        *l = match rng.gen_range(0, 6) {
+            _ if i >= top => 0,
+            0 => 0,
+            1 => !0,
+            2 => 1 << rng.gen_range(0, 64),
+            3 => !0 << 32,
+            _ => *l,
+        };
+    }
+
+    r
+}
+
+/// Schoolbook multiplication with `u128` arithmetic.
+fn mul_u128(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut res = vec![0u64; a.len() + b.len()];
+    for (i, &a) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &b) in b.iter().enumerate() {
+            let t = u128::from(res[i + j]) + u128::from(a) * u128::from(b) + carry;
+            res[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        res[i + b.len()] = carry as u64;
+    }
+
+    res
+}
+
+/// Returns `q * d + r` as a double-width number, asserting that it does not
+/// overflow.
+fn mul_add<R: WidePrimeFieldRepr>(q: &R, d: &R, r: &R) -> R::Wide {
+    let mut res = q.mul_wide(d);
+    let mut carry = 0u128;
+    for (i, l) in res.as_mut().iter_mut().enumerate() {
+        let t = u128::from(*l) + u128::from(r.as_ref().get(i).cloned().unwrap_or(0)) + carry;
+        *l = t as u64;
+        carry = t >> 64;
+    }
+    assert_eq!(carry, 0);
+
+    res
+}
+
+fn random_carry_tests<R: PrimeFieldRepr>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..1000 {
+        let a: R = sample(&mut rng);
+        let b: R = sample(&mut rng);
+
+        // The sum overflows iff it wraps around below either summand.
+        let mut c = a;
+        let carry = c.add_with_carry(&b);
+        assert_eq!(carry, c < a);
+        let mut d = a;
+        d.add_nocarry(&b);
+        assert_eq!(c, d);
+
+        // The difference underflows iff the subtrahend is larger.
+        let borrow = c.sub_with_borrow(&b);
+        assert_eq!(c, a);
+        assert_eq!(borrow, carry);
+        let mut c = a;
+        assert_eq!(c.sub_with_borrow(&b), a < b);
+        c.add_nocarry(&b);
+        assert_eq!(c, a);
+    }
+}
+
+fn random_bit_tests<R: PrimeFieldRepr>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..100 {
+        let a: R = sample(&mut rng);
+        let width = 64 * a.as_ref().len() as u32;
+
+        for i in 0..(width + 64) {
+            let mut shifted = a;
+            shifted.shr(i);
+            assert_eq!(a.bit(i), i < width && shifted.is_odd());
+        }
+
+        let bits = (0..width).rev().skip_while(|&i| !a.bit(i)).count() as u32;
+        assert_eq!(bits, a.num_bits());
+    }
+}
+
+fn random_mul_wide_tests<R: WidePrimeFieldRepr>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..1000 {
+        let a: R = sample(&mut rng);
+        let b: R = sample(&mut rng);
+
+        let c = a.mul_wide(&b);
+        assert_eq!(c.as_ref(), &mul_u128(a.as_ref(), b.as_ref())[..]);
+        assert_eq!(c, b.mul_wide(&a));
+
+        let one = a.mul_wide(&R::from(1));
+        let len = a.as_ref().len();
+        assert_eq!(&one.as_ref()[..len], a.as_ref());
+        assert!(one.as_ref()[len..].iter().all(|&l| l == 0));
+    }
+}
+
+/// Checks that `from_wide_repr` reduces the full products of the
+/// representations of field elements to their products in the field.
+pub fn random_from_wide_repr_tests<F: PrimeField>()
+where
+    F::Repr: WidePrimeFieldRepr,
+{
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    for _ in 0..1000 {
+        let a = F::rand(&mut rng);
+        let b = F::rand(&mut rng);
+        let mut ab = a;
+        ab.mul_assign(&b);
+
+        let wide = a.into_repr().mul_wide(&b.into_repr());
+        assert_eq!(F::Repr::from_wide_repr::<F>(&wide), ab);
+    }
+}
+
+fn random_div_rem_tests<R: WidePrimeFieldRepr>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let a: R = sample(&mut rng);
+    assert!(a.div_rem(&R::from(0)).is_none());
+    assert_eq!(a.div_rem(&R::from(1)), Some((a, R::from(0))));
+    if !a.is_zero() {
+        assert_eq!(a.div_rem(&a), Some((R::from(1), R::from(0))));
+    }
+
+    // The first estimate of the quotient digit is one too large here, even
+    // after correction, so the divisor has to be added back.
+    let mut a = R::from(0);
+    a.as_mut()[0] = 0xfffffffe00000000;
+    a.as_mut()[1] = 0x80000000;
+    let b = R::from(0x80000000ffffffff);
+    assert_eq!(
+        a.div_rem(&b),
+        Some((R::from(0xffffffff), R::from(0x7fffffffffffffff)))
+    );
+
+    for _ in 0..1000 {
+        let a: R = sample(&mut rng);
+        let mut b: R = sample(&mut rng);
+        if b.is_zero() {
+            b = R::from(rng.gen::<u64>() | 1);
+        }
+
+        // a = q * b + r with r < b
+        let (q, r) = a.div_rem(&b).unwrap();
+        assert!(r < b);
+        let expected = mul_add(&q, &b, &r);
+        let len = a.as_ref().len();
+        assert_eq!(&expected.as_ref()[..len], a.as_ref());
+        assert!(expected.as_ref()[len..].iter().all(|&l| l == 0));
+
+        let d = match rng.gen_range(0, 3) {
+            0 => rng.gen_range(1, 1 << 32),
+            1 => rng.gen::<u64>() | (1 << 63),
+            _ => rng.gen::<u64>() | 1,
+        };
+        let mut q = a;
+        let r = q.div_rem_u64(d);
+        assert!(r < d);
+        let expected = mul_add(&q, &R::from(d), &R::from(r));
+        assert_eq!(&expected.as_ref()[..len], a.as_ref());
+        assert_eq!(a.div_rem(&R::from(d)), Some((q, R::from(r))));
+
+        // Reducing q * b + r modulo b gives back r, for quotients as wide
+        // as the representation.
+        let (_, r) = sample::<R, _>(&mut rng).div_rem(&b).unwrap();
+        let q: R = sample(&mut rng);
+        assert_eq!(R::rem_wide(&mul_add(&q, &b, &r), &b), r);
+    }
+}
+
+/// The Jacobi symbol `(a / n)` for odd `n` by the textbook algorithm, with
+/// reductions by long division.
+fn jacobi_reference<R: PrimeFieldRepr>(a: &R, n: &R) -> i32 {
+    let (mut a, mut n) = (a.div_rem(n).unwrap().1, *n);
+    let mut t = 1;
+    while !a.is_zero() {
+        while a.is_even() {
+            a.div2();
+            if n.as_ref()[0] & 7 == 3 || n.as_ref()[0] & 7 == 5 {
+                t = -t;
+            }
+        }
+        ::std::mem::swap(&mut a, &mut n);
+        if a.as_ref()[0] & 3 == 3 && n.as_ref()[0] & 3 == 3 {
+            t = -t;
+        }
+        a = a.div_rem(&n).unwrap().1;
+    }
+
+    if n == R::from(1) {
+        t
+    } else {
+        0
+    }
+}
+
+fn random_jacobi_tests<R: PrimeFieldRepr>() {
+    let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+    let mut max = R::from(0);
+    max.sub_noborrow(&R::from(1));
+    assert_eq!(R::from(0).jacobi(&R::from(1)), 1);
+    assert_eq!(max.jacobi(&R::from(1)), 1);
+    assert_eq!(R::from(0).jacobi(&max), 0);
+    assert_eq!(max.jacobi(&max), 0);
+    assert_eq!(R::from(2).jacobi(&R::from(3)), -1);
+
+    for _ in 0..1000 {
+        let a: R = sample(&mut rng);
+        let mut b: R = sample(&mut rng);
+        b.as_mut()[0] |= 1;
+        assert_eq!(a.jacobi(&b), jacobi_reference(&a, &b));
+
+        // Numbers that agree in their top bits, which the approximations
+        // of the binary GCD cannot tell apart.
+        let mut c = b;
+        c.sub_noborrow(&R::from(rng.gen::<u64>() >> rng.gen_range(0, 64)));
+        assert_eq!(c.jacobi(&b), jacobi_reference(&c, &b));
+        if c.is_odd() {
+            assert_eq!(b.jacobi(&c), jacobi_reference(&b, &c));
+        }
+    }
 }
 
 fn random_encoding_tests<R: PrimeFieldRepr>() {
@@ -1,16 +1,87 @@
 use super::{CurveProjective, PrimeField, PrimeFieldRepr};
 
+/// A group of prime order in which w-NAF exponentiation can be performed,
+/// written additively. This covers the elliptic curve groups as well as
+/// multiplicative groups such as the target group of a pairing, where
+/// "doubling" is squaring and "subtraction" is multiplication by an inverse.
+///
+/// w-NAF relies on subtraction being about as cheap as addition, so this
+/// should only be implemented for groups with cheap inversion.
+///
+/// The FFT over the scalar field also transforms vectors of elements of
+/// such a group; see `EvaluationDomain::group_fft`.
+pub trait WnafGroup: Copy + Send + Sync + 'static {
+    /// The scalar field of the group.
+    type Scalar: PrimeField;
+
+    /// Returns the identity of the group.
+    fn group_identity() -> Self;
+
+    /// Doubles this element.
+    fn group_double(&mut self);
+
+    /// Adds another element to this element.
+    fn group_add(&mut self, other: &Self);
+
+    /// Subtracts another element from this element.
+    fn group_sub(&mut self, other: &Self);
+
+    /// Multiplies this element by a scalar.
+    fn group_mul(&mut self, by: &Self::Scalar) {
+        *self = Wnaf::new().scalar(by.into_repr()).base(*self);
+    }
+
+    /// Recommends a wNAF window table size given a scalar.
+    fn wnaf_window_for_scalar(scalar: <Self::Scalar as PrimeField>::Repr) -> usize;
+
+    /// Recommends a wNAF window size given the number of scalars you intend
+    /// to multiply a base by.
+    fn wnaf_window_for_num_scalars(num_scalars: usize) -> usize;
+}
+
+impl<G: CurveProjective> WnafGroup for G {
+    type Scalar = G::Scalar;
+
+    fn group_identity() -> Self {
+        G::zero()
+    }
+
+    fn group_double(&mut self) {
+        self.double();
+    }
+
+    fn group_add(&mut self, other: &Self) {
+        self.add_assign(other);
+    }
+
+    fn group_sub(&mut self, other: &Self) {
+        self.sub_assign(other);
+    }
+
+    fn group_mul(&mut self, by: &Self::Scalar) {
+        self.mul_assign(*by);
+    }
+
+    fn wnaf_window_for_scalar(scalar: <Self::Scalar as PrimeField>::Repr) -> usize {
+        G::recommended_wnaf_for_scalar(scalar)
+    }
+
+    fn wnaf_window_for_num_scalars(num_scalars: usize) -> usize {
+        G::recommended_wnaf_for_num_scalars(num_scalars)
+    }
+}
+
 /// Replaces the contents of `table` with a w-NAF window table for the given window size.
-pub(crate) fn wnaf_table<G: CurveProjective>(table: &mut Vec<G>, mut base: G, window: usize) {
+pub(crate) fn wnaf_table<G: WnafGroup>(table: &mut Vec<G>, mut base: G, window: usize) {
     table.truncate(0);
     table.reserve(1 << (window - 1));
 
     let mut dbl = base;
-    dbl.double();
+    dbl.group_double();
 
     for _ in 0..(1 << (window - 1)) {
         table.push(base);
-        base.add_assign(&dbl);
+        base.group_add(&dbl);
     }
 }
 
@@ -46,23 +117,23 @@ pub(crate) fn wnaf_form<S: PrimeFieldRepr>(wnaf: &mut Vec<i64>, mut c: S, window
 ///
 /// This function must be provided a `table` and `wnaf` that were constructed with
 /// the same window size; otherwise, it may panic or produce invalid results.
-pub(crate) fn wnaf_exp<G: CurveProjective>(table: &[G], wnaf: &[i64]) -> G {
-    let mut result = G::zero();
+pub(crate) fn wnaf_exp<G: WnafGroup>(table: &[G], wnaf: &[i64]) -> G {
+    let mut result = G::group_identity();
 
     let mut found_one = false;
 
     for n in wnaf.iter().rev() {
         if found_one {
-            result.double();
+            result.group_double();
         }
 
         if *n != 0 {
             found_one = true;
 
             if *n > 0 {
-                result.add_assign(&table[(n / 2) as usize]);
+                result.group_add(&table[(n / 2) as usize]);
             } else {
-                result.sub_assign(&table[((-n) / 2) as usize]);
+                result.group_sub(&table[((-n) / 2) as usize]);
             }
         }
     }
@@ -78,7 +149,7 @@ pub struct Wnaf<W, B, S> {
     window_size: W,
 }
 
-impl<G: CurveProjective> Wnaf<(), Vec<G>, Vec<i64>> {
+impl<G: WnafGroup> Wnaf<(), Vec<G>, Vec<i64>> {
     /// Construct a new wNAF context without allocating.
     pub fn new() -> Self {
         Wnaf {
@@ -92,7 +163,7 @@ impl<G: CurveProjective> Wnaf<(), Vec<G>, Vec<i64>> {
     /// can perform exponentiations with `.scalar(..)`.
     pub fn base(&mut self, base: G, num_scalars: usize) -> Wnaf<usize, &[G], &mut Vec<i64>> {
         // Compute the appropriate window size based on the number of scalars.
-        let window_size = G::recommended_wnaf_for_num_scalars(num_scalars);
+        let window_size = G::wnaf_window_for_num_scalars(num_scalars);
 
         // Compute a wNAF table for the provided base and window size.
         wnaf_table(&mut self.base, base, window_size);
@@ -110,10 +181,10 @@ impl<G: CurveProjective> Wnaf<(), Vec<G>, Vec<i64>> {
     /// exponentiations with `.base(..)`.
     pub fn scalar(
         &mut self,
-        scalar: <<G as CurveProjective>::Scalar as PrimeField>::Repr,
+        scalar: <<G as WnafGroup>::Scalar as PrimeField>::Repr,
     ) -> Wnaf<usize, &mut Vec<G>, &[i64]> {
         // Compute the appropriate window size for the scalar.
-        let window_size = G::recommended_wnaf_for_scalar(scalar);
+        let window_size = G::wnaf_window_for_scalar(scalar);
 
         // Compute the wNAF form of the scalar.
         wnaf_form(&mut self.scalar, scalar, window_size);
@@ -128,7 +199,7 @@ impl<G: CurveProjective> Wnaf<(), Vec<G>, Vec<i64>> {
     }
 }
 
-impl<'a, G: CurveProjective> Wnaf<usize, &'a [G], &'a mut Vec<i64>> {
+impl<'a, G: WnafGroup> Wnaf<usize, &'a [G], &'a mut Vec<i64>> {
     /// Constructs new space for the scalar representation while borrowing
     /// the computed window table, for sending the window table across threads.
     pub fn shared(&self) -> Wnaf<usize, &'a [G], Vec<i64>> {
@@ -140,7 +211,7 @@ impl<'a, G: CurveProjective> Wnaf<usize, &'a [G], &'a mut Vec<i64>> {
     }
 }
 
-impl<'a, G: CurveProjective> Wnaf<usize, &'a mut Vec<G>, &'a [i64]> {
+impl<'a, G: WnafGroup> Wnaf<usize, &'a mut Vec<G>, &'a [i64]> {
     /// Constructs new space for the window table while borrowing
     /// the computed scalar representation, for sending the scalar representation
     /// across threads.
@@ -155,7 +226,7 @@ impl<'a, G: CurveProjective> Wnaf<usize, &'a mut Vec<G>, &'a [i64]> {
 
 impl<B, S: AsRef<[i64]>> Wnaf<usize, B, S> {
     /// Performs exponentiation given a base.
-    pub fn base<G: CurveProjective>(&mut self, base: G) -> G
+    pub fn base<G: WnafGroup>(&mut self, base: G) -> G
     where
         B: AsMut<Vec<G>>,
     {
@@ -166,9 +237,9 @@ impl<B, S: AsRef<[i64]>> Wnaf<usize, B, S> {
 
 impl<B, S: AsMut<Vec<i64>>> Wnaf<usize, B, S> {
     /// Performs exponentiation given a scalar.
-    pub fn scalar<G: CurveProjective>(
+    pub fn scalar<G: WnafGroup>(
         &mut self,
-        scalar: <<G as CurveProjective>::Scalar as PrimeField>::Repr,
+        scalar: <<G as WnafGroup>::Scalar as PrimeField>::Repr,
     ) -> G
     where
         B: AsRef<[G]>,
@@ -177,3 +248,142 @@ impl<B, S: AsMut<Vec<i64>>> Wnaf<usize, B, S> {
         wnaf_exp(self.base.as_ref(), self.scalar.as_mut())
     }
 }
+
+/// Returns the `window` bits of `c` starting at bit `offset`, which may run
+/// past its most significant limb.
+fn window_bits(c: &[u64], offset: usize, window: usize) -> u64 {
+    let limb = offset / 64;
+    let shift = offset % 64;
+
+    let mut bits = c.get(limb).map_or(0, |l| l >> shift);
+    if shift + window > 64 && shift != 0 {
+        bits |= c.get(limb + 1).map_or(0, |l| l << (64 - shift));
+    }
+
+    bits & ((1 << window) - 1)
+}
+
+/// A table of precomputed multiples of a fixed base, such as a generator,
+/// with which an exponentiation takes no doublings and at most one addition
+/// per window of the scalar.
+///
+/// The scalar is recoded into signed digits, so each window only needs half
+/// of its multiples. A table with window size `w` holds `2^(w - 1)` elements
+/// for each of the `ceil(256 / w) + 1` windows of a 256-bit scalar.
+#[derive(Clone, Debug)]
+pub struct FixedBaseTable<G> {
+    window: usize,
+    // `tables[i][j]` is the base multiplied by `(j + 1) * 2^(window * i)`.
+    tables: Vec<Vec<G>>,
+}
+
+impl<G: WnafGroup> FixedBaseTable<G> {
+    /// Precomputes the table for a base with the given window size, which
+    /// must be between 1 and 16, inclusive; a window of 16 already takes
+    /// over half a million elements for a 256-bit scalar.
+    pub fn new(mut base: G, window: usize) -> Self {
+        assert!((1..=16).contains(&window), "invalid window size");
+
+        let bits = <G::Scalar as PrimeField>::Repr::default().as_ref().len() * 64;
+        let num_windows = (bits - 1) / window + 2;
+
+        let mut tables = Vec::with_capacity(num_windows);
+        for _ in 0..num_windows {
+            let mut table = Vec::with_capacity(1 << (window - 1));
+            let mut acc = base;
+            for _ in 0..(1 << (window - 1)) {
+                table.push(acc);
+                acc.group_add(&base);
+            }
+
+            // The base of the next window is twice the largest multiple.
+            base = table[table.len() - 1];
+            base.group_double();
+
+            tables.push(table);
+        }
+
+        FixedBaseTable { window, tables }
+    }
+
+    /// Returns the window size of the table.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Performs exponentiation of the base given a scalar.
+    pub fn exp(&self, scalar: <G::Scalar as PrimeField>::Repr) -> G {
+        let half = 1 << (self.window - 1);
+        let mut result = G::group_identity();
+        let mut carry = 0;
+
+        for (i, table) in self.tables.iter().enumerate() {
+            let digit = window_bits(scalar.as_ref(), i * self.window, self.window) + carry;
+
+            // Recode the digit into [-2^(w - 1), 2^(w - 1)], carrying into
+            // the next window.
+            if digit > half {
+                let neg = (1 << self.window) - digit;
+                if neg != 0 {
+                    result.group_sub(&table[(neg - 1) as usize]);
+                }
+                carry = 1;
+            } else {
+                if digit != 0 {
+                    result.group_add(&table[(digit - 1) as usize]);
+                }
+                carry = 0;
+            }
+        }
+
+        result
+    }
+}
+
+/// Performs multi-exponentiation, the sum of every base multiplied by its
+/// scalar (the product of every base raised to its scalar in a group
+/// written multiplicatively), by interleaving their w-NAF forms so that
+/// the doublings are shared between all terms.
+///
+/// Panics if the number of bases and scalars differ.
+pub fn multiexp<G: WnafGroup>(bases: &[G], scalars: &[<G::Scalar as PrimeField>::Repr]) -> G {
+    assert_eq!(bases.len(), scalars.len(), "mismatched number of bases and scalars");
+
+    let terms: Vec<(Vec<G>, Vec<i64>)> = bases
+        .iter()
+        .zip(scalars.iter())
+        .map(|(&base, &scalar)| {
+            let window = G::wnaf_window_for_scalar(scalar);
+            let mut table = vec![];
+            let mut wnaf = vec![];
+            wnaf_table(&mut table, base, window);
+            wnaf_form(&mut wnaf, scalar, window);
+
+            (table, wnaf)
+        })
+        .collect();
+
+    let len = terms.iter().map(|(_, wnaf)| wnaf.len()).max().unwrap_or(0);
+
+    let mut result = G::group_identity();
+
+    let mut found_one = false;
+
+    for i in (0..len).rev() {
+        if found_one {
+            result.group_double();
+        }
+
+        for (table, wnaf) in &terms {
+            match wnaf.get(i) {
+                Some(&n) if n > 0 => result.group_add(&table[(n / 2) as usize]),
+                Some(&n) if n < 0 => result.group_sub(&table[((-n) / 2) as usize]),
+                _ => continue,
+            }
+
+            found_one = true;
+        }
+    }
+
+    result
+}